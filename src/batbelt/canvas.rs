@@ -0,0 +1,489 @@
+use crate::batbelt::metadata::miro_metadata::SignerInfo;
+use crate::batbelt::miro::frame::MiroCodeOverhaulConfig;
+use crate::batbelt::miro::layout::{MiroFrameLayout, MIRO_LAYOUT_MARGIN};
+use crate::batbelt::path::BatFolder;
+use error_stack::{IntoReport, Result, ResultExt};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs};
+
+#[derive(Debug)]
+pub struct CanvasError;
+
+impl fmt::Display for CanvasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Canvas error")
+    }
+}
+
+impl Error for CanvasError {}
+
+pub type CanvasResult<T> = Result<T, CanvasError>;
+
+pub const CANVAS_FOLDER_NAME: &str = "canvas";
+pub const CANVAS_FRAME_NODE_ID: &str = "frame";
+pub const CANVAS_SIGNER_WIDTH: i64 = 374;
+pub const CANVAS_SIGNER_HEIGHT: i64 = 230;
+// used when the screenshot is not a readable png
+pub const CANVAS_DEFAULT_IMAGE_WIDTH: i64 = 1000;
+pub const CANVAS_DEFAULT_IMAGE_HEIGHT: i64 = 600;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonCanvasNodeType {
+    Text,
+    File,
+    Link,
+    Group,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonCanvasNode {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub node_type: JsonCanvasNodeType,
+    pub x: i64,
+    pub y: i64,
+    pub width: i64,
+    pub height: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonCanvasEdge {
+    pub id: String,
+    pub from_node: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_side: Option<String>,
+    pub to_node: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_side: Option<String>,
+}
+
+/// A JSON Canvas (https://jsoncanvas.org) document, it can be opened with Obsidian and other
+/// local editors
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JsonCanvas {
+    #[serde(default)]
+    pub nodes: Vec<JsonCanvasNode>,
+    #[serde(default)]
+    pub edges: Vec<JsonCanvasEdge>,
+}
+
+impl JsonCanvas {
+    /// Reads the canvas in the path, or an empty one if the file doesn't exist
+    pub fn read(file_path: &str) -> CanvasResult<Self> {
+        if !Path::new(file_path).is_file() {
+            return Ok(Self::default());
+        }
+        let canvas_content = fs::read_to_string(file_path)
+            .into_report()
+            .change_context(CanvasError)
+            .attach_printable(format!("Error reading canvas file in {}", file_path))?;
+        serde_json::from_str(&canvas_content)
+            .into_report()
+            .change_context(CanvasError)
+            .attach_printable(format!("Error parsing canvas file in {}", file_path))
+    }
+
+    pub fn save(&self, file_path: &str) -> CanvasResult<()> {
+        let canvas_content = serde_json::to_string_pretty(self)
+            .into_report()
+            .change_context(CanvasError)?;
+        fs::write(file_path, canvas_content)
+            .into_report()
+            .change_context(CanvasError)
+            .attach_printable(format!("Error writing canvas file in {}", file_path))
+    }
+
+    /// Adds the node, replacing the one with the same id
+    pub fn upsert_node(&mut self, node: JsonCanvasNode) {
+        match self.nodes.iter().position(|current| current.id == node.id) {
+            Some(position) => self.nodes[position] = node,
+            None => self.nodes.push(node),
+        }
+    }
+
+    pub fn upsert_edge(&mut self, edge: JsonCanvasEdge) {
+        match self.edges.iter().position(|current| current.id == edge.id) {
+            Some(position) => self.edges[position] = edge,
+            None => self.edges.push(edge),
+        }
+    }
+}
+
+/// The code-overhaul frame of an entry point drawn in a local canvas, with the same layout of
+/// the Miro frame
+pub struct CodeOverhaulCanvas {
+    pub entry_point_name: String,
+    pub canvas: JsonCanvas,
+    pub frame_layout: MiroFrameLayout,
+}
+
+impl CodeOverhaulCanvas {
+    pub fn new_from_entry_point_name(entry_point_name: &str) -> CanvasResult<Self> {
+        let mut co_canvas = Self {
+            entry_point_name: entry_point_name.to_string(),
+            canvas: JsonCanvas::default(),
+            frame_layout: MiroFrameLayout::get_layout().change_context(CanvasError)?,
+        };
+        co_canvas.canvas = JsonCanvas::read(&co_canvas.get_canvas_file_path()?)?;
+        Ok(co_canvas)
+    }
+
+    pub fn get_canvas_folder_path() -> CanvasResult<String> {
+        let auditor_figures_path = BatFolder::AuditorFigures
+            .get_path(false)
+            .change_context(CanvasError)?;
+        Ok(format!("{}/{}", auditor_figures_path, CANVAS_FOLDER_NAME))
+    }
+
+    pub fn get_canvas_file_path(&self) -> CanvasResult<String> {
+        Ok(format!(
+            "{}/{}.canvas",
+            Self::get_canvas_folder_path()?,
+            self.entry_point_name
+        ))
+    }
+
+    pub fn get_images_folder_path(&self) -> CanvasResult<String> {
+        Ok(format!(
+            "{}/{}",
+            Self::get_canvas_folder_path()?,
+            self.entry_point_name
+        ))
+    }
+
+    pub fn get_frame_title(&self) -> String {
+        format!("co: {}", self.entry_point_name)
+    }
+
+    pub fn get_screenshot_node_id(co_config: &MiroCodeOverhaulConfig) -> &'static str {
+        match co_config {
+            MiroCodeOverhaulConfig::EntryPoint => "entry_point",
+            MiroCodeOverhaulConfig::ContextAccount => "context_accounts",
+            MiroCodeOverhaulConfig::Validations => "validations",
+            MiroCodeOverhaulConfig::Handler => "handler",
+            MiroCodeOverhaulConfig::Signers => "signers",
+            MiroCodeOverhaulConfig::StateChanges => "state_changes",
+            MiroCodeOverhaulConfig::HandlerParameters => "handler_parameters",
+        }
+    }
+
+    pub fn add_frame(&mut self) {
+        let frame_node = JsonCanvasNode {
+            id: CANVAS_FRAME_NODE_ID.to_string(),
+            node_type: JsonCanvasNodeType::Group,
+            x: 0,
+            y: 0,
+            width: self.frame_layout.frame_width as i64,
+            height: self.frame_layout.frame_height as i64,
+            text: None,
+            file: None,
+            label: Some(self.get_frame_title()),
+            color: None,
+        };
+        self.canvas.upsert_node(frame_node);
+    }
+
+    /// Node id of a screenshot of the state changes or handler parameters, which can be many
+    pub fn get_group_screenshot_node_id(
+        co_config: &MiroCodeOverhaulConfig,
+        index: usize,
+    ) -> String {
+        format!("{}_{}", Self::get_screenshot_node_id(co_config), index)
+    }
+
+    fn is_screenshot_node_id(node_id: &str) -> bool {
+        let is_single_screenshot = [
+            MiroCodeOverhaulConfig::EntryPoint,
+            MiroCodeOverhaulConfig::ContextAccount,
+            MiroCodeOverhaulConfig::Validations,
+            MiroCodeOverhaulConfig::Handler,
+        ]
+        .iter()
+        .any(|co_config| Self::get_screenshot_node_id(co_config) == node_id);
+        let is_group_screenshot = [
+            MiroCodeOverhaulConfig::StateChanges,
+            MiroCodeOverhaulConfig::HandlerParameters,
+        ]
+        .iter()
+        .any(|co_config| {
+            node_id
+                .strip_prefix(Self::get_screenshot_node_id(co_config))
+                .and_then(|suffix| suffix.strip_prefix('_'))
+                .is_some_and(|index| index.parse::<usize>().is_ok())
+        });
+        is_single_screenshot || is_group_screenshot
+    }
+
+    /// Moves the screenshot next to the canvas and centers it in the position used for Miro
+    pub fn add_screenshot(
+        &mut self,
+        screenshot_path: &str,
+        co_config: MiroCodeOverhaulConfig,
+    ) -> CanvasResult<()> {
+        let node_id = Self::get_screenshot_node_id(&co_config).to_string();
+        self.upsert_screenshot_node(&node_id, screenshot_path, co_config, None)
+    }
+
+    /// Same as add_screenshot, the screenshots after the first one of the group are placed below
+    /// the previous one instead of on top of it
+    pub fn add_group_screenshot(
+        &mut self,
+        screenshot_path: &str,
+        co_config: MiroCodeOverhaulConfig,
+        index: usize,
+    ) -> CanvasResult<()> {
+        let node_id = Self::get_group_screenshot_node_id(&co_config, index);
+        let previous_node_id = index
+            .checked_sub(1)
+            .map(|previous_index| Self::get_group_screenshot_node_id(&co_config, previous_index));
+        self.upsert_screenshot_node(
+            &node_id,
+            screenshot_path,
+            co_config,
+            previous_node_id.as_deref(),
+        )
+    }
+
+    fn upsert_screenshot_node(
+        &mut self,
+        node_id: &str,
+        screenshot_path: &str,
+        co_config: MiroCodeOverhaulConfig,
+        previous_node_id: Option<&str>,
+    ) -> CanvasResult<()> {
+        let images_folder_path = self.get_images_folder_path()?;
+        fs::create_dir_all(&images_folder_path)
+            .into_report()
+            .change_context(CanvasError)?;
+        let image_path = format!("{}/{}.png", images_folder_path, node_id);
+        fs::rename(screenshot_path, &image_path)
+            .into_report()
+            .change_context(CanvasError)
+            .attach_printable(format!(
+                "Error moving {} to {}",
+                screenshot_path, image_path
+            ))?;
+        let (mut width, mut height) = get_png_dimensions(&image_path)
+            .unwrap_or((CANVAS_DEFAULT_IMAGE_WIDTH, CANVAS_DEFAULT_IMAGE_HEIGHT));
+        if let Some(slot_width) = self.frame_layout.get_width(&co_config) {
+            height = height * slot_width as i64 / width;
+            width = slot_width as i64;
+        }
+        let (x_position, y_position) = self.frame_layout.get_position(&co_config);
+        let previous_node = previous_node_id.and_then(|previous_node_id| {
+            self.canvas
+                .nodes
+                .iter()
+                .find(|node| node.id == previous_node_id)
+        });
+        let y = match previous_node {
+            Some(previous_node) => previous_node.y + previous_node.height + MIRO_LAYOUT_MARGIN,
+            None => y_position - height / 2,
+        };
+        self.canvas.upsert_node(JsonCanvasNode {
+            id: node_id.to_string(),
+            node_type: JsonCanvasNodeType::File,
+            x: x_position - width / 2,
+            y,
+            width,
+            height,
+            text: None,
+            file: Some(image_path),
+            label: None,
+            color: None,
+        });
+        Ok(())
+    }
+
+    /// Signer sticky note, connected to the entry point screenshot
+    pub fn add_signer(&mut self, signer_index: usize, signer_info: &SignerInfo) {
+        let node_id = format!("signer_{}", signer_index);
+        let ((x_position, y_position), _) = self.frame_layout.get_signer_positions(signer_index);
+        self.canvas.upsert_node(JsonCanvasNode {
+            id: node_id.clone(),
+            node_type: JsonCanvasNodeType::Text,
+            x: x_position - CANVAS_SIGNER_WIDTH / 2,
+            y: y_position - CANVAS_SIGNER_HEIGHT / 2,
+            width: CANVAS_SIGNER_WIDTH,
+            height: CANVAS_SIGNER_HEIGHT,
+            text: Some(
+                signer_info
+                    .signer_text
+                    .replace("<br>", "\n")
+                    .replace("<strong>", "**")
+                    .replace("</strong>", "**"),
+            ),
+            file: None,
+            label: None,
+            color: Some(
+                signer_info
+                    .signer_type
+                    .get_sticky_note_color()
+                    .to_hex()
+                    .to_string(),
+            ),
+        });
+        self.connect(
+            &node_id,
+            Self::get_screenshot_node_id(&MiroCodeOverhaulConfig::EntryPoint),
+            Some(("right", "left")),
+        );
+    }
+
+    /// Repositions the screenshots with the auto-layout of the frame layout, in the order they
+    /// were added
+    pub fn pack_screenshots(&mut self) {
+        let mut screenshot_nodes = self
+            .canvas
+            .nodes
+            .iter_mut()
+            .filter(|node| Self::is_screenshot_node_id(&node.id))
+            .collect::<Vec<_>>();
+        let item_sizes = screenshot_nodes
+            .iter()
+            .map(|node| (node.width as u64, node.height as u64))
+            .collect::<Vec<_>>();
+        let positions = self.frame_layout.pack_items(&item_sizes);
+        for (node, (x_position, y_position)) in screenshot_nodes.iter_mut().zip(positions) {
+            node.x = x_position - node.width / 2;
+            node.y = y_position - node.height / 2;
+        }
+    }
+
+    pub fn connect(&mut self, from_node: &str, to_node: &str, sides: Option<(&str, &str)>) {
+        self.canvas.upsert_edge(JsonCanvasEdge {
+            id: format!("{}-{}", from_node, to_node),
+            from_node: from_node.to_string(),
+            from_side: sides.map(|(from_side, _)| from_side.to_string()),
+            to_node: to_node.to_string(),
+            to_side: sides.map(|(_, to_side)| to_side.to_string()),
+        });
+    }
+
+    /// Edges pointing to a missing node are dropped, so the canvas stays valid
+    pub fn save(&mut self) -> CanvasResult<String> {
+        let node_ids = self
+            .canvas
+            .nodes
+            .iter()
+            .map(|node| node.id.clone())
+            .collect::<Vec<_>>();
+        self.canvas
+            .edges
+            .retain(|edge| node_ids.contains(&edge.from_node) && node_ids.contains(&edge.to_node));
+        fs::create_dir_all(Self::get_canvas_folder_path()?)
+            .into_report()
+            .change_context(CanvasError)?;
+        let canvas_file_path = self.get_canvas_file_path()?;
+        self.canvas.save(&canvas_file_path)?;
+        Ok(canvas_file_path)
+    }
+}
+
+/// Width and height from the IHDR chunk of a png file
+pub fn get_png_dimensions(png_path: &str) -> Option<(i64, i64)> {
+    let png_content = fs::read(png_path).ok()?;
+    if png_content.len() < 24 || &png_content[1..4] != b"PNG" || &png_content[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(png_content[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(png_content[20..24].try_into().ok()?);
+    Some((width as i64, height as i64))
+}
+
+#[test]
+fn test_json_canvas_serialization() {
+    let mut canvas = JsonCanvas::default();
+    canvas.upsert_node(JsonCanvasNode {
+        id: "frame".to_string(),
+        node_type: JsonCanvasNodeType::Group,
+        x: 0,
+        y: 0,
+        width: 100,
+        height: 50,
+        text: None,
+        file: None,
+        label: Some("co: deposit".to_string()),
+        color: None,
+    });
+    canvas.upsert_edge(JsonCanvasEdge {
+        id: "signer_0-entry_point".to_string(),
+        from_node: "signer_0".to_string(),
+        from_side: Some("right".to_string()),
+        to_node: "entry_point".to_string(),
+        to_side: None,
+    });
+    let canvas_json = serde_json::to_value(&canvas).unwrap();
+    assert_eq!(canvas_json["nodes"][0]["type"], "group");
+    assert!(canvas_json["nodes"][0].get("file").is_none());
+    assert_eq!(canvas_json["edges"][0]["fromNode"], "signer_0");
+    assert_eq!(canvas_json["edges"][0]["fromSide"], "right");
+
+    // same id replaces the node
+    let mut frame_node = canvas.nodes[0].clone();
+    frame_node.width = 200;
+    canvas.upsert_node(frame_node);
+    assert_eq!(canvas.nodes.len(), 1);
+    assert_eq!(canvas.nodes[0].width, 200);
+}
+
+#[test]
+fn test_pack_screenshots_with_groups() {
+    let new_node = |id: &str, width: i64, height: i64| JsonCanvasNode {
+        id: id.to_string(),
+        node_type: JsonCanvasNodeType::File,
+        x: 0,
+        y: 0,
+        width,
+        height,
+        text: None,
+        file: None,
+        label: None,
+        color: None,
+    };
+    let mut co_canvas = CodeOverhaulCanvas {
+        entry_point_name: "deposit".to_string(),
+        canvas: JsonCanvas::default(),
+        frame_layout: MiroFrameLayout::default(),
+    };
+    co_canvas.canvas.nodes = vec![
+        new_node("entry_point", 1000, 600),
+        new_node("signer_0", 374, 230),
+        new_node("handler", 1000, 400),
+        new_node("state_changes_0", 800, 300),
+        new_node("handler_parameters_0", 600, 200),
+    ];
+    co_canvas.pack_screenshots();
+    let positions =
+        co_canvas
+            .frame_layout
+            .pack_items(&[(1000, 600), (1000, 400), (800, 300), (600, 200)]);
+    let screenshot_nodes = co_canvas
+        .canvas
+        .nodes
+        .iter()
+        .filter(|node| node.id != "signer_0");
+    for (node, (x_position, y_position)) in screenshot_nodes.zip(positions) {
+        assert_eq!(
+            (node.x, node.y),
+            (x_position - node.width / 2, y_position - node.height / 2)
+        );
+    }
+    // the signers are placed by the layout, not packed
+    assert_eq!(
+        (co_canvas.canvas.nodes[1].x, co_canvas.canvas.nodes[1].y),
+        (0, 0)
+    );
+}
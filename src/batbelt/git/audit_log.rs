@@ -0,0 +1,196 @@
+use crate::batbelt::git::commit_message::{BatCommitMessage, BatCommitType};
+use crate::batbelt::git::repository::{BatRepository, BatRepositoryCommit};
+use crate::batbelt::git::{GitError, GitResult};
+use error_stack::{IntoReport, ResultExt};
+use serde::Serialize;
+
+const BAT_LOG_CSV_HEADER: &str =
+    "date,timestamp,author,email,commit_type,entity,action,metadata_id,commit_hash";
+
+/// A change of state of the audit, like a code-overhaul file started or a finding accepted
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatLogEvent {
+    pub date: String,
+    pub timestamp: i64,
+    pub author: String,
+    pub email: String,
+    pub commit_type: BatCommitType,
+    pub entity: String,
+    pub action: String,
+    pub metadata_id: String,
+    pub commit_hash: String,
+}
+
+/// Audit timeline built from the commits following the BatCommitMessage convention
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatAuditLog {
+    pub events: Vec<BatLogEvent>,
+}
+
+impl BatAuditLog {
+    /// Reads the commits of every local and remote-tracking branch, so the work of all the
+    /// auditors is included
+    pub fn new_from_repository() -> GitResult<Self> {
+        let commits = BatRepository::open()
+            .and_then(|bat_repository| bat_repository.get_all_branches_commits())
+            .change_context(GitError)?;
+        Ok(Self::new_from_commits(&commits))
+    }
+
+    pub fn new_from_commits(commits: &[BatRepositoryCommit]) -> Self {
+        let mut events = vec![];
+        for commit in commits {
+            let commit_message = match BatCommitMessage::new_from_message(&commit.message) {
+                Some(commit_message) => commit_message,
+                None => continue,
+            };
+            let new_event = |entity: &str| BatLogEvent {
+                date: get_utc_date(commit.timestamp),
+                timestamp: commit.timestamp,
+                author: commit.author_name.clone(),
+                email: commit.author_email.clone(),
+                commit_type: commit_message.commit_type,
+                entity: entity.to_string(),
+                action: commit_message.action.clone(),
+                metadata_id: commit_message.metadata_id.clone().unwrap_or_default(),
+                commit_hash: commit.commit_hash.clone(),
+            };
+            // one event per finding when they changed state together
+            if commit_message.finding_names.is_empty() {
+                events.push(new_event(&commit_message.entity));
+            } else {
+                events.extend(
+                    commit_message
+                        .finding_names
+                        .iter()
+                        .map(|name| new_event(name)),
+                );
+            }
+        }
+        events.sort_by_key(|event| event.timestamp);
+        Self { events }
+    }
+
+    pub fn get_csv(&self) -> String {
+        let escape = |value: &str| {
+            if value.contains([',', '"', '\n']) {
+                format!("\"{}\"", value.replace('"', "\"\""))
+            } else {
+                value.to_string()
+            }
+        };
+        let mut lines = vec![BAT_LOG_CSV_HEADER.to_string()];
+        lines.extend(self.events.iter().map(|event| {
+            [
+                event.date.clone(),
+                event.timestamp.to_string(),
+                event.author.clone(),
+                event.email.clone(),
+                event.commit_type.to_string(),
+                event.entity.clone(),
+                event.action.clone(),
+                event.metadata_id.clone(),
+                event.commit_hash.clone(),
+            ]
+            .iter()
+            .map(|value| escape(value))
+            .collect::<Vec<_>>()
+            .join(",")
+        }));
+        lines.join("\n")
+    }
+
+    pub fn get_json(&self) -> GitResult<String> {
+        serde_json::to_string_pretty(&self.events)
+            .into_report()
+            .change_context(GitError)
+    }
+}
+
+/// ISO 8601 date in UTC, from the days since the epoch to the civil calendar
+fn get_utc_date(timestamp: i64) -> String {
+    let (days, seconds) = (timestamp.div_euclid(86400), timestamp.rem_euclid(86400));
+    let shifted_days = days + 719468;
+    let era = shifted_days.div_euclid(146097);
+    let day_of_era = shifted_days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+#[test]
+fn test_audit_log_from_commits() {
+    let new_commit = |timestamp: i64, author_name: &str, message: &str| BatRepositoryCommit {
+        commit_hash: format!("{:040}", timestamp),
+        author_name: author_name.to_string(),
+        author_email: format!("{}@bat.cli", author_name),
+        timestamp,
+        message: message.to_string(),
+    };
+    let audit_log = BatAuditLog::new_from_commits(&[
+        new_commit(
+            1700000300,
+            "bob",
+            "finding(to-review): accepted\n\nfinding: overflow\nfinding: missing_signer",
+        ),
+        new_commit(
+            1700000000,
+            "alice",
+            "co(deposit): started\n\nmetadata-id: 4f8a2c",
+        ),
+        new_commit(1700000100, "alice", "initial commit"),
+        new_commit(
+            1700000200,
+            "alice",
+            "co(deposit): finished\n\nmetadata-id: 4f8a2c",
+        ),
+    ]);
+    let event_summaries = audit_log
+        .events
+        .iter()
+        .map(|event| {
+            format!(
+                "{} {} {} {}",
+                event.author, event.commit_type, event.entity, event.action
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        event_summaries,
+        vec![
+            "alice co deposit started",
+            "alice co deposit finished",
+            "bob finding overflow accepted",
+            "bob finding missing_signer accepted",
+        ]
+    );
+    assert_eq!(audit_log.events[0].date, "2023-11-14T22:13:20Z");
+    assert_eq!(get_utc_date(0), "1970-01-01T00:00:00Z");
+    let csv_lines = audit_log.get_csv();
+    let mut csv_lines = csv_lines.lines();
+    assert_eq!(csv_lines.next(), Some(BAT_LOG_CSV_HEADER));
+    assert_eq!(
+        csv_lines.next().unwrap(),
+        format!(
+            "2023-11-14T22:13:20Z,1700000000,alice,alice@bat.cli,co,deposit,started,4f8a2c,{:040}",
+            1700000000
+        )
+    );
+}
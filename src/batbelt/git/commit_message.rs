@@ -0,0 +1,121 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+pub const BAT_COMMIT_METADATA_ID_TRAILER: &str = "metadata-id";
+pub const BAT_COMMIT_FINDING_TRAILER: &str = "finding";
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    strum_macros::Display,
+    strum_macros::EnumString,
+    strum_macros::EnumIter,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum BatCommitType {
+    Repo,
+    Auditor,
+    Co,
+    Finding,
+    Notes,
+    Metadata,
+    Candidate,
+}
+
+/// Commit message with the `<type>(<entity>): <action>` subject, the metadata id and the
+/// findings moved together go as trailers of the body
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatCommitMessage {
+    pub commit_type: BatCommitType,
+    pub entity: String,
+    pub action: String,
+    pub metadata_id: Option<String>,
+    pub finding_names: Vec<String>,
+}
+
+impl BatCommitMessage {
+    pub fn new(commit_type: BatCommitType, entity: &str, action: &str) -> Self {
+        Self {
+            commit_type,
+            entity: entity.to_string(),
+            action: action.to_string(),
+            metadata_id: None,
+            finding_names: vec![],
+        }
+    }
+
+    pub fn get_message(&self) -> String {
+        let mut message = format!("{}({}): {}", self.commit_type, self.entity, self.action);
+        let mut trailers = vec![];
+        if let Some(metadata_id) = &self.metadata_id {
+            trailers.push(format!(
+                "{}: {}",
+                BAT_COMMIT_METADATA_ID_TRAILER, metadata_id
+            ));
+        }
+        for finding_name in &self.finding_names {
+            trailers.push(format!("{}: {}", BAT_COMMIT_FINDING_TRAILER, finding_name));
+        }
+        if !trailers.is_empty() {
+            message = format!("{}\n\n{}", message, trailers.join("\n"));
+        }
+        message
+    }
+
+    /// None for messages not following the convention, like the ones of older bat-cli versions
+    pub fn new_from_message(message: &str) -> Option<Self> {
+        let subject_regex = Regex::new(r#"^(\w+)\(([^)]+)\): (.+)$"#).unwrap();
+        let mut lines = message.lines();
+        let captures = subject_regex.captures(lines.next()?.trim())?;
+        let mut commit_message = Self::new(
+            BatCommitType::from_str(&captures[1]).ok()?,
+            &captures[2],
+            &captures[3],
+        );
+        for line in lines {
+            match line.trim().split_once(": ") {
+                Some((BAT_COMMIT_METADATA_ID_TRAILER, metadata_id)) => {
+                    commit_message.metadata_id = Some(metadata_id.trim().to_string())
+                }
+                Some((BAT_COMMIT_FINDING_TRAILER, finding_name)) => commit_message
+                    .finding_names
+                    .push(finding_name.trim().to_string()),
+                _ => {}
+            }
+        }
+        Some(commit_message)
+    }
+}
+
+#[test]
+fn test_commit_message_round_trip() {
+    let mut commit_message = BatCommitMessage::new(BatCommitType::Co, "deposit", "started");
+    commit_message.metadata_id = Some("4f8a2c".to_string());
+    assert_eq!(
+        commit_message.get_message(),
+        "co(deposit): started\n\nmetadata-id: 4f8a2c"
+    );
+    assert_eq!(
+        BatCommitMessage::new_from_message(&commit_message.get_message()),
+        Some(commit_message)
+    );
+
+    let mut accept_message = BatCommitMessage::new(BatCommitType::Finding, "to-review", "accepted");
+    accept_message.finding_names = vec!["missing_signer".to_string(), "overflow".to_string()];
+    assert_eq!(
+        BatCommitMessage::new_from_message(&accept_message.get_message())
+            .unwrap()
+            .finding_names,
+        accept_message.finding_names
+    );
+    assert_eq!(
+        BatCommitMessage::new_from_message("co: deposit started"),
+        None
+    );
+}
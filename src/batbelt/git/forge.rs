@@ -0,0 +1,211 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Hosting service of a repository, decides the format of the permalinks
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    strum_macros::Display,
+    strum_macros::EnumIter,
+)]
+pub enum GitForge {
+    GitHub,
+    GitLab,
+    Bitbucket,
+    Gitea,
+    /// repositories without a web interface, linked with file:// urls
+    Local,
+}
+
+impl GitForge {
+    /// None for hosts that don't tell the forge, like most self-hosted instances
+    pub fn new_from_host(host: &str) -> Option<Self> {
+        let host = host.to_lowercase();
+        if host.contains("github") {
+            Some(GitForge::GitHub)
+        } else if host.contains("gitlab") {
+            Some(GitForge::GitLab)
+        } else if host.contains("bitbucket") {
+            Some(GitForge::Bitbucket)
+        } else if host.contains("gitea") || host.contains("codeberg") {
+            Some(GitForge::Gitea)
+        } else {
+            None
+        }
+    }
+
+    fn get_file_path_prefix(&self) -> &'static str {
+        match self {
+            GitForge::GitHub => "blob",
+            GitForge::GitLab => "-/blob",
+            GitForge::Bitbucket => "src",
+            GitForge::Gitea => "src/commit",
+            GitForge::Local => "",
+        }
+    }
+
+    fn get_line_anchor(&self, start_line_index: usize, end_line_index: Option<usize>) -> String {
+        let end_line_index = end_line_index.filter(|end_line| *end_line > start_line_index);
+        match (self, end_line_index) {
+            (GitForge::Bitbucket, None) => format!("#lines-{}", start_line_index),
+            (GitForge::Bitbucket, Some(end_line)) => {
+                format!("#lines-{}:{}", start_line_index, end_line)
+            }
+            (GitForge::GitLab, Some(end_line)) => format!("#L{}-{}", start_line_index, end_line),
+            (_, None) => format!("#L{}", start_line_index),
+            (_, Some(end_line)) => format!("#L{}-L{}", start_line_index, end_line),
+        }
+    }
+}
+
+/// Web url of a repository and the forge serving it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForgeRepository {
+    pub forge: GitForge,
+    pub web_url: String,
+}
+
+impl ForgeRepository {
+    pub fn new_local(repository_path: &str) -> Self {
+        Self {
+            forge: GitForge::Local,
+            web_url: format!("file://{}", repository_path.trim_end_matches('/')),
+        }
+    }
+
+    /// From an https or ssh remote url. forge takes precedence over the detection by host,
+    /// so self-hosted instances work. None for local remotes and unknown hosts
+    pub fn new_from_remote_url(remote_url: &str, forge: Option<GitForge>) -> Option<Self> {
+        let remote_url_regex =
+            Regex::new(r#"^(?:[\w+]+://)?(?:[^@/]+@)?([^/:]+)(?::\d+)?[:/](.+?)(?:\.git)?/?$"#)
+                .unwrap();
+        let remote_url = remote_url.trim();
+        if remote_url.starts_with("file://") || remote_url.starts_with('/') {
+            return None;
+        }
+        let captures = remote_url_regex.captures(remote_url)?;
+        let host = captures.get(1)?.as_str();
+        let path = captures.get(2)?.as_str().trim_start_matches('/');
+        let forge = forge
+            .filter(|forge| *forge != GitForge::Local)
+            .or_else(|| GitForge::new_from_host(host))?;
+        Some(Self {
+            forge,
+            web_url: format!("https://{}/{}", host, path),
+        })
+    }
+
+    /// From a commit url, like the commit_hash_url of the Bat.toml. Returns the repository
+    /// and the commit hash
+    pub fn new_from_commit_url(
+        commit_url: &str,
+        forge: Option<GitForge>,
+    ) -> Option<(Self, String)> {
+        let commit_url_regex =
+            Regex::new(r#"^(https?://([^/]+)/.+?)(/-)?/(commit|commits)/([0-9a-fA-F]{40})"#)
+                .unwrap();
+        let captures = commit_url_regex.captures(commit_url.trim())?;
+        let detected_forge = match (
+            GitForge::new_from_host(&captures[2]),
+            captures.get(3),
+            &captures[4],
+        ) {
+            (Some(forge), _, _) => Some(forge),
+            // only GitLab uses /-/ and only Bitbucket uses /commits/
+            (None, Some(_), _) => Some(GitForge::GitLab),
+            (None, None, "commits") => Some(GitForge::Bitbucket),
+            (None, None, _) => None,
+        };
+        let forge = forge
+            .filter(|forge| *forge != GitForge::Local)
+            .or(detected_forge)?;
+        Some((
+            Self {
+                forge,
+                web_url: captures[1].to_string(),
+            },
+            captures[5].to_string(),
+        ))
+    }
+
+    /// file_path is relative to the root of the repository
+    pub fn get_permalink(
+        &self,
+        commit_hash: &str,
+        file_path: &str,
+        start_line_index: usize,
+        end_line_index: Option<usize>,
+    ) -> String {
+        let file_path = file_path.trim_start_matches("./").trim_start_matches('/');
+        let line_anchor = self.forge.get_line_anchor(start_line_index, end_line_index);
+        match self.forge {
+            GitForge::Local => format!("{}/{}{}", self.web_url, file_path, line_anchor),
+            _ => format!(
+                "{}/{}/{}/{}{}",
+                self.web_url,
+                self.forge.get_file_path_prefix(),
+                commit_hash,
+                file_path,
+                line_anchor
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_forge_permalinks() {
+    let commit_hash = "641bdb72210edcafe555102f2ecd2952a7b60722";
+    let file_path = "programs/vault/src/lib.rs";
+
+    let github =
+        ForgeRepository::new_from_remote_url("git@github.com:org/vault.git", None).unwrap();
+    assert_eq!(
+        github.get_permalink(commit_hash, file_path, 10, Some(12)),
+        format!("https://github.com/org/vault/blob/{commit_hash}/{file_path}#L10-L12")
+    );
+
+    // self-hosted GitLab with subgroups, detected from the /-/ of the commit url
+    let (gitlab, gitlab_commit_hash) = ForgeRepository::new_from_commit_url(
+        &format!("https://code.client.io/group/sub/vault/-/commit/{commit_hash}"),
+        None,
+    )
+    .unwrap();
+    assert_eq!(gitlab_commit_hash, commit_hash);
+    assert_eq!(
+        gitlab.get_permalink(commit_hash, file_path, 10, Some(12)),
+        format!("https://code.client.io/group/sub/vault/-/blob/{commit_hash}/{file_path}#L10-12")
+    );
+
+    let bitbucket =
+        ForgeRepository::new_from_remote_url("https://user@bitbucket.org/org/vault.git", None)
+            .unwrap();
+    assert_eq!(
+        bitbucket.get_permalink(commit_hash, file_path, 10, Some(12)),
+        format!("https://bitbucket.org/org/vault/src/{commit_hash}/{file_path}#lines-10:12")
+    );
+
+    // unknown host, the forge comes from the Bat.toml
+    assert_eq!(
+        ForgeRepository::new_from_remote_url("ssh://git@git.client.io:2222/org/vault.git", None),
+        None
+    );
+    let gitea = ForgeRepository::new_from_remote_url(
+        "ssh://git@git.client.io:2222/org/vault.git",
+        Some(GitForge::Gitea),
+    )
+    .unwrap();
+    assert_eq!(
+        gitea.get_permalink(commit_hash, file_path, 10, None),
+        format!("https://git.client.io/org/vault/src/commit/{commit_hash}/{file_path}#L10")
+    );
+
+    let local = ForgeRepository::new_local("/home/auditor/vault/");
+    assert_eq!(
+        local.get_permalink(commit_hash, file_path, 10, Some(10)),
+        format!("file:///home/auditor/vault/{file_path}#L10")
+    );
+}
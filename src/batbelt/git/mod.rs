@@ -0,0 +1,832 @@
+pub mod audit_log;
+pub mod commit_message;
+pub mod forge;
+pub mod repository;
+
+use std::error::Error;
+use std::fmt;
+
+use std::cell::RefCell;
+
+use colored::Colorize;
+use std::path::Path;
+use std::rc::Rc;
+
+use super::path::BatFolder;
+
+use crate::batbelt::git::commit_message::{BatCommitMessage, BatCommitType};
+use crate::batbelt::git::forge::{ForgeRepository, GitForge};
+use crate::batbelt::git::repository::BatRepository;
+use crate::batbelt::metadata::metadata_merge::BAT_METADATA_MERGE_DRIVER_NAME;
+use crate::batbelt::metadata::{BatMetadata, BatMetadataCommit};
+use crate::config::BatAuditorConfig;
+use crate::{batbelt::path::BatFile, config::BatConfig, Suggestion};
+use error_stack::{IntoReport, Report, Result, ResultExt};
+
+#[derive(Debug)]
+pub struct GitError;
+
+impl fmt::Display for GitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Git operation error")
+    }
+}
+
+impl Error for GitError {}
+
+type GitResult<T> = Result<T, GitError>;
+
+pub const BAT_MAIN_BRANCH_NAME: &str = "main";
+pub const BAT_DEVELOP_BRANCH_NAME: &str = "develop";
+
+#[derive(Debug, PartialEq, strum_macros::Display)]
+pub enum GitAction {
+    CreateBranch {
+        branch_name: String,
+    },
+    CheckoutAuditorBranch,
+    Init,
+    RemoteAddProjectRepo,
+    AddAll,
+    CheckGitIsInitialized {
+        is_initialized: Rc<RefCell<bool>>,
+    },
+    CheckBranchDontExist {
+        branch_name: String,
+    },
+    CheckCorrectBranch,
+    /// Registers the BatMetadata.json merge driver in the git config and .gitattributes
+    RegisterMetadataMergeDriver,
+    GetRepositoryPermalink {
+        file_path: String,
+        start_line_index: usize,
+        /// links a range of lines when bigger than start_line_index
+        end_line_index: Option<usize>,
+        permalink: Rc<RefCell<String>>,
+    },
+}
+
+impl GitAction {
+    pub fn execute_action(&self) -> GitResult<()> {
+        let bat_config = BatConfig::get_config().change_context(GitError)?;
+        match self {
+            GitAction::Init => {
+                BatRepository::init(".", BAT_MAIN_BRANCH_NAME).change_context(GitError)?;
+            }
+            GitAction::RemoteAddProjectRepo => {
+                BatRepository::open()
+                    .and_then(|bat_repository| {
+                        bat_repository.add_remote("origin", &bat_config.project_repository_url)
+                    })
+                    .change_context(GitError)?;
+            }
+            GitAction::CreateBranch { branch_name } => {
+                BatRepository::open()
+                    .and_then(|bat_repository| {
+                        bat_repository.create_and_checkout_branch(branch_name)
+                    })
+                    .change_context(GitError)?;
+            }
+            GitAction::AddAll => {
+                BatRepository::open()
+                    .and_then(|bat_repository| bat_repository.add_all())
+                    .change_context(GitError)?;
+            }
+            GitAction::CheckGitIsInitialized { is_initialized } => {
+                // discovered from the parent folders, bare repositories have no work tree
+                let is_initialized_result = BatRepository::open()
+                    .map(|bat_repository| bat_repository.get_workdir().is_ok())
+                    .unwrap_or(false);
+
+                log::debug!(
+                    "is_initialized {} {}",
+                    self.to_string(),
+                    is_initialized_result
+                );
+                *is_initialized.borrow_mut() = is_initialized_result;
+            }
+            GitAction::CheckoutAuditorBranch => {
+                let auditor_branch_name = get_auditor_branch_name()?;
+                if get_current_branch_name()? != auditor_branch_name {
+                    self.checkout_branch(&auditor_branch_name)?
+                }
+                return Ok(());
+            }
+            GitAction::GetRepositoryPermalink {
+                file_path,
+                start_line_index,
+                end_line_index,
+                permalink,
+            } => {
+                let permalink_result = get_repository_permalink(
+                    file_path,
+                    *start_line_index,
+                    *end_line_index,
+                    &bat_config.commit_hash_url,
+                    bat_config.git_forge,
+                )?;
+                *permalink.borrow_mut() = permalink_result;
+                return Ok(());
+            }
+            GitAction::CheckCorrectBranch => self.check_correct_branch()?,
+            GitAction::CheckBranchDontExist { branch_name: _ } => {}
+            GitAction::RegisterMetadataMergeDriver => {
+                let driver_config = format!("merge.{}", BAT_METADATA_MERGE_DRIVER_NAME);
+                let bat_repository = BatRepository::open().change_context(GitError)?;
+                bat_repository
+                    .set_config_value(
+                        &format!("{}.name", driver_config),
+                        "BatMetadata.json three-way merge",
+                    )
+                    .change_context(GitError)?;
+                bat_repository
+                    .set_config_value(
+                        &format!("{}.driver", driver_config),
+                        "bat-cli repository merge-metadata %O %A %B",
+                    )
+                    .change_context(GitError)?;
+                let git_attributes_file = BatFile::GitAttributes;
+                let metadata_file_name = BatFile::BatMetadataFile
+                    .get_path(false)
+                    .change_context(GitError)?
+                    .trim_start_matches("./")
+                    .to_string();
+                let attribute_line = format!(
+                    "{} merge={}",
+                    metadata_file_name, BAT_METADATA_MERGE_DRIVER_NAME
+                );
+                let mut git_attributes_content =
+                    if git_attributes_file.file_exists().change_context(GitError)? {
+                        git_attributes_file
+                            .read_content(false)
+                            .change_context(GitError)?
+                    } else {
+                        "".to_string()
+                    };
+                if !git_attributes_content
+                    .lines()
+                    .any(|line| line.trim() == attribute_line)
+                {
+                    if !git_attributes_content.is_empty() && !git_attributes_content.ends_with('\n')
+                    {
+                        git_attributes_content.push('\n');
+                    }
+                    git_attributes_content.push_str(&format!("{}\n", attribute_line));
+                    git_attributes_file
+                        .write_content(false, &git_attributes_content)
+                        .change_context(GitError)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_correct_branch(&self) -> GitResult<()> {
+        let expected_auditor_branch = get_auditor_branch_name()?;
+        let current_branch = get_current_branch_name()?;
+        if current_branch != expected_auditor_branch {
+            let message = format!(
+                "Incorrect branch: \n -current: {}\n -expected: {}",
+                current_branch, expected_auditor_branch
+            );
+            return Err(Report::new(GitError).attach_printable(message)).attach(Suggestion(
+                format!(
+                    "run \"{} {}\" or \"{}\" to move to the correct branch",
+                    "git checkout".green(),
+                    expected_auditor_branch.green(),
+                    "bat-cli refresh".green()
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    fn checkout_branch(&self, branch_name: &str) -> GitResult<()> {
+        BatRepository::open()
+            .and_then(|bat_repository| bat_repository.checkout_branch(branch_name))
+            .change_context(GitError)
+            .attach_printable(format!("Error checking out {}", branch_name))
+    }
+}
+
+pub fn get_auditor_branch_name() -> GitResult<String> {
+    let bat_auditor_config = BatAuditorConfig::get_config().change_context(GitError)?;
+    get_branch_name_of_auditor(&bat_auditor_config.auditor_name)
+}
+
+pub fn get_branch_name_of_auditor(auditor_name: &str) -> GitResult<String> {
+    let bat_config = BatConfig::get_config().change_context(GitError)?;
+    Ok(format!("{}-{}", auditor_name, bat_config.project_name))
+}
+
+pub fn check_if_branch_exists(branch_name: &str) -> GitResult<bool> {
+    BatRepository::open()
+        .and_then(|bat_repository| bat_repository.check_if_branch_exists(branch_name))
+        .change_context(GitError)
+        .attach_printable(format!("Error checking if {} exists", branch_name))
+}
+
+pub fn check_files_not_committed() -> GitResult<()> {
+    let modified_files = get_not_committed_files()?;
+    if !modified_files.is_empty() {
+        let message = format!(
+            "There are modified files that needs to be committed:\n{:#?}",
+            modified_files
+        );
+        return Err(Report::new(GitError).attach_printable(message));
+    }
+    Ok(())
+}
+
+pub fn get_not_committed_files() -> GitResult<Vec<String>> {
+    BatRepository::open()
+        .and_then(|bat_repository| bat_repository.get_not_committed_files())
+        .change_context(GitError)
+}
+
+pub fn get_local_branches() -> GitResult<Vec<String>> {
+    BatRepository::open()
+        .and_then(|bat_repository| bat_repository.get_local_branch_names())
+        .change_context(GitError)
+}
+
+/// Remote-tracking branches, like origin/main
+pub fn get_remote_branches() -> GitResult<Vec<String>> {
+    BatRepository::open()
+        .and_then(|bat_repository| bat_repository.get_remote_branch_names())
+        .change_context(GitError)
+}
+
+// Git
+pub fn get_current_branch_name() -> GitResult<String> {
+    BatRepository::open()
+        .and_then(|bat_repository| bat_repository.get_current_branch_name())
+        .change_context(GitError)
+}
+
+/// Permalink to the lines of file_path, at the HEAD commit of the repository of the file.
+/// The forge comes from the origin remote, then from commit_hash_url, and the local
+/// repository is linked when none of them is known
+pub fn get_repository_permalink(
+    file_path: &str,
+    start_line_index: usize,
+    end_line_index: Option<usize>,
+    commit_hash_url: &str,
+    git_forge: Option<GitForge>,
+) -> GitResult<String> {
+    let config_repository = ForgeRepository::new_from_commit_url(commit_hash_url, git_forge);
+    let file_folder = Path::new(file_path)
+        .parent()
+        .and_then(|parent| parent.to_str())
+        .filter(|parent| !parent.is_empty())
+        .unwrap_or(".");
+    let program_repository = BatRepository::open_from_path(file_folder).ok();
+    let head_commit_hash = program_repository
+        .as_ref()
+        .and_then(|repository| repository.get_head_commit_hash().ok());
+    if let (Some(head_commit_hash), Some((_, config_commit_hash))) =
+        (&head_commit_hash, &config_repository)
+    {
+        if head_commit_hash != config_commit_hash {
+            log::warn!(
+                "HEAD of {} is {}, but commit_hash_url points to {}",
+                file_path,
+                head_commit_hash,
+                config_commit_hash
+            );
+        }
+    }
+    let config_commit_hash = config_repository
+        .as_ref()
+        .map(|(_, commit_hash)| commit_hash.clone());
+    let local_repository = || {
+        program_repository
+            .as_ref()
+            .and_then(|repository| repository.get_workdir().ok())
+            .map(|workdir| ForgeRepository::new_local(&workdir))
+    };
+    let forge_repository = program_repository
+        .as_ref()
+        .filter(|_| git_forge != Some(GitForge::Local))
+        .and_then(|repository| repository.get_remote_url("origin").ok())
+        .and_then(|remote_url| ForgeRepository::new_from_remote_url(&remote_url, git_forge))
+        .or(config_repository
+            .filter(|_| git_forge != Some(GitForge::Local))
+            .map(|(forge_repository, _)| forge_repository))
+        .or_else(local_repository);
+    let commit_hash = head_commit_hash.or(config_commit_hash);
+    let (forge_repository, commit_hash) = match (forge_repository, commit_hash) {
+        (Some(forge_repository), Some(commit_hash)) => (forge_repository, commit_hash),
+        _ => {
+            println!(
+                "Commit hash url format is not compatible, got {}, expected the url of a commit on GitHub, GitLab, Bitbucket or Gitea, set git_forge on the Bat.toml for self-hosted instances",
+                commit_hash_url.red()
+            );
+            return Ok("".to_string());
+        }
+    };
+    let program_path = match program_repository
+        .as_ref()
+        .and_then(|repository| repository.get_relative_path(file_path).ok())
+    {
+        Some(relative_path) => relative_path,
+        None => {
+            let mut program_path_formatted = file_path.trim_start_matches("../").split('/');
+            program_path_formatted
+                .next()
+                .ok_or(GitError)
+                .into_report()?;
+            program_path_formatted.collect::<Vec<_>>().join("/")
+        }
+    };
+    Ok(forge_repository.get_permalink(
+        &commit_hash,
+        &program_path,
+        start_line_index,
+        end_line_index,
+    ))
+}
+
+pub enum GitCommit {
+    Init,
+    InitAuditor,
+    StartCO {
+        entrypoint_name: String,
+    },
+    FinishCO {
+        entrypoint_name: String,
+    },
+    UpdateCO {
+        entrypoint_name: String,
+    },
+    UpdateCOSummary,
+    StartFinding {
+        finding_name: String,
+    },
+    FinishFinding {
+        finding_name: String,
+    },
+    RejectFinding {
+        finding_name: String,
+    },
+    UpdateFinding {
+        finding_name: String,
+    },
+    /// PoC stub generated for a to-review finding and linked from it
+    AddFindingPoc {
+        finding_name: String,
+        poc_file_name: String,
+    },
+    AcceptFindings {
+        finding_names: Vec<String>,
+    },
+    /// ids assigned to the findings of the auditor and the regenerated ids table
+    UpdateFindingIds {
+        finding_names: Vec<String>,
+    },
+    /// fix review written on an accepted finding and the regenerated remediation table
+    ReviewFindingFix {
+        finding_file_path: String,
+        fix_status: String,
+    },
+    /// to-review duplicates moved to rejected after merging them into the kept finding
+    MergeFindings {
+        kept_file_path: String,
+        merged_finding_names: Vec<String>,
+    },
+    UpdateFindingCandidate {
+        candidate_id: usize,
+        action: String,
+        /// finding created from the candidate
+        finding_name: Option<String>,
+    },
+    UpdateOpenQuestions {
+        action: String,
+        /// code-overhaul files that got the answers on their Notes section
+        co_bat_files: Vec<BatFile>,
+    },
+    BatReload,
+    Notes,
+    ThreatModel,
+    UpdateMetadataJson {
+        bat_metadata_commit: BatMetadataCommit,
+    },
+    UpdateBatToml,
+}
+
+impl GitCommit {
+    pub fn create_commit(&self) -> GitResult<()> {
+        let commit_message = self.get_commit_message()?;
+        let commit_files = self.get_commit_files()?;
+        BatRepository::open()
+            .and_then(|bat_repository| bat_repository.create_commit(&commit_files, &commit_message))
+            .change_context(GitError)
+            .attach_printable(format!("Error creating the commit \"{}\"", commit_message))?;
+        Ok(())
+    }
+
+    fn get_commit_files(&self) -> GitResult<Vec<String>> {
+        let commit_files = match self {
+            GitCommit::Init => {
+                vec![".".to_string()]
+            }
+            GitCommit::InitAuditor => {
+                vec![BatFolder::AuditorNotes
+                    .get_path(true)
+                    .change_context(GitError)?]
+            }
+            GitCommit::StartCO { entrypoint_name } => {
+                vec![
+                    BatFile::CodeOverhaulToReview {
+                        file_name: entrypoint_name.clone(),
+                    }
+                    .get_path(false)
+                    .change_context(GitError)?,
+                    BatFile::CodeOverhaulStarted {
+                        file_name: entrypoint_name.clone(),
+                    }
+                    .get_path(true)
+                    .change_context(GitError)?,
+                    BatFile::BatMetadataFile
+                        .get_path(false)
+                        .change_context(GitError)?,
+                ]
+            }
+            GitCommit::FinishCO { entrypoint_name } => {
+                vec![
+                    BatFile::CodeOverhaulStarted {
+                        file_name: entrypoint_name.clone(),
+                    }
+                    .get_path(false)
+                    .change_context(GitError)?,
+                    BatFile::CodeOverhaulFinished {
+                        file_name: entrypoint_name.clone(),
+                    }
+                    .get_path(true)
+                    .change_context(GitError)?,
+                ]
+            }
+            GitCommit::UpdateCO { entrypoint_name } => {
+                vec![BatFile::CodeOverhaulFinished {
+                    file_name: entrypoint_name.clone(),
+                }
+                .get_path(true)
+                .change_context(GitError)?]
+            }
+            GitCommit::UpdateCOSummary => {
+                vec![BatFile::CodeOverhaulSummaryFile
+                    .get_path(true)
+                    .change_context(GitError)?]
+            }
+            GitCommit::StartFinding { finding_name } => {
+                vec![BatFile::FindingToReview {
+                    file_name: finding_name.clone(),
+                }
+                .get_path(true)
+                .change_context(GitError)?]
+            }
+            GitCommit::FinishFinding { finding_name } => {
+                vec![
+                    BatFile::FindingToReview {
+                        file_name: finding_name.clone(),
+                    }
+                    .get_path(true)
+                    .change_context(GitError)?,
+                    BatFolder::AuditorFigures
+                        .get_path(true)
+                        .change_context(GitError)?,
+                ]
+            }
+            GitCommit::AddFindingPoc {
+                finding_name,
+                poc_file_name,
+            } => {
+                vec![
+                    BatFile::FindingToReview {
+                        file_name: finding_name.clone(),
+                    }
+                    .get_path(true)
+                    .change_context(GitError)?,
+                    BatFile::FindingPoc {
+                        file_name: poc_file_name.clone(),
+                    }
+                    .get_path(true)
+                    .change_context(GitError)?,
+                ]
+            }
+            GitCommit::UpdateFinding { finding_name } => {
+                vec![
+                    BatFile::FindingToReview {
+                        file_name: finding_name.clone(),
+                    }
+                    .get_path(true)
+                    .change_context(GitError)?,
+                    BatFolder::AuditorFigures
+                        .get_path(true)
+                        .change_context(GitError)?,
+                ]
+            }
+            GitCommit::RejectFinding { finding_name } => {
+                vec![
+                    BatFile::FindingToReview {
+                        file_name: finding_name.clone(),
+                    }
+                    .get_path(false)
+                    .change_context(GitError)?,
+                    BatFile::FindingRejected {
+                        file_name: finding_name.clone(),
+                    }
+                    .get_path(true)
+                    .change_context(GitError)?,
+                    BatFolder::AuditorFigures
+                        .get_path(true)
+                        .change_context(GitError)?,
+                ]
+            }
+            GitCommit::AcceptFindings { .. } => {
+                vec![
+                    BatFolder::FindingsAccepted
+                        .get_path(true)
+                        .change_context(GitError)?,
+                    BatFolder::AuditorFigures
+                        .get_path(true)
+                        .change_context(GitError)?,
+                ]
+            }
+            GitCommit::UpdateFindingCandidate { finding_name, .. } => {
+                let mut commit_files = vec![
+                    BatFile::FindingCandidates
+                        .get_path(true)
+                        .change_context(GitError)?,
+                    BatFile::FindingCandidatesToml
+                        .get_path(true)
+                        .change_context(GitError)?,
+                ];
+                if let Some(finding_name) = finding_name {
+                    commit_files.push(
+                        BatFile::FindingToReview {
+                            file_name: finding_name.clone(),
+                        }
+                        .get_path(true)
+                        .change_context(GitError)?,
+                    );
+                }
+                commit_files
+            }
+            GitCommit::UpdateOpenQuestions { co_bat_files, .. } => {
+                let mut commit_files = vec![
+                    BatFile::OpenQuestions
+                        .get_path(true)
+                        .change_context(GitError)?,
+                    BatFile::OpenQuestionsToml
+                        .get_path(true)
+                        .change_context(GitError)?,
+                ];
+                for co_bat_file in co_bat_files {
+                    commit_files.push(co_bat_file.get_path(true).change_context(GitError)?);
+                }
+                commit_files
+            }
+            GitCommit::UpdateFindingIds { .. } => {
+                vec![
+                    BatFile::FindingIdsTable
+                        .get_path(true)
+                        .change_context(GitError)?,
+                    BatFolder::FindingsFolderPath
+                        .get_path(true)
+                        .change_context(GitError)?,
+                ]
+            }
+            GitCommit::ReviewFindingFix {
+                finding_file_path, ..
+            } => {
+                vec![
+                    BatFile::Generic {
+                        file_path: finding_file_path.clone(),
+                    }
+                    .get_path(true)
+                    .change_context(GitError)?,
+                    BatFile::RemediationTable
+                        .get_path(true)
+                        .change_context(GitError)?,
+                ]
+            }
+            GitCommit::MergeFindings {
+                kept_file_path,
+                merged_finding_names,
+            } => {
+                let mut commit_files = vec![BatFile::Generic {
+                    file_path: kept_file_path.clone(),
+                }
+                .get_path(true)
+                .change_context(GitError)?];
+                for finding_name in merged_finding_names {
+                    commit_files.push(
+                        BatFile::FindingToReview {
+                            file_name: finding_name.clone(),
+                        }
+                        .get_path(false)
+                        .change_context(GitError)?,
+                    );
+                    commit_files.push(
+                        BatFile::FindingRejected {
+                            file_name: finding_name.clone(),
+                        }
+                        .get_path(true)
+                        .change_context(GitError)?,
+                    );
+                }
+                commit_files
+            }
+            GitCommit::BatReload => {
+                vec![
+                    BatFolder::CodeOverhaulToReview
+                        .get_path(true)
+                        .change_context(GitError)?,
+                    BatFile::GitIgnore.get_path(true).change_context(GitError)?,
+                    BatFile::GitAttributes
+                        .get_path(true)
+                        .change_context(GitError)?,
+                ]
+            }
+            GitCommit::Notes => {
+                let mut commit_files = vec![
+                    BatFile::FindingCandidates
+                        .get_path(true)
+                        .change_context(GitError)?,
+                    BatFile::ThreatModeling
+                        .get_path(true)
+                        .change_context(GitError)?,
+                    BatFile::OpenQuestions
+                        .get_path(true)
+                        .change_context(GitError)?,
+                ];
+                // only exist once managed with bat-cli candidate, questions and threat-model
+                for sidecar_file in [
+                    BatFile::FindingCandidatesToml,
+                    BatFile::OpenQuestionsToml,
+                    BatFile::ThreatModelingToml,
+                ] {
+                    if sidecar_file.file_exists().change_context(GitError)? {
+                        commit_files.push(sidecar_file.get_path(true).change_context(GitError)?);
+                    }
+                }
+                commit_files
+            }
+            GitCommit::ThreatModel => {
+                vec![
+                    BatFile::ThreatModeling
+                        .get_path(true)
+                        .change_context(GitError)?,
+                    BatFile::ThreatModelingToml
+                        .get_path(true)
+                        .change_context(GitError)?,
+                ]
+            }
+            GitCommit::UpdateBatToml => {
+                vec![BatFile::BatToml.get_path(true).change_context(GitError)?]
+            }
+            GitCommit::UpdateMetadataJson { .. } => {
+                vec![BatFile::BatMetadataFile
+                    .get_path(false)
+                    .change_context(GitError)?]
+            }
+        };
+        Ok(commit_files)
+    }
+
+    fn get_commit_message(&self) -> GitResult<String> {
+        let bat_config = BatConfig::get_config().change_context(GitError)?;
+        let commit_message = match self {
+            GitCommit::Init => {
+                BatCommitMessage::new(BatCommitType::Repo, &bat_config.project_name, "initialized")
+            }
+            GitCommit::InitAuditor => {
+                let bat_auditor_config = BatAuditorConfig::get_config().change_context(GitError)?;
+                BatCommitMessage::new(
+                    BatCommitType::Auditor,
+                    &bat_auditor_config.auditor_name,
+                    "initialized",
+                )
+            }
+            GitCommit::StartCO { entrypoint_name } => {
+                Self::get_co_commit_message(entrypoint_name, "started")
+            }
+            GitCommit::FinishCO { entrypoint_name } => {
+                Self::get_co_commit_message(entrypoint_name, "finished")
+            }
+            GitCommit::UpdateCO { entrypoint_name } => {
+                Self::get_co_commit_message(entrypoint_name, "updated")
+            }
+            GitCommit::UpdateCOSummary => {
+                BatCommitMessage::new(BatCommitType::Co, "code_overhaul_summary", "updated")
+            }
+            GitCommit::StartFinding { finding_name } => {
+                BatCommitMessage::new(BatCommitType::Finding, finding_name, "started")
+            }
+            GitCommit::FinishFinding { finding_name } => {
+                BatCommitMessage::new(BatCommitType::Finding, finding_name, "finished")
+            }
+            GitCommit::RejectFinding { finding_name } => {
+                BatCommitMessage::new(BatCommitType::Finding, finding_name, "rejected")
+            }
+            GitCommit::UpdateFinding { finding_name } => {
+                BatCommitMessage::new(BatCommitType::Finding, finding_name, "updated")
+            }
+            GitCommit::AddFindingPoc { finding_name, .. } => {
+                BatCommitMessage::new(BatCommitType::Finding, finding_name, "poc added")
+            }
+            GitCommit::AcceptFindings { finding_names } => {
+                let mut commit_message =
+                    BatCommitMessage::new(BatCommitType::Finding, "to-review", "accepted");
+                commit_message.finding_names = finding_names.clone();
+                commit_message
+            }
+            GitCommit::UpdateFindingCandidate {
+                candidate_id,
+                action,
+                ..
+            } => BatCommitMessage::new(BatCommitType::Candidate, &candidate_id.to_string(), action),
+            GitCommit::UpdateOpenQuestions { action, .. } => {
+                BatCommitMessage::new(BatCommitType::Notes, "open_questions", action)
+            }
+            GitCommit::UpdateFindingIds { finding_names } => {
+                let mut commit_message =
+                    BatCommitMessage::new(BatCommitType::Finding, "ids", "updated");
+                commit_message.finding_names = finding_names.clone();
+                commit_message
+            }
+            GitCommit::ReviewFindingFix {
+                finding_file_path,
+                fix_status,
+            } => {
+                let finding_name = Path::new(finding_file_path)
+                    .file_stem()
+                    .map(|file_stem| file_stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                BatCommitMessage::new(
+                    BatCommitType::Finding,
+                    &finding_name,
+                    &format!("fix {}", fix_status.to_lowercase()),
+                )
+            }
+            GitCommit::MergeFindings {
+                kept_file_path,
+                merged_finding_names,
+            } => {
+                let kept_finding_name = Path::new(kept_file_path)
+                    .file_stem()
+                    .map(|file_stem| file_stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let mut commit_message =
+                    BatCommitMessage::new(BatCommitType::Finding, &kept_finding_name, "merged");
+                commit_message.finding_names = merged_finding_names.clone();
+                commit_message
+            }
+            GitCommit::BatReload => {
+                BatCommitMessage::new(BatCommitType::Repo, &bat_config.project_name, "reloaded")
+            }
+            GitCommit::Notes => {
+                let bat_auditor_config = BatAuditorConfig::get_config().change_context(GitError)?;
+                BatCommitMessage::new(
+                    BatCommitType::Notes,
+                    &bat_auditor_config.auditor_name,
+                    "updated",
+                )
+            }
+            GitCommit::ThreatModel => {
+                BatCommitMessage::new(BatCommitType::Notes, "threat_modeling", "generated")
+            }
+            GitCommit::UpdateBatToml => {
+                BatCommitMessage::new(BatCommitType::Repo, "Bat.toml", "updated to last version")
+            }
+            GitCommit::UpdateMetadataJson {
+                bat_metadata_commit,
+            } => bat_metadata_commit.get_commit_message(),
+        };
+        Ok(commit_message.get_message())
+    }
+
+    // the metadata id is left out if the entry point is not on the BatMetadata
+    fn get_co_commit_message(entrypoint_name: &str, action: &str) -> BatCommitMessage {
+        let mut commit_message = BatCommitMessage::new(BatCommitType::Co, entrypoint_name, action);
+        commit_message.metadata_id = BatMetadata::read_metadata()
+            .and_then(|bat_metadata| {
+                bat_metadata.get_entrypoint_metadata_by_name(entrypoint_name.to_string())
+            })
+            .map(|entrypoint_metadata| entrypoint_metadata.metadata_id)
+            .ok();
+        commit_message
+    }
+}
+
+#[test]
+fn test_get_branches_list() {
+    let _branches_list = get_local_branches().unwrap();
+}
+
+#[test]
+fn test_check_files_not_committed() {
+    env_logger::init();
+    check_files_not_committed().unwrap();
+}
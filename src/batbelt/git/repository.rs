@@ -0,0 +1,865 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use error_stack::{IntoReport, Report, Result, ResultExt};
+use git2::build::CheckoutBuilder;
+use git2::{
+    Branch, BranchType, Commit, ErrorCode, IndexAddOption, ObjectType, Repository,
+    RepositoryInitOptions, Sort, Status, StatusOptions, TreeWalkMode, TreeWalkResult,
+};
+
+// remote of the local bare repository the audit branches are pushed to
+pub const BAT_BARE_REMOTE_NAME: &str = "bare";
+
+/// Errors of the libgit2 backend, kept as the context of the report so callers can match them
+#[derive(Debug, Clone, PartialEq)]
+pub enum GitRepositoryError {
+    RepositoryNotFound { path: String },
+    DetachedHead,
+    UnbornBranch,
+    BranchNotFound { branch_name: String },
+    PathOutsideRepository { path: String },
+    RemoteNotFound { remote_name: String },
+    SignatureNotConfigured,
+    Backend { code: ErrorCode, message: String },
+}
+
+impl fmt::Display for GitRepositoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitRepositoryError::RepositoryNotFound { path } => {
+                write!(f, "No git repository found for {}", path)
+            }
+            GitRepositoryError::DetachedHead => f.write_str("HEAD is not pointing to a branch"),
+            GitRepositoryError::UnbornBranch => f.write_str("The current branch has no commits"),
+            GitRepositoryError::BranchNotFound { branch_name } => {
+                write!(f, "Branch {} not found", branch_name)
+            }
+            GitRepositoryError::PathOutsideRepository { path } => {
+                write!(f, "{} is outside of the repository", path)
+            }
+            GitRepositoryError::RemoteNotFound { remote_name } => {
+                write!(f, "Remote {} not found", remote_name)
+            }
+            GitRepositoryError::SignatureNotConfigured => {
+                f.write_str("user.name and user.email are not configured for git")
+            }
+            GitRepositoryError::Backend { code, message } => {
+                write!(f, "Git backend error {:?}: {}", code, message)
+            }
+        }
+    }
+}
+
+impl Error for GitRepositoryError {}
+
+impl From<git2::Error> for GitRepositoryError {
+    fn from(error: git2::Error) -> Self {
+        GitRepositoryError::Backend {
+            code: error.code(),
+            message: error.message().to_string(),
+        }
+    }
+}
+
+pub type GitRepositoryResult<T> = Result<T, GitRepositoryError>;
+
+trait IntoGitRepositoryReport<T> {
+    fn into_git_report(self) -> GitRepositoryResult<T>;
+}
+
+impl<T> IntoGitRepositoryReport<T> for std::result::Result<T, git2::Error> {
+    fn into_git_report(self) -> GitRepositoryResult<T> {
+        self.map_err(|error| Report::new(GitRepositoryError::from(error)))
+    }
+}
+
+/// A commit of the history, timestamp in seconds since the epoch
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatRepositoryCommit {
+    pub commit_hash: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub timestamp: i64,
+    pub message: String,
+}
+
+pub struct BatRepository {
+    repository: Repository,
+}
+
+impl BatRepository {
+    /// Opens the repository of the current directory
+    pub fn open() -> GitRepositoryResult<Self> {
+        Self::open_from_path(".")
+    }
+
+    /// Opens the repository containing path, looking up the parent folders
+    pub fn open_from_path(path: &str) -> GitRepositoryResult<Self> {
+        let repository = Repository::discover(path)
+            .map_err(|_| {
+                Report::new(GitRepositoryError::RepositoryNotFound {
+                    path: path.to_string(),
+                })
+            })
+            .attach_printable(format!("Error opening the git repository of {}", path))?;
+        Ok(Self { repository })
+    }
+
+    /// Initializes a repository at path with HEAD on initial_branch_name
+    pub fn init(path: &str, initial_branch_name: &str) -> GitRepositoryResult<Self> {
+        let mut init_options = RepositoryInitOptions::new();
+        init_options.initial_head(initial_branch_name);
+        let repository = Repository::init_opts(path, &init_options)
+            .into_git_report()
+            .attach_printable(format!("Error initializing a git repository on {}", path))?;
+        Ok(Self { repository })
+    }
+
+    /// Creates branch_name pointing to the last commit of from_branch_name, without checking it out
+    pub fn create_branch(
+        &self,
+        branch_name: &str,
+        from_branch_name: &str,
+    ) -> GitRepositoryResult<()> {
+        let from_commit = self
+            .repository
+            .find_branch(from_branch_name, BranchType::Local)
+            .map_err(|_| {
+                Report::new(GitRepositoryError::BranchNotFound {
+                    branch_name: from_branch_name.to_string(),
+                })
+            })?
+            .get()
+            .peel_to_commit()
+            .into_git_report()?;
+        self.repository
+            .branch(branch_name, &from_commit, false)
+            .into_git_report()
+            .attach_printable(format!("Error creating the branch {}", branch_name))?;
+        Ok(())
+    }
+
+    /// Checks out a local branch, fails instead of overwriting local changes. Like git checkout,
+    /// a branch only fetched from a remote is created tracking it
+    pub fn checkout_branch(&self, branch_name: &str) -> GitRepositoryResult<()> {
+        let branch = match self.repository.find_branch(branch_name, BranchType::Local) {
+            Ok(branch) => branch,
+            Err(_) => self.create_tracking_branch(branch_name)?,
+        };
+        let branch_reference = branch
+            .get()
+            .name()
+            .ok_or(GitRepositoryError::BranchNotFound {
+                branch_name: branch_name.to_string(),
+            })
+            .into_report()?
+            .to_string();
+        let branch_commit = branch.get().peel_to_commit().into_git_report()?;
+        self.repository
+            .checkout_tree(
+                branch_commit.as_object(),
+                Some(CheckoutBuilder::new().safe()),
+            )
+            .into_git_report()
+            .attach_printable(format!("Error checking out {}", branch_name))?;
+        self.repository
+            .set_head(&branch_reference)
+            .into_git_report()?;
+        Ok(())
+    }
+
+    /// Creates branch_name from its remote-tracking branch, the origin one first
+    fn create_tracking_branch(&self, branch_name: &str) -> GitRepositoryResult<Branch<'_>> {
+        let remote_suffix = format!("/{}", branch_name);
+        let remote_branch_name = self
+            .get_remote_branch_names()?
+            .into_iter()
+            .filter(|remote_branch_name| remote_branch_name.ends_with(&remote_suffix))
+            .min_by_key(|remote_branch_name| !remote_branch_name.starts_with("origin/"))
+            .ok_or(GitRepositoryError::BranchNotFound {
+                branch_name: branch_name.to_string(),
+            })
+            .into_report()?;
+        let remote_commit = self
+            .repository
+            .find_branch(&remote_branch_name, BranchType::Remote)
+            .into_git_report()?
+            .get()
+            .peel_to_commit()
+            .into_git_report()?;
+        let mut branch = self
+            .repository
+            .branch(branch_name, &remote_commit, false)
+            .into_git_report()?;
+        branch
+            .set_upstream(Some(&remote_branch_name))
+            .into_git_report()?;
+        Ok(branch)
+    }
+
+    /// Creates a branch from the current one and checks it out, like git checkout -b
+    pub fn create_and_checkout_branch(&self, branch_name: &str) -> GitRepositoryResult<()> {
+        let current_branch_name = self.get_current_branch_name()?;
+        self.create_branch(branch_name, &current_branch_name)?;
+        self.checkout_branch(branch_name)
+    }
+
+    pub fn get_local_branch_names(&self) -> GitRepositoryResult<Vec<String>> {
+        self.get_branch_names(BranchType::Local)
+    }
+
+    /// Remote-tracking branches like origin/main, without the symbolic origin/HEAD
+    pub fn get_remote_branch_names(&self) -> GitRepositoryResult<Vec<String>> {
+        self.get_branch_names(BranchType::Remote)
+    }
+
+    fn get_branch_names(&self, branch_type: BranchType) -> GitRepositoryResult<Vec<String>> {
+        let mut branch_names = vec![];
+        for branch in self
+            .repository
+            .branches(Some(branch_type))
+            .into_git_report()?
+        {
+            let (branch, _) = branch.into_git_report()?;
+            if branch.get().symbolic_target().is_some() {
+                continue;
+            }
+            if let Some(branch_name) = branch.name().into_git_report()? {
+                branch_names.push(branch_name.to_string());
+            }
+        }
+        branch_names.sort();
+        Ok(branch_names)
+    }
+
+    /// Deletes a local branch even if it is not merged, like git branch -D
+    pub fn delete_branch(&self, branch_name: &str) -> GitRepositoryResult<()> {
+        self.repository
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| {
+                Report::new(GitRepositoryError::BranchNotFound {
+                    branch_name: branch_name.to_string(),
+                })
+            })?
+            .delete()
+            .into_git_report()
+            .attach_printable(format!("Error deleting the branch {}", branch_name))?;
+        Ok(())
+    }
+
+    pub fn add_remote(&self, remote_name: &str, remote_url: &str) -> GitRepositoryResult<()> {
+        self.repository
+            .remote(remote_name, remote_url)
+            .into_git_report()
+            .attach_printable(format!("Error adding the remote {}", remote_name))?;
+        Ok(())
+    }
+
+    /// Stages every file of the working tree, deleted files included, like git add -A
+    pub fn add_all(&self) -> GitRepositoryResult<()> {
+        let mut index = self.repository.index().into_git_report()?;
+        index
+            .add_all(["*"], IndexAddOption::DEFAULT, None)
+            .into_git_report()?;
+        index.update_all(["*"], None).into_git_report()?;
+        index.write().into_git_report()?;
+        Ok(())
+    }
+
+    /// Sets a value on the config of the repository, like git config name value
+    pub fn set_config_value(&self, name: &str, value: &str) -> GitRepositoryResult<()> {
+        self.repository
+            .config()
+            .into_git_report()?
+            .set_str(name, value)
+            .into_git_report()
+            .attach_printable(format!("Error setting {} on the git config", name))?;
+        Ok(())
+    }
+
+    /// Pushes the branches to the bare repository at bare_repository_path, which is created if
+    /// it doesn't exist, and keeps it as the BAT_BARE_REMOTE_NAME remote
+    pub fn push_to_bare_repository(
+        &self,
+        bare_repository_path: &str,
+        branch_names: &[String],
+    ) -> GitRepositoryResult<()> {
+        if Repository::open_bare(bare_repository_path).is_err() {
+            Repository::init_bare(bare_repository_path)
+                .into_git_report()
+                .attach_printable(format!(
+                    "Error creating the bare repository {}",
+                    bare_repository_path
+                ))?;
+        }
+        match self.repository.find_remote(BAT_BARE_REMOTE_NAME) {
+            Ok(remote) if remote.url() == Some(bare_repository_path) => {}
+            Ok(_) => self
+                .repository
+                .remote_set_url(BAT_BARE_REMOTE_NAME, bare_repository_path)
+                .into_git_report()?,
+            Err(_) => {
+                self.repository
+                    .remote(BAT_BARE_REMOTE_NAME, bare_repository_path)
+                    .into_git_report()?;
+            }
+        }
+        let mut remote = self
+            .repository
+            .find_remote(BAT_BARE_REMOTE_NAME)
+            .into_git_report()?;
+        let refspecs = branch_names
+            .iter()
+            .map(|branch_name| format!("refs/heads/{0}:refs/heads/{0}", branch_name))
+            .collect::<Vec<_>>();
+        remote
+            .push(&refspecs, None)
+            .into_git_report()
+            .attach_printable(format!("Error pushing to {}", bare_repository_path))?;
+        Ok(())
+    }
+
+    pub fn get_current_branch_name(&self) -> GitRepositoryResult<String> {
+        match self.repository.head() {
+            Ok(head) => {
+                if !head.is_branch() {
+                    return Err(Report::new(GitRepositoryError::DetachedHead));
+                }
+                head.shorthand()
+                    .map(|branch_name| branch_name.to_string())
+                    .ok_or(GitRepositoryError::DetachedHead)
+                    .into_report()
+            }
+            // no commits yet, HEAD still points to the branch name
+            Err(error) if error.code() == ErrorCode::UnbornBranch => {
+                let head = self.repository.find_reference("HEAD").into_git_report()?;
+                head.symbolic_target()
+                    .map(|target| target.trim_start_matches("refs/heads/").to_string())
+                    .ok_or(GitRepositoryError::DetachedHead)
+                    .into_report()
+            }
+            Err(error) => Err(error).into_git_report(),
+        }
+    }
+
+    /// True if branch_name is a local branch or a revision that resolves, like origin/main
+    pub fn check_if_branch_exists(&self, branch_name: &str) -> GitRepositoryResult<bool> {
+        if self
+            .repository
+            .find_branch(branch_name, BranchType::Local)
+            .is_ok()
+        {
+            return Ok(true);
+        }
+        match self.repository.revparse_single(branch_name) {
+            Ok(_) => Ok(true),
+            Err(error)
+                if matches!(
+                    error.code(),
+                    ErrorCode::NotFound | ErrorCode::InvalidSpec | ErrorCode::UnbornBranch
+                ) =>
+            {
+                Ok(false)
+            }
+            Err(error) => Err(error).into_git_report(),
+        }
+    }
+
+    /// Paths of the modified, staged and untracked files, ignored files excluded
+    pub fn get_not_committed_files(&self) -> GitRepositoryResult<Vec<String>> {
+        let mut status_options = StatusOptions::new();
+        status_options
+            .include_untracked(true)
+            .include_ignored(false)
+            .recurse_untracked_dirs(true);
+        let statuses = self
+            .repository
+            .statuses(Some(&mut status_options))
+            .into_git_report()?;
+        let not_committed_files = statuses
+            .iter()
+            .filter(|entry| entry.status() != Status::CURRENT)
+            .filter_map(|entry| entry.path().map(|path| path.to_string()))
+            .collect::<Vec<_>>();
+        Ok(not_committed_files)
+    }
+
+    /// Commits reachable from any local or remote-tracking branch, like the auditors branches
+    /// fetched from origin, each commit once and oldest first
+    pub fn get_all_branches_commits(&self) -> GitRepositoryResult<Vec<BatRepositoryCommit>> {
+        let mut revwalk = self.repository.revwalk().into_git_report()?;
+        revwalk.push_glob("refs/heads/*").into_git_report()?;
+        revwalk.push_glob("refs/remotes/*").into_git_report()?;
+        revwalk
+            .set_sorting(Sort::TIME | Sort::REVERSE)
+            .into_git_report()?;
+        let mut commit_ids = HashSet::new();
+        let mut commits = vec![];
+        for commit_id in revwalk {
+            let commit_id = commit_id.into_git_report()?;
+            if !commit_ids.insert(commit_id) {
+                continue;
+            }
+            let commit = self.repository.find_commit(commit_id).into_git_report()?;
+            let author = commit.author();
+            commits.push(BatRepositoryCommit {
+                commit_hash: commit.id().to_string(),
+                author_name: author.name().unwrap_or_default().to_string(),
+                author_email: author.email().unwrap_or_default().to_string(),
+                timestamp: author.when().seconds(),
+                message: commit.message().unwrap_or_default().to_string(),
+            });
+        }
+        Ok(commits)
+    }
+
+    /// Last commit of branch_name between the local branch and the remote-tracking ones, like
+    /// origin/branch_name, None if there is no branch with that name
+    fn find_branch_last_commit(
+        &self,
+        branch_name: &str,
+    ) -> GitRepositoryResult<Option<Commit<'_>>> {
+        let mut branch_commits = vec![];
+        if let Ok(local_branch) = self.repository.find_branch(branch_name, BranchType::Local) {
+            branch_commits.push(local_branch.get().peel_to_commit().into_git_report()?);
+        }
+        let remote_suffix = format!("/{}", branch_name);
+        for remote_branch in self
+            .repository
+            .branches(Some(BranchType::Remote))
+            .into_git_report()?
+        {
+            let (remote_branch, _) = remote_branch.into_git_report()?;
+            if remote_branch
+                .name()
+                .into_git_report()?
+                .is_some_and(|remote_branch_name| remote_branch_name.ends_with(&remote_suffix))
+            {
+                branch_commits.push(remote_branch.get().peel_to_commit().into_git_report()?);
+            }
+        }
+        Ok(branch_commits
+            .into_iter()
+            .max_by_key(|commit| commit.time().seconds()))
+    }
+
+    /// Files under folder_path, relative to the root of the repository, on the last commit of
+    /// branch_name, as (path, content). None if the branch doesn't exist
+    pub fn get_branch_files(
+        &self,
+        branch_name: &str,
+        folder_path: &str,
+    ) -> GitRepositoryResult<Option<Vec<(String, String)>>> {
+        let branch_commit = match self.find_branch_last_commit(branch_name)? {
+            Some(branch_commit) => branch_commit,
+            None => return Ok(None),
+        };
+        let branch_tree = branch_commit.tree().into_git_report()?;
+        let folder_path = folder_path.trim_matches('/');
+        let folder_tree = if folder_path.is_empty() {
+            branch_tree
+        } else {
+            match branch_tree.get_path(Path::new(folder_path)) {
+                Ok(folder_entry) if folder_entry.kind() == Some(ObjectType::Tree) => self
+                    .repository
+                    .find_tree(folder_entry.id())
+                    .into_git_report()?,
+                Ok(_) => return Ok(Some(vec![])),
+                Err(error) if error.code() == ErrorCode::NotFound => return Ok(Some(vec![])),
+                Err(error) => return Err(error).into_git_report(),
+            }
+        };
+        let mut blob_entries = vec![];
+        folder_tree
+            .walk(TreeWalkMode::PreOrder, |entry_root, entry| {
+                if entry.kind() == Some(ObjectType::Blob) {
+                    blob_entries.push((
+                        Path::new(folder_path)
+                            .join(entry_root)
+                            .join(entry.name().unwrap_or_default())
+                            .to_string_lossy()
+                            .to_string(),
+                        entry.id(),
+                    ));
+                }
+                TreeWalkResult::Ok
+            })
+            .into_git_report()?;
+        let mut branch_files = vec![];
+        for (file_path, blob_id) in blob_entries {
+            let blob = self.repository.find_blob(blob_id).into_git_report()?;
+            branch_files.push((
+                file_path,
+                String::from_utf8_lossy(blob.content()).to_string(),
+            ));
+        }
+        Ok(Some(branch_files))
+    }
+
+    pub fn get_head_commit_hash(&self) -> GitRepositoryResult<String> {
+        let head = self.repository.head().map_err(|error| {
+            if error.code() == ErrorCode::UnbornBranch {
+                Report::new(GitRepositoryError::UnbornBranch)
+            } else {
+                Report::new(GitRepositoryError::from(error))
+            }
+        })?;
+        let head_commit = head.peel_to_commit().into_git_report()?;
+        Ok(head_commit.id().to_string())
+    }
+
+    pub fn get_remote_url(&self, remote_name: &str) -> GitRepositoryResult<String> {
+        let remote = self.repository.find_remote(remote_name).map_err(|_| {
+            Report::new(GitRepositoryError::RemoteNotFound {
+                remote_name: remote_name.to_string(),
+            })
+        })?;
+        remote
+            .url()
+            .map(|url| url.to_string())
+            .ok_or(GitRepositoryError::RemoteNotFound {
+                remote_name: remote_name.to_string(),
+            })
+            .into_report()
+    }
+
+    /// Absolute path of the root of the repository
+    pub fn get_workdir(&self) -> GitRepositoryResult<String> {
+        let workdir = self
+            .repository
+            .workdir()
+            .ok_or(GitRepositoryError::PathOutsideRepository {
+                path: ".".to_string(),
+            })
+            .into_report()?;
+        Ok(workdir.to_string_lossy().to_string())
+    }
+
+    /// Path of file_path relative to the root of the repository, with / separators
+    pub fn get_relative_path(&self, file_path: &str) -> GitRepositoryResult<String> {
+        let outside_error = || {
+            Report::new(GitRepositoryError::PathOutsideRepository {
+                path: file_path.to_string(),
+            })
+        };
+        let workdir = self
+            .repository
+            .workdir()
+            .ok_or_else(outside_error)?
+            .canonicalize()
+            .map_err(|_| outside_error())?;
+        let path = Path::new(file_path);
+        // deleted files can't be canonicalized, so the parent folder is used
+        let absolute_path = match path.canonicalize() {
+            Ok(absolute_path) => absolute_path,
+            Err(_) => {
+                let parent = match path.parent() {
+                    Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                    _ => PathBuf::from("."),
+                };
+                parent
+                    .canonicalize()
+                    .map_err(|_| outside_error())?
+                    .join(path.file_name().ok_or_else(outside_error)?)
+            }
+        };
+        let relative_path = absolute_path
+            .strip_prefix(&workdir)
+            .map_err(|_| outside_error())?;
+        Ok(relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/"))
+    }
+
+    /// Stages the paths like git add, deleted files included, and commits them on HEAD.
+    /// Does nothing if the staged tree didn't change, returns the new commit hash otherwise
+    pub fn create_commit(
+        &self,
+        file_paths: &[String],
+        message: &str,
+    ) -> GitRepositoryResult<Option<String>> {
+        let mut index = self.repository.index().into_git_report()?;
+        for file_path in file_paths {
+            let relative_path = self.get_relative_path(file_path)?;
+            let pathspec = if relative_path.is_empty() {
+                "*".to_string()
+            } else {
+                relative_path
+            };
+            index
+                .add_all([pathspec.as_str()], IndexAddOption::DEFAULT, None)
+                .into_git_report()
+                .attach_printable(format!("Error staging {}", file_path))?;
+            index
+                .update_all([pathspec.as_str()], None)
+                .into_git_report()
+                .attach_printable(format!("Error staging {}", file_path))?;
+        }
+        index.write().into_git_report()?;
+        let tree_id = index.write_tree().into_git_report()?;
+        let tree = self.repository.find_tree(tree_id).into_git_report()?;
+        let parent_commit = match self.repository.head() {
+            Ok(head) => Some(head.peel_to_commit().into_git_report()?),
+            Err(error) if error.code() == ErrorCode::UnbornBranch => None,
+            Err(error) => return Err(error).into_git_report(),
+        };
+        if let Some(parent_commit) = &parent_commit {
+            if parent_commit.tree_id() == tree_id {
+                log::debug!("nothing to commit for \"{}\"", message);
+                return Ok(None);
+            }
+        }
+        let signature = self
+            .repository
+            .signature()
+            .map_err(|_| Report::new(GitRepositoryError::SignatureNotConfigured))?;
+        let parents = parent_commit.iter().collect::<Vec<_>>();
+        let commit_id = self
+            .repository
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .into_git_report()?;
+        Ok(Some(commit_id.to_string()))
+    }
+}
+
+#[test]
+fn test_bat_repository_commit_and_branches() {
+    use assert_fs::prelude::*;
+    let temp_dir = assert_fs::TempDir::new().unwrap();
+    let repository = Repository::init(temp_dir.path()).unwrap();
+    let mut config = repository.config().unwrap();
+    config.set_str("user.name", "auditor").unwrap();
+    config.set_str("user.email", "auditor@bat.cli").unwrap();
+    temp_dir
+        .child("notes/open_questions.md")
+        .write_str("- [ ] why?")
+        .unwrap();
+    let bat_repository = BatRepository {
+        repository: Repository::open(temp_dir.path()).unwrap(),
+    };
+    let current_branch = bat_repository.get_current_branch_name().unwrap();
+    assert!(!bat_repository
+        .check_if_branch_exists(&current_branch)
+        .unwrap());
+    assert_eq!(
+        bat_repository
+            .get_head_commit_hash()
+            .unwrap_err()
+            .current_context(),
+        &GitRepositoryError::UnbornBranch
+    );
+    assert_eq!(
+        bat_repository.get_not_committed_files().unwrap(),
+        vec!["notes/open_questions.md".to_string()]
+    );
+
+    let notes_paths = vec![temp_dir.child("notes").path().to_str().unwrap().to_string()];
+    let commit_hash = bat_repository
+        .create_commit(&notes_paths, "notes: open_questions updated")
+        .unwrap()
+        .unwrap();
+    assert_eq!(bat_repository.get_head_commit_hash().unwrap(), commit_hash);
+    assert!(bat_repository
+        .check_if_branch_exists(&current_branch)
+        .unwrap());
+    assert!(!bat_repository.check_if_branch_exists("develop").unwrap());
+    assert!(bat_repository.get_not_committed_files().unwrap().is_empty());
+    // nothing changed
+    assert_eq!(
+        bat_repository
+            .create_commit(&notes_paths, "notes: open_questions updated")
+            .unwrap(),
+        None
+    );
+
+    // deleted files are staged too
+    std::fs::remove_file(temp_dir.child("notes/open_questions.md").path()).unwrap();
+    bat_repository
+        .create_commit(&notes_paths, "notes: open_questions removed")
+        .unwrap()
+        .unwrap();
+    let head_tree = bat_repository
+        .repository
+        .head()
+        .unwrap()
+        .peel_to_tree()
+        .unwrap();
+    assert!(head_tree.is_empty());
+}
+
+#[test]
+fn test_bat_repository_branches_and_bare_push() {
+    use assert_fs::prelude::*;
+    let temp_dir = assert_fs::TempDir::new().unwrap();
+    let project_dir = temp_dir.child("project");
+    project_dir.create_dir_all().unwrap();
+    project_dir
+        .child(".gitignore")
+        .write_str("BatAuditor.toml")
+        .unwrap();
+    let bat_repository = BatRepository::init(project_dir.path().to_str().unwrap(), "main").unwrap();
+    let mut config = bat_repository.repository.config().unwrap();
+    config.set_str("user.name", "auditor").unwrap();
+    config.set_str("user.email", "auditor@bat.cli").unwrap();
+    assert_eq!(bat_repository.get_current_branch_name().unwrap(), "main");
+    let project_path = project_dir.path().to_str().unwrap().to_string();
+    bat_repository
+        .create_commit(&[project_path], "initial commit")
+        .unwrap();
+
+    bat_repository
+        .create_and_checkout_branch("develop")
+        .unwrap();
+    assert_eq!(bat_repository.get_current_branch_name().unwrap(), "develop");
+    bat_repository
+        .create_branch("auditor-project", "develop")
+        .unwrap();
+    bat_repository.checkout_branch("auditor-project").unwrap();
+    assert_eq!(
+        bat_repository.get_local_branch_names().unwrap(),
+        vec!["auditor-project", "develop", "main"]
+    );
+    bat_repository
+        .set_config_value("merge.bat-metadata.name", "BatMetadata.json merge")
+        .unwrap();
+    assert_eq!(
+        bat_repository
+            .repository
+            .config()
+            .unwrap()
+            .get_string("merge.bat-metadata.name")
+            .unwrap(),
+        "BatMetadata.json merge"
+    );
+    assert_eq!(
+        bat_repository.get_current_branch_name().unwrap(),
+        "auditor-project"
+    );
+    assert_eq!(
+        bat_repository
+            .create_branch("other-project", "missing")
+            .unwrap_err()
+            .current_context(),
+        &GitRepositoryError::BranchNotFound {
+            branch_name: "missing".to_string()
+        }
+    );
+
+    let bare_path = temp_dir
+        .child("bare.git")
+        .path()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let branch_names = ["main", "develop", "auditor-project"].map(|name| name.to_string());
+    bat_repository
+        .push_to_bare_repository(&bare_path, &branch_names)
+        .unwrap();
+    let bare_repository = Repository::open_bare(&bare_path).unwrap();
+    for branch_name in branch_names {
+        assert!(bare_repository
+            .find_branch(&branch_name, BranchType::Local)
+            .is_ok());
+    }
+
+    // a commit only on the remote-tracking branch of another auditor
+    let other_dir = temp_dir.child("other");
+    let other_repository = Repository::clone(&bare_path, other_dir.path()).unwrap();
+    {
+        let main_commit = other_repository
+            .find_branch("origin/main", BranchType::Remote)
+            .unwrap()
+            .get()
+            .peel_to_commit()
+            .unwrap();
+        other_repository
+            .branch("main", &main_commit, false)
+            .unwrap();
+    }
+    let mut other_config = other_repository.config().unwrap();
+    other_config.set_str("user.name", "other").unwrap();
+    other_config.set_str("user.email", "other@bat.cli").unwrap();
+    other_dir.child("notes.md").write_str("- other").unwrap();
+    let other_bat_repository = BatRepository {
+        repository: other_repository,
+    };
+    other_bat_repository
+        .create_branch("other-project", "main")
+        .unwrap();
+    other_bat_repository
+        .checkout_branch("other-project")
+        .unwrap();
+    other_bat_repository
+        .create_commit(
+            &[other_dir.path().to_str().unwrap().to_string()],
+            "other notes",
+        )
+        .unwrap();
+    other_bat_repository
+        .push_to_bare_repository(&bare_path, &["other-project".to_string()])
+        .unwrap();
+    bat_repository
+        .repository
+        .find_remote(BAT_BARE_REMOTE_NAME)
+        .unwrap()
+        .fetch(&["refs/heads/*:refs/remotes/bare/*"], None, None)
+        .unwrap();
+    assert!(bat_repository
+        .get_remote_branch_names()
+        .unwrap()
+        .contains(&"bare/other-project".to_string()));
+    assert!(!bat_repository
+        .get_local_branch_names()
+        .unwrap()
+        .contains(&"other-project".to_string()));
+    // the findings of the other auditor are read from the remote-tracking branch
+    assert_eq!(
+        bat_repository
+            .get_branch_files("other-project", "/")
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .filter(|(file_path, _)| file_path == "notes.md")
+            .collect::<Vec<_>>(),
+        vec![("notes.md".to_string(), "- other".to_string())]
+    );
+    assert_eq!(
+        bat_repository
+            .get_branch_files("other-project", "notes")
+            .unwrap(),
+        Some(vec![])
+    );
+    assert_eq!(
+        bat_repository
+            .get_branch_files("missing-project", "notes")
+            .unwrap(),
+        None
+    );
+    let commits = bat_repository.get_all_branches_commits().unwrap();
+    assert_eq!(
+        commits
+            .iter()
+            .map(|commit| commit.message.as_str())
+            .collect::<Vec<_>>(),
+        vec!["initial commit", "other notes"]
+    );
+    // like git checkout, the branch only fetched from the bare remote is created tracking it
+    bat_repository.checkout_branch("other-project").unwrap();
+    assert_eq!(
+        bat_repository.get_current_branch_name().unwrap(),
+        "other-project"
+    );
+    assert!(project_dir.child("notes.md").path().is_file());
+}
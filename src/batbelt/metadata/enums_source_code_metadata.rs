@@ -1,3 +1,4 @@
+use crate::batbelt::sonar::frontend::SourceLanguage;
 use crate::batbelt::sonar::{BatSonar, SonarResultType};
 
 use crate::batbelt::metadata::{
@@ -73,6 +74,10 @@ impl BatMetadataParser<EnumMetadataType> for EnumSourceCodeMetadata {
     //noinspection DuplicatedCode
     fn create_metadata_from_dir_entry(entry: DirEntry) -> Result<Vec<Self>, MetadataError> {
         let entry_path = entry.path().to_str().unwrap().to_string();
+        // only the Rust front-end produces traits and enums
+        if SourceLanguage::from_path(&entry_path) != Some(SourceLanguage::Rust) {
+            return Ok(vec![]);
+        }
         let file_content = fs::read_to_string(entry.path()).unwrap();
         log::debug!("entry_path:{}", &entry_path);
         log::debug!("file_content:\n{}", &file_content);
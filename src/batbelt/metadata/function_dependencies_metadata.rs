@@ -5,6 +5,17 @@ use serde::{Deserialize, Serialize};
 pub struct FunctionDependencyInfo {
     pub function_name: String,
     pub function_metadata_id: String,
+    /// true if the dependency was resolved from a method call with more than one candidate impl
+    #[serde(default)]
+    pub ambiguous_dispatch: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ExternalCrateDependencyInfo {
+    pub call_path: String,
+    pub crate_name: String,
+    pub crate_version: String,
+    pub is_workspace_crate: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -14,6 +25,8 @@ pub struct FunctionDependenciesMetadata {
     pub function_metadata_id: String,
     pub dependencies: Vec<FunctionDependencyInfo>,
     pub external_dependencies: Vec<MetadataId>,
+    #[serde(default)]
+    pub external_crate_dependencies: Vec<ExternalCrateDependencyInfo>,
 }
 
 impl FunctionDependenciesMetadata {
@@ -23,6 +36,7 @@ impl FunctionDependenciesMetadata {
         function_metadata_id: String,
         dependencies: Vec<FunctionDependencyInfo>,
         external_dependencies: Vec<MetadataId>,
+        external_crate_dependencies: Vec<ExternalCrateDependencyInfo>,
     ) -> Self {
         Self {
             function_name,
@@ -30,6 +44,7 @@ impl FunctionDependenciesMetadata {
             function_metadata_id,
             dependencies,
             external_dependencies,
+            external_crate_dependencies,
         }
     }
 
@@ -4,6 +4,7 @@ use crate::batbelt::parser::entrypoint_parser::EntrypointParser;
 use crate::config::BatConfig;
 use strum::IntoEnumIterator;
 
+use crate::batbelt::sonar::frontend::SourceLanguage;
 use crate::batbelt::sonar::{BatSonar, SonarResult, SonarResultType};
 
 use crate::batbelt::metadata::{BatMetadataParser, BatMetadataType, MetadataResult};
@@ -74,10 +75,26 @@ impl BatMetadataParser<FunctionMetadataType> for FunctionSourceCodeMetadata {
     }
 
     fn create_metadata_from_dir_entry(entry: DirEntry) -> Result<Vec<Self>, MetadataError> {
-        let mut metadata_result: Vec<FunctionSourceCodeMetadata> = vec![];
         let entry_path = entry.path().to_str().unwrap().to_string();
         let file_content = fs::read_to_string(entry.path()).unwrap();
-        let bat_sonar = BatSonar::new_scanned(&file_content, SonarResultType::Function);
+        match SourceLanguage::from_path(&entry_path) {
+            None => Ok(vec![]),
+            Some(source_language) => source_language
+                .get_frontend()
+                .get_functions(&entry_path, &file_content)
+                .change_context(MetadataError),
+        }
+    }
+}
+
+impl FunctionSourceCodeMetadata {
+    pub fn get_rust_functions(
+        entry_path: &str,
+        file_content: &str,
+    ) -> Result<Vec<Self>, MetadataError> {
+        let mut metadata_result: Vec<FunctionSourceCodeMetadata> = vec![];
+        let entry_path = entry_path.to_string();
+        let bat_sonar = BatSonar::new_scanned(file_content, SonarResultType::Function);
         for result in bat_sonar.results {
             let function_type = if Self::assert_function_is_entrypoint(&entry_path, result.clone())?
             {
@@ -97,15 +114,33 @@ impl BatMetadataParser<FunctionMetadataType> for FunctionSourceCodeMetadata {
             );
             metadata_result.push(function_metadata);
         }
+        // entry points generated by macros are not detected as functions by the sonar
+        let program_lib_path = BatConfig::get_config()
+            .change_context(MetadataError)?
+            .program_lib_path;
+        if entry_path == program_lib_path {
+            let macro_entrypoints = EntrypointParser::get_program_entrypoints()
+                .change_context(MetadataError)?
+                .into_iter()
+                .filter(|entrypoint| entrypoint.is_macro_generated);
+            for macro_entrypoint in macro_entrypoints {
+                metadata_result.push(FunctionSourceCodeMetadata::new(
+                    entry_path.clone(),
+                    macro_entrypoint.name,
+                    FunctionMetadataType::EntryPoint,
+                    macro_entrypoint.start_line_index + 1,
+                    macro_entrypoint.end_line_index + 1,
+                    Self::create_metadata_id(),
+                ));
+            }
+        }
         // let bat_metadata = BatMetadata::read_metadata()?;
         // bat_metadata
         //     .source_code
         //     .update_functions(metadata_result.clone())?;
         Ok(metadata_result)
     }
-}
 
-impl FunctionSourceCodeMetadata {
     pub fn to_function_parser(&self) -> Result<FunctionParser, MetadataError> {
         FunctionParser::new_from_metadata(self.clone()).change_context(MetadataError)
     }
@@ -114,8 +149,12 @@ impl FunctionSourceCodeMetadata {
         entry_path: &str,
         sonar_result: SonarResult,
     ) -> MetadataResult<bool> {
-        let entrypoints_names = EntrypointParser::get_entrypoint_names(false).unwrap();
-        if entry_path == BatConfig::get_config().unwrap().program_lib_path {
+        let entrypoints_names =
+            EntrypointParser::get_entrypoint_names(false).change_context(MetadataError)?;
+        let program_lib_path = BatConfig::get_config()
+            .change_context(MetadataError)?
+            .program_lib_path;
+        if entry_path == program_lib_path {
             if entrypoints_names
                 .into_iter()
                 .any(|ep_name| ep_name == sonar_result.name)
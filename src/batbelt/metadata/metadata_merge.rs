@@ -0,0 +1,300 @@
+use crate::batbelt::metadata::{MetadataError, MetadataResult};
+use error_stack::{IntoReport, ResultExt};
+use serde_json::{Map, Value};
+use std::fs;
+
+pub const BAT_METADATA_MERGE_DRIVER_NAME: &str = "bat-metadata";
+// fields that identify the items of a vector, by priority
+pub const BAT_METADATA_MERGE_KEYS: [&str; 3] = ["entry_point_name", "metadata_id", "name"];
+
+/// A value changed differently on both sides, the merge keeps ours
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatMetadataConflict {
+    pub path: String,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+}
+
+impl BatMetadataConflict {
+    pub fn get_description(&self) -> String {
+        let value_to_string = |value: &Option<Value>| match value {
+            Some(value) => value.to_string(),
+            None => "deleted".to_string(),
+        };
+        format!(
+            "{}: ours {} - theirs {}",
+            self.path,
+            value_to_string(&self.ours),
+            value_to_string(&self.theirs)
+        )
+    }
+}
+
+/// Three-way merge of BatMetadata.json. Objects are merged by field and vectors by the first
+/// of BAT_METADATA_MERGE_KEYS their items have, so only the same item changed differently
+/// on both branches conflicts
+#[derive(Debug, Clone)]
+pub struct BatMetadataMerge {
+    pub merged: Value,
+    pub conflicts: Vec<BatMetadataConflict>,
+}
+
+impl BatMetadataMerge {
+    pub fn new_merged(base: &Value, ours: &Value, theirs: &Value) -> Self {
+        let mut metadata_merge = Self {
+            merged: Value::Null,
+            conflicts: vec![],
+        };
+        metadata_merge.merged = metadata_merge.merge_value("", Some(base), ours, theirs);
+        metadata_merge
+    }
+
+    /// Merges the files git passes to a merge driver, the result is written to ours_path
+    pub fn merge_files(
+        base_path: &str,
+        ours_path: &str,
+        theirs_path: &str,
+    ) -> MetadataResult<Self> {
+        let read_json = |path: &str| -> MetadataResult<Value> {
+            let content = fs::read_to_string(path)
+                .into_report()
+                .change_context(MetadataError)
+                .attach_printable(format!("Error reading {}", path))?;
+            // an empty base means the file was added on both branches
+            if content.trim().is_empty() {
+                return Ok(Value::Object(Map::new()));
+            }
+            serde_json::from_str(&content)
+                .into_report()
+                .change_context(MetadataError)
+                .attach_printable(format!("Error parsing {}", path))
+        };
+        let metadata_merge = Self::new_merged(
+            &read_json(base_path)?,
+            &read_json(ours_path)?,
+            &read_json(theirs_path)?,
+        );
+        let merged_content = serde_json::to_string_pretty(&metadata_merge.merged)
+            .into_report()
+            .change_context(MetadataError)?;
+        fs::write(ours_path, merged_content)
+            .into_report()
+            .change_context(MetadataError)?;
+        Ok(metadata_merge)
+    }
+
+    fn merge_value(
+        &mut self,
+        path: &str,
+        base: Option<&Value>,
+        ours: &Value,
+        theirs: &Value,
+    ) -> Value {
+        if ours == theirs || base == Some(theirs) {
+            return ours.clone();
+        }
+        if base == Some(ours) {
+            return theirs.clone();
+        }
+        match (ours, theirs) {
+            (Value::Object(ours_map), Value::Object(theirs_map)) => {
+                let base_entries = match base {
+                    Some(Value::Object(base_map)) => Self::map_to_entries(base_map),
+                    _ => vec![],
+                };
+                let merged_entries = self.merge_entries(
+                    path,
+                    ".",
+                    base_entries,
+                    Self::map_to_entries(ours_map),
+                    Self::map_to_entries(theirs_map),
+                );
+                Value::Object(merged_entries.into_iter().collect())
+            }
+            (Value::Array(ours_vec), Value::Array(theirs_vec)) => {
+                let base_vec = match base {
+                    Some(Value::Array(base_vec)) => base_vec.clone(),
+                    _ => vec![],
+                };
+                match Self::get_vector_key(&[&base_vec, ours_vec, theirs_vec]) {
+                    Some(key_name) => {
+                        let to_entries = |vector: &Vec<Value>| {
+                            vector
+                                .iter()
+                                .map(|item| {
+                                    (item[key_name].as_str().unwrap().to_string(), item.clone())
+                                })
+                                .collect::<Vec<_>>()
+                        };
+                        let merged_entries = self.merge_entries(
+                            path,
+                            "",
+                            to_entries(&base_vec),
+                            to_entries(ours_vec),
+                            to_entries(theirs_vec),
+                        );
+                        Value::Array(merged_entries.into_iter().map(|(_, item)| item).collect())
+                    }
+                    None => self.add_conflict(path, Some(ours), Some(theirs)),
+                }
+            }
+            _ => self.add_conflict(path, Some(ours), Some(theirs)),
+        }
+    }
+
+    /// Keeps the order of ours, with the entries only added by theirs at the end
+    fn merge_entries(
+        &mut self,
+        path: &str,
+        separator: &str,
+        base_entries: Vec<(String, Value)>,
+        ours_entries: Vec<(String, Value)>,
+        theirs_entries: Vec<(String, Value)>,
+    ) -> Vec<(String, Value)> {
+        let find_entry = |entries: &[(String, Value)], key: &str| {
+            entries
+                .iter()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value.clone())
+        };
+        let mut keys = ours_entries
+            .iter()
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        keys.extend(
+            theirs_entries
+                .iter()
+                .map(|(key, _)| key.clone())
+                .filter(|key| find_entry(&ours_entries, key).is_none()),
+        );
+        let mut merged_entries = vec![];
+        for key in keys {
+            let entry_path = if separator.is_empty() {
+                format!("{}[{}]", path, key)
+            } else if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}{}{}", path, separator, key)
+            };
+            let base_value = find_entry(&base_entries, &key);
+            let merged_value = match (
+                find_entry(&ours_entries, &key),
+                find_entry(&theirs_entries, &key),
+            ) {
+                (Some(ours_value), Some(theirs_value)) => Some(self.merge_value(
+                    &entry_path,
+                    base_value.as_ref(),
+                    &ours_value,
+                    &theirs_value,
+                )),
+                // deleted on one side and unchanged on the other
+                (Some(value), None) | (None, Some(value))
+                    if base_value.as_ref() == Some(&value) =>
+                {
+                    None
+                }
+                (Some(ours_value), None) => match base_value {
+                    None => Some(ours_value),
+                    Some(_) => Some(self.add_conflict(&entry_path, Some(&ours_value), None)),
+                },
+                (None, Some(theirs_value)) => match base_value {
+                    None => Some(theirs_value),
+                    // ours deleted it, keep it deleted but report the conflict
+                    Some(_) => {
+                        self.add_conflict(&entry_path, None, Some(&theirs_value));
+                        None
+                    }
+                },
+                (None, None) => None,
+            };
+            if let Some(merged_value) = merged_value {
+                merged_entries.push((key, merged_value));
+            }
+        }
+        merged_entries
+    }
+
+    fn add_conflict(&mut self, path: &str, ours: Option<&Value>, theirs: Option<&Value>) -> Value {
+        self.conflicts.push(BatMetadataConflict {
+            path: path.to_string(),
+            ours: ours.cloned(),
+            theirs: theirs.cloned(),
+        });
+        ours.cloned().unwrap_or(Value::Null)
+    }
+
+    /// The first key every item has as a unique string, None for vectors of plain values
+    fn get_vector_key(vectors: &[&Vec<Value>]) -> Option<&'static str> {
+        if vectors.iter().all(|vector| vector.is_empty()) {
+            return None;
+        }
+        BAT_METADATA_MERGE_KEYS.into_iter().find(|key_name| {
+            vectors.iter().all(|vector| {
+                let keys = vector
+                    .iter()
+                    .filter_map(|item| item.get(key_name).and_then(Value::as_str))
+                    .collect::<Vec<_>>();
+                keys.len() == vector.len()
+                    && keys
+                        .iter()
+                        .enumerate()
+                        .all(|(index, key)| !keys[index + 1..].contains(key))
+            })
+        })
+    }
+
+    fn map_to_entries(map: &Map<String, Value>) -> Vec<(String, Value)> {
+        map.iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+#[test]
+fn test_bat_metadata_three_way_merge() {
+    use serde_json::json;
+    let base = json!({
+        "initialized": true,
+        "entry_points": [{ "metadata_id": "1", "name": "deposit" }],
+        "miro": { "code_overhaul": [
+            { "entry_point_name": "deposit", "metadata_id": "a", "miro_frame_id": "", "images_deployed": false },
+            { "entry_point_name": "withdraw", "metadata_id": "b", "miro_frame_id": "", "images_deployed": false }
+        ]}
+    });
+    // sonar on one branch, miro on the other
+    let ours = json!({
+        "initialized": true,
+        "entry_points": [
+            { "metadata_id": "1", "name": "deposit" },
+            { "metadata_id": "2", "name": "withdraw" }
+        ],
+        "miro": { "code_overhaul": [
+            { "entry_point_name": "deposit", "metadata_id": "a", "miro_frame_id": "10", "images_deployed": false },
+            { "entry_point_name": "withdraw", "metadata_id": "b", "miro_frame_id": "20", "images_deployed": false }
+        ]}
+    });
+    let theirs = json!({
+        "initialized": true,
+        "entry_points": [{ "metadata_id": "1", "name": "deposit" }],
+        "miro": { "code_overhaul": [
+            { "entry_point_name": "deposit", "metadata_id": "a", "miro_frame_id": "", "images_deployed": true },
+            { "entry_point_name": "withdraw", "metadata_id": "b", "miro_frame_id": "21", "images_deployed": false },
+            { "entry_point_name": "swap", "metadata_id": "c", "miro_frame_id": "30", "images_deployed": false }
+        ]}
+    });
+    let metadata_merge = BatMetadataMerge::new_merged(&base, &ours, &theirs);
+    let merged = &metadata_merge.merged;
+    assert_eq!(merged["entry_points"].as_array().unwrap().len(), 2);
+    let co_metadata = merged["miro"]["code_overhaul"].as_array().unwrap();
+    assert_eq!(co_metadata.len(), 3);
+    assert_eq!(co_metadata[0]["miro_frame_id"], "10");
+    assert_eq!(co_metadata[0]["images_deployed"], true);
+    assert_eq!(co_metadata[2]["entry_point_name"], "swap");
+    // withdraw frame changed on both branches, ours is kept
+    assert_eq!(co_metadata[1]["miro_frame_id"], "20");
+    assert_eq!(metadata_merge.conflicts.len(), 1);
+    assert_eq!(
+        metadata_merge.conflicts[0].path,
+        "miro.code_overhaul[withdraw].miro_frame_id"
+    );
+}
@@ -66,9 +66,26 @@ pub struct MiroCodeOverhaulMetadata {
     pub validations_image_id: String,
     pub handler_image_id: String,
     pub signers: Vec<SignerInfo>,
+    /// screenshots of the mutable program accounts
+    #[serde(default)]
+    pub state_changes_image_ids: Vec<String>,
+    #[serde(default)]
+    pub handler_parameters_image_ids: Vec<String>,
 }
 
 impl MiroCodeOverhaulMetadata {
+    /// Forgets the deployed images and signers, so code-overhaul-screenshots deploys them again
+    pub fn reset_deployed_items(&mut self) {
+        self.images_deployed = false;
+        self.entry_point_image_id = "".to_string();
+        self.context_accounts_image_id = "".to_string();
+        self.validations_image_id = "".to_string();
+        self.handler_image_id = "".to_string();
+        self.signers = vec![];
+        self.state_changes_image_ids = vec![];
+        self.handler_parameters_image_ids = vec![];
+    }
+
     pub fn update_code_overhaul_metadata(&self) -> MetadataResult<()> {
         let mut bat_metadata = BatMetadata::read_metadata()?;
         let position = bat_metadata
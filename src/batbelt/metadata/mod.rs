@@ -3,6 +3,7 @@ pub mod entrypoint_metadata;
 pub mod enums_source_code_metadata;
 pub mod function_dependencies_metadata;
 pub mod functions_source_code_metadata;
+pub mod metadata_merge;
 pub mod miro_metadata;
 pub mod structs_source_code_metadata;
 pub mod trait_metadata;
@@ -35,14 +36,15 @@ use crate::batbelt::metadata::traits_source_code_metadata::{
 };
 use crate::batbelt::parser::parse_formatted_path;
 use crate::batbelt::parser::source_code_parser::SourceCodeParser;
-use crate::batbelt::BatEnumerator;
+use crate::batbelt::{BatEnumerator, ShareableData};
 use crate::Suggestion;
 use error_stack::{FutureExt, IntoReport, Report, Result, ResultExt};
 use rand::distributions::Alphanumeric;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
-use crate::batbelt::git::GitCommit;
+use crate::batbelt::git::commit_message::{BatCommitMessage, BatCommitType};
+use crate::batbelt::git::{GitAction, GitCommit};
 use crate::batbelt::metadata::enums_source_code_metadata::{
     EnumMetadataType, EnumSourceCodeMetadata,
 };
@@ -74,16 +76,13 @@ pub enum BatMetadataCommit {
 }
 
 impl BatMetadataCommit {
-    pub fn get_commit_message(&self) -> String {
-        match self {
-            BatMetadataCommit::RunSonarMetadataCommit => {
-                "metadata: bat-cli sonar executed".to_string()
-            }
-            BatMetadataCommit::MiroMetadataCommit => "metadata: miro metadata updated".to_string(),
-            BatMetadataCommit::UpdateMetadataVersion => {
-                "metadata: BatMetadata.json updated to last version".to_string()
-            }
-        }
+    pub fn get_commit_message(&self) -> BatCommitMessage {
+        let action = match self {
+            BatMetadataCommit::RunSonarMetadataCommit => "sonar executed",
+            BatMetadataCommit::MiroMetadataCommit => "miro metadata updated",
+            BatMetadataCommit::UpdateMetadataVersion => "updated to last version",
+        };
+        BatCommitMessage::new(BatCommitType::Metadata, "BatMetadata.json", action)
     }
 }
 
@@ -92,6 +91,8 @@ pub struct BatMetadata {
     // #[serde(default = "project_name_default")]
     #[serde(default)]
     pub project_name: String,
+    #[serde(default)]
+    pub program_id: String,
     pub initialized: bool,
     pub source_code: SourceCodeMetadata,
     pub entry_points: Vec<EntrypointMetadata>,
@@ -111,6 +112,7 @@ impl BatMetadata {
         let bat_config = BatConfig::get_config().unwrap();
         Self {
             project_name: bat_config.project_name,
+            program_id: "".to_string(),
             initialized: false,
             source_code: Default::default(),
             entry_points: vec![],
@@ -713,6 +715,82 @@ impl BatMetadataType {
         let metadata_type_selected = &metadata_types_vec[selection];
         Ok(*metadata_type_selected)
     }
+
+    /// Prompts the metadata type and the source code item, linking all its lines
+    pub fn prompt_source_code_location() -> MetadataResult<SourceCodeLocation> {
+        let (name, metadata_id, path, start_line_index, end_line_index) =
+            match Self::prompt_metadata_type_selection()? {
+                BatMetadataType::Struct => {
+                    let metadata = StructSourceCodeMetadata::prompt_selection()?;
+                    (
+                        metadata.name,
+                        metadata.metadata_id,
+                        metadata.path,
+                        metadata.start_line_index,
+                        metadata.end_line_index,
+                    )
+                }
+                BatMetadataType::Function => {
+                    let metadata = FunctionSourceCodeMetadata::prompt_selection()?;
+                    (
+                        metadata.name,
+                        metadata.metadata_id,
+                        metadata.path,
+                        metadata.start_line_index,
+                        metadata.end_line_index,
+                    )
+                }
+                BatMetadataType::Trait => {
+                    let metadata = TraitSourceCodeMetadata::prompt_selection()?;
+                    (
+                        metadata.name,
+                        metadata.metadata_id,
+                        metadata.path,
+                        metadata.start_line_index,
+                        metadata.end_line_index,
+                    )
+                }
+                BatMetadataType::Enum => {
+                    let metadata = EnumSourceCodeMetadata::prompt_selection()?;
+                    (
+                        metadata.name,
+                        metadata.metadata_id,
+                        metadata.path,
+                        metadata.start_line_index,
+                        metadata.end_line_index,
+                    )
+                }
+            };
+        let shared_permalink = ShareableData::new(String::new());
+        GitAction::GetRepositoryPermalink {
+            file_path: path.clone(),
+            start_line_index,
+            end_line_index: Some(end_line_index),
+            permalink: shared_permalink.original,
+        }
+        .execute_action()
+        .change_context(MetadataError)?;
+        let permalink = shared_permalink.cloned.borrow().clone();
+        Ok(SourceCodeLocation {
+            name,
+            metadata_id,
+            permalink,
+            path,
+            start_line_index,
+            end_line_index,
+        })
+    }
+}
+
+/// Source code item referenced from the notes, like a finding candidate or an open question
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceCodeLocation {
+    pub name: String,
+    pub metadata_id: MetadataId,
+    pub permalink: String,
+    pub path: String,
+    pub start_line_index: usize,
+    pub end_line_index: usize,
 }
 
 pub trait BatMetadataParser<U>
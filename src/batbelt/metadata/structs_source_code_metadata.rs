@@ -1,14 +1,14 @@
 use crate::batbelt::parser::entrypoint_parser::EntrypointParser;
+use crate::batbelt::parser::macro_parser::MacroParser;
+use crate::config::BatConfig;
 
 use std::fmt::Debug;
 
-use crate::batbelt;
-use crate::batbelt::path::BatFile;
-
 use crate::batbelt::metadata::{
     BatMetadataParser, BatMetadataType, MetadataId, SourceCodeMetadata,
 };
 
+use crate::batbelt::sonar::frontend::SourceLanguage;
 use crate::batbelt::sonar::{BatSonar, SonarResult, SonarResultType};
 use crate::batbelt::BatEnumerator;
 use error_stack::{Result, ResultExt};
@@ -78,17 +78,33 @@ impl BatMetadataParser<StructMetadataType> for StructSourceCodeMetadata {
     fn create_metadata_from_dir_entry(entry: DirEntry) -> Result<Vec<Self>, MetadataError> {
         let entry_path = entry.path().to_str().unwrap().to_string();
         let file_content = fs::read_to_string(entry.path()).unwrap();
-        let bat_sonar = BatSonar::new_scanned(&file_content, SonarResultType::Struct);
+        match SourceLanguage::from_path(&entry_path) {
+            None => Ok(vec![]),
+            Some(source_language) => source_language
+                .get_frontend()
+                .get_structs(&entry_path, &file_content)
+                .change_context(MetadataError),
+        }
+    }
+}
+
+impl StructSourceCodeMetadata {
+    pub fn get_rust_structs(
+        entry_path: &str,
+        file_content: &str,
+    ) -> Result<Vec<Self>, MetadataError> {
+        let entry_path = entry_path.to_string();
+        let bat_sonar = BatSonar::new_scanned(file_content, SonarResultType::Struct);
         let mut metadata_result = vec![];
         for result in bat_sonar.results {
-            let struct_type =
-                if Self::assert_struct_is_solana_account(&file_content, result.clone()) {
-                    StructMetadataType::SolanaAccount
-                } else if Self::assert_struct_is_context_accounts(&file_content, result.clone())? {
-                    StructMetadataType::ContextAccounts
-                } else {
-                    StructMetadataType::Other
-                };
+            let struct_type = if Self::assert_struct_is_solana_account(file_content, result.clone())
+            {
+                StructMetadataType::SolanaAccount
+            } else if Self::assert_struct_is_context_accounts(file_content, result.clone())? {
+                StructMetadataType::ContextAccounts
+            } else {
+                StructMetadataType::Other
+            };
             let struct_metadata = StructSourceCodeMetadata::new(
                 entry_path.clone(),
                 result.name.to_string(),
@@ -106,24 +122,21 @@ impl BatMetadataParser<StructMetadataType> for StructSourceCodeMetadata {
 
         Ok(metadata_result)
     }
-}
 
-impl StructSourceCodeMetadata {
     fn assert_struct_is_context_accounts(
         file_info_content: &str,
         sonar_result: SonarResult,
     ) -> Result<bool, MetadataError> {
-        if sonar_result.start_line_index > 0 {
-            let previous_line =
-                file_info_content.lines().collect::<Vec<_>>()[sonar_result.start_line_index - 1];
-            let filtered_previous_line = previous_line
-                .trim()
-                .trim_end_matches(")]")
-                .trim_start_matches("#[derive(");
-            let mut tokenized = filtered_previous_line.split(", ");
-            if tokenized.any(|token| token == "Acccounts") {
-                return Ok(true);
-            }
+        // #[derive(Accounts)] (or a registered macro) is authoritative when the struct has attributes
+        let context_accounts_macros = BatConfig::get_config()
+            .change_context(MetadataError)?
+            .context_accounts_macros;
+        if let Some(is_context_accounts) = MacroParser::struct_derives_accounts(
+            file_info_content,
+            sonar_result.start_line_index,
+            &context_accounts_macros,
+        ) {
+            return Ok(is_context_accounts);
         }
         let context_accounts_content = vec![
             "Signer<",
@@ -137,18 +150,10 @@ impl StructSourceCodeMetadata {
         {
             return Ok(true);
         }
-        let lib_file_path = batbelt::path::get_file_path(BatFile::ProgramLib, false)
-            .change_context(MetadataError)?;
-        let entrypoints = BatSonar::new_from_path(
-            &lib_file_path,
-            Some("#[program]"),
-            SonarResultType::Function,
-        );
-        let mut entrypoints_context_accounts_names = entrypoints
-            .results
-            .iter()
-            .map(|result| EntrypointParser::get_context_name(&result.name).unwrap());
-        if entrypoints_context_accounts_names.any(|name| name == sonar_result.name) {
+        if EntrypointParser::get_all_contexts_names()
+            .into_iter()
+            .any(|name| name == sonar_result.name)
+        {
             return Ok(true);
         }
         Ok(false)
@@ -0,0 +1,280 @@
+use crate::batbelt::miro::{MiroApiResult, MiroConfig, MiroError, MiroResult};
+use error_stack::{IntoReport, ResultExt};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, RETRY_AFTER};
+use reqwest::multipart::{Form, Part};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+pub const MIRO_API_BASE_URL: &str = "https://api.miro.com";
+pub const MIRO_MAX_RETRIES: u32 = 3;
+pub const MIRO_INITIAL_BACKOFF_MILLIS: u64 = 500;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MiroPosition {
+    pub x: f64,
+    pub y: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+}
+
+impl MiroPosition {
+    pub fn new_centered(x_position: i64, y_position: i64) -> Self {
+        Self {
+            x: x_position as f64,
+            y: y_position as f64,
+            origin: Some("center".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MiroGeometry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MiroParent {
+    pub id: String,
+}
+
+/// Body to move an item, or to attach it to a frame
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MiroItemUpdateRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<MiroParent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<MiroPosition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geometry: Option<MiroGeometry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MiroItemResponse {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub item_type: String,
+    #[serde(default)]
+    pub data: Value,
+    pub geometry: Option<MiroGeometry>,
+    pub position: Option<MiroPosition>,
+    pub parent: Option<MiroParent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MiroItemsPage {
+    pub data: Vec<MiroItemResponse>,
+    pub size: i64,
+    pub limit: i64,
+    pub cursor: Option<String>,
+}
+
+/// Single entry point to the Miro REST API, the base url can point to a local mock server
+#[derive(Debug, Clone)]
+pub struct MiroClient {
+    pub base_url: String,
+    pub board_id: String,
+    access_token: String,
+    http_client: reqwest::Client,
+    max_retries: u32,
+    initial_backoff: Duration,
+}
+
+impl MiroClient {
+    pub fn new() -> MiroResult<Self> {
+        let MiroConfig {
+            access_token,
+            board_id,
+            api_base_url,
+            ..
+        } = MiroConfig::new()?;
+        Ok(Self::new_with_base_url(
+            &api_base_url,
+            &access_token,
+            &board_id,
+        ))
+    }
+
+    pub fn new_with_base_url(base_url: &str, access_token: &str, board_id: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            board_id: board_id.to_string(),
+            access_token: access_token.to_string(),
+            http_client: reqwest::Client::new(),
+            max_retries: MIRO_MAX_RETRIES,
+            initial_backoff: Duration::from_millis(MIRO_INITIAL_BACKOFF_MILLIS),
+        }
+    }
+
+    pub fn with_retries(mut self, max_retries: u32, initial_backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    pub fn get_board_url(&self, path: &str) -> String {
+        format!(
+            "{}/v2/boards/{}/{}",
+            self.base_url,
+            self.board_id,
+            path.trim_start_matches('/')
+        )
+    }
+
+    pub async fn get(&self, path: &str) -> MiroApiResult {
+        let url = self.get_board_url(path);
+        self.send_with_retry(true, || {
+            self.http_client
+                .get(&url)
+                .header(CONTENT_TYPE, "application/json")
+        })
+        .await
+    }
+
+    pub async fn post_json<T: Serialize>(&self, path: &str, body: &T) -> MiroApiResult {
+        let url = self.get_board_url(path);
+        let body = Self::serialize_body(body)?;
+        self.send_with_retry(false, || {
+            self.http_client
+                .post(&url)
+                .body(body.clone())
+                .header(CONTENT_TYPE, "application/json")
+        })
+        .await
+    }
+
+    pub async fn patch_json<T: Serialize>(&self, path: &str, body: &T) -> MiroApiResult {
+        let url = self.get_board_url(path);
+        let body = Self::serialize_body(body)?;
+        self.send_with_retry(true, || {
+            self.http_client
+                .patch(&url)
+                .body(body.clone())
+                .header(CONTENT_TYPE, "application/json")
+        })
+        .await
+    }
+
+    pub async fn delete(&self, path: &str) -> MiroApiResult {
+        let url = self.get_board_url(path);
+        self.send_with_retry(true, || self.http_client.delete(&url))
+            .await
+    }
+
+    /// Uploads a file as the resource of a multipart form, like the images do
+    pub async fn post_file(&self, path: &str, file_path: &str) -> MiroApiResult {
+        let url = self.get_board_url(path);
+        let (file_name, file_content) = Self::read_resource_file(file_path)?;
+        self.send_with_retry(false, || {
+            self.http_client
+                .post(&url)
+                .multipart(Self::get_resource_form(&file_name, &file_content))
+        })
+        .await
+    }
+
+    pub async fn patch_file(&self, path: &str, file_path: &str) -> MiroApiResult {
+        let url = self.get_board_url(path);
+        let (file_name, file_content) = Self::read_resource_file(file_path)?;
+        self.send_with_retry(true, || {
+            self.http_client
+                .patch(&url)
+                .multipart(Self::get_resource_form(&file_name, &file_content))
+        })
+        .await
+    }
+
+    pub async fn parse_response<T: DeserializeOwned>(response: reqwest::Response) -> MiroResult<T> {
+        let response_string = response
+            .text()
+            .await
+            .into_report()
+            .change_context(MiroError)?;
+        serde_json::from_str(&response_string)
+            .into_report()
+            .change_context(MiroError)
+            .attach_printable(format!(
+                "Unexpected response from Miro:\n{}",
+                response_string
+            ))
+    }
+
+    /// Sends the request, retrying with exponential backoff on 429 responses, and on 5xx
+    /// responses only if it's idempotent: a POST that failed with 5xx could have created the item
+    /// anyway. The request is built again on every attempt, since a multipart body can't be cloned
+    async fn send_with_retry<F>(&self, is_idempotent: bool, build_request: F) -> MiroApiResult
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 0;
+        loop {
+            let response = MiroConfig::parse_response_from_miro(
+                build_request()
+                    .header(AUTHORIZATION, format!("Bearer {}", self.access_token))
+                    .send()
+                    .await,
+            )?;
+            let status = response.status();
+            let should_retry = status == StatusCode::TOO_MANY_REQUESTS
+                || (is_idempotent && status.is_server_error());
+            if !should_retry || attempt >= self.max_retries {
+                return Ok(response);
+            }
+            let wait = Self::get_retry_after(&response).unwrap_or(backoff);
+            log::warn!(
+                "Miro responded {}, retrying in {:?} ({}/{})",
+                status,
+                wait,
+                attempt + 1,
+                self.max_retries
+            );
+            tokio::time::sleep(wait).await;
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+
+    fn get_retry_after(response: &reqwest::Response) -> Option<Duration> {
+        response
+            .headers()
+            .get(RETRY_AFTER)?
+            .to_str()
+            .ok()?
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .map(Duration::from_secs)
+    }
+
+    fn serialize_body<T: Serialize>(body: &T) -> MiroResult<String> {
+        serde_json::to_string(body)
+            .into_report()
+            .change_context(MiroError)
+    }
+
+    fn read_resource_file(file_path: &str) -> MiroResult<(String, Vec<u8>)> {
+        let file_name = Path::new(file_path)
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .unwrap_or(file_path)
+            .to_string();
+        let file_content = fs::read(file_path)
+            .into_report()
+            .change_context(MiroError)
+            .attach_printable(format!("Error reading file in {}", file_path))?;
+        Ok((file_name, file_content))
+    }
+
+    fn get_resource_form(file_name: &str, file_content: &[u8]) -> Form {
+        let resource = Part::bytes(file_content.to_vec()).file_name(file_name.to_string());
+        Form::new().part("resource", resource)
+    }
+}
@@ -1,5 +1,4 @@
-use crate::batbelt::miro::MiroConfig;
-use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+use crate::batbelt::miro::client::MiroClient;
 use serde_json::json;
 
 use super::MiroApiResult;
@@ -16,11 +15,7 @@ pub async fn create_connector(
     end_item_id: &str,
     connect_options: Option<ConnectorOptions>,
 ) -> MiroApiResult {
-    let MiroConfig {
-        access_token,
-        board_id,
-        ..
-    } = MiroConfig::new()?;
+    let miro_client = MiroClient::new()?;
     let body = if let Some(options) = connect_options {
         let ConnectorOptions {
             start_x_position,
@@ -48,7 +43,6 @@ pub async fn create_connector(
             },
            "shape": "elbowed"
         })
-        .to_string()
     } else {
         json!({
             "startItem": {
@@ -62,17 +56,6 @@ pub async fn create_connector(
            },
            "shape": "elbowed"
         })
-        .to_string()
     };
-    let client = reqwest::Client::new();
-    let response = client
-        .post(format!(
-            "https://api.miro.com/v2/boards/{board_id}/connectors",
-        ))
-        .body(body)
-        .header(CONTENT_TYPE, "application/json")
-        .header(AUTHORIZATION, format!("Bearer {access_token}"))
-        .send()
-        .await;
-    MiroConfig::parse_response_from_miro(response)
+    miro_client.post_json("connectors", &body).await
 }
@@ -1,10 +1,14 @@
 use super::*;
 use crate::batbelt::bat_dialoguer::BatDialoguer;
 
+use crate::batbelt::miro::client::{MiroClient, MiroItemResponse};
+use crate::batbelt::miro::layout::MiroFrameLayout;
 use crate::batbelt::miro::MiroItemType;
+use crate::batbelt::BatEnumerator;
 use colored::Colorize;
 use error_stack::{IntoReport, Result};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 pub const MIRO_FRAME_WIDTH: u64 = 5600;
@@ -13,33 +17,54 @@ pub const MIRO_BOARD_COLUMNS: i64 = 5;
 pub const MIRO_INITIAL_X: i64 = 4800;
 pub const MIRO_INITIAL_Y: i64 = 0;
 
-#[derive(Debug, Clone)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    strum_macros::Display,
+    strum_macros::EnumIter,
+)]
 pub enum MiroCodeOverhaulConfig {
     EntryPoint,
     ContextAccount,
     Validations,
     Handler,
+    /// the first signer, the next ones are placed below
+    Signers,
+    /// the mutable program accounts
+    StateChanges,
+    HandlerParameters,
 }
 
+impl BatEnumerator for MiroCodeOverhaulConfig {}
+
 impl MiroCodeOverhaulConfig {
+    /// Position of the content in the frame, from the layout of the Bat.toml
     pub fn get_positions(&self) -> (i64, i64) {
+        let frame_layout = MiroFrameLayout::get_layout().unwrap_or_else(|error| {
+            log::warn!(
+                "Error reading the Miro frame layout, using the default:\n{:?}",
+                error
+            );
+            MiroFrameLayout::default()
+        });
+        frame_layout.get_position(self)
+    }
+
+    pub fn get_default_positions(&self, frame_width: u64, frame_height: u64) -> (i64, i64) {
+        let (frame_width, frame_height) = (frame_width as i64, frame_height as i64);
         match self {
-            MiroCodeOverhaulConfig::EntryPoint => (
-                MIRO_FRAME_WIDTH as i64 * 3 / 10,
-                (MIRO_FRAME_HEIGHT as i64) / 10,
-            ),
-            MiroCodeOverhaulConfig::ContextAccount => (
-                MIRO_FRAME_WIDTH as i64 * 6 / 10,
-                (MIRO_FRAME_HEIGHT as i64) / 4,
-            ),
-            MiroCodeOverhaulConfig::Validations => (
-                MIRO_FRAME_WIDTH as i64 * 10 / 12,
-                (MIRO_FRAME_HEIGHT as i64) / 4,
-            ),
-            MiroCodeOverhaulConfig::Handler => (
-                MIRO_FRAME_WIDTH as i64 * 10 / 12,
-                (MIRO_FRAME_HEIGHT as i64) * 3 / 4,
-            ),
+            MiroCodeOverhaulConfig::EntryPoint => (frame_width * 3 / 10, frame_height / 10),
+            MiroCodeOverhaulConfig::ContextAccount => (frame_width * 6 / 10, frame_height / 4),
+            MiroCodeOverhaulConfig::Validations => (frame_width * 10 / 12, frame_height / 4),
+            MiroCodeOverhaulConfig::Handler => (frame_width * 10 / 12, frame_height * 3 / 4),
+            MiroCodeOverhaulConfig::Signers => (550, 150),
+            MiroCodeOverhaulConfig::StateChanges | MiroCodeOverhaulConfig::HandlerParameters => {
+                (0, frame_height)
+            }
         }
     }
 }
@@ -134,58 +159,25 @@ impl MiroFrame {
         Ok(())
     }
 
-    pub async fn get_frames_from_miro() -> Result<Vec<MiroFrame>, MiroError> {
-        let response = MiroItem::get_items_on_board(Some(MiroItemType::Frame), None)
-            .await
-            .ok()
-            .ok_or(MiroError)
-            .into_report()?;
-        let response_string = response.text().await.ok().ok_or(MiroError).into_report()?;
-        let response: Value = serde_json::from_str(response_string.as_str())
+    pub fn new_from_item_response(item_response: MiroItemResponse) -> Result<Self, MiroError> {
+        let frame_value = serde_json::to_value(item_response)
+            .into_report()
+            .change_context(MiroError)?;
+        let mut new_frame = Self::new_empty();
+        new_frame
+            .parse_value(frame_value)
             .ok()
             .ok_or(MiroError)
             .into_report()?;
-        debug!("MiroItem::get_items_on_board:\n {}", response);
-        let mut data: Vec<Value> = response["data"]
-            .as_array()
-            .ok_or(MiroError)
-            .into_report()?
-            .clone();
-        let mut size = response["size"].as_i64().ok_or(MiroError).into_report()?;
-        let mut limit = response["limit"].as_i64().ok_or(MiroError).into_report()?;
-        while limit == size {
-            let cursor = response["cursor"].as_str().ok_or(MiroError).into_report()?;
-            let response =
-                MiroItem::get_items_on_board(Some(MiroItemType::Frame), Some(cursor.to_string()))
-                    .await
-                    .ok()
-                    .ok_or(MiroError)
-                    .into_report()?;
-            let response_string = response.text().await.ok().ok_or(MiroError).into_report()?;
-            let response: Value = serde_json::from_str(response_string.as_str())
-                .ok()
-                .ok_or(MiroError)
-                .into_report()?;
-            let mut cursor_data: Vec<Value> = response["data"]
-                .as_array()
-                .ok_or(MiroError)
-                .into_report()?
-                .clone();
-            data.append(&mut cursor_data);
-            size = response["size"].as_i64().ok_or(MiroError).into_report()?;
-            limit = response["limit"].as_i64().ok_or(MiroError).into_report()?;
-        }
-        let mut frames = vec![];
-        for frame_data in data {
-            let mut new_frame = Self::new_empty();
-            new_frame
-                .parse_value(frame_data)
-                .ok()
-                .ok_or(MiroError)
-                .into_report()?;
-            frames.push(new_frame);
-        }
-        Ok(frames)
+        Ok(new_frame)
+    }
+
+    pub async fn get_frames_from_miro() -> Result<Vec<MiroFrame>, MiroError> {
+        MiroItem::get_all_items_on_board(Some(MiroItemType::Frame))
+            .await?
+            .into_iter()
+            .map(Self::new_from_item_response)
+            .collect()
     }
 
     pub async fn get_items_within_frame(&self) -> Result<Vec<MiroObject>, MiroError> {
@@ -261,6 +253,7 @@ impl MiroFrame {
 
 mod api {
     use super::*;
+    use crate::batbelt::miro::client::{MiroItemUpdateRequest, MiroPosition};
 
     // returns the frame url
     pub async fn create_frame(
@@ -270,17 +263,11 @@ mod api {
         width: u64,
         height: u64,
     ) -> MiroApiResult {
-        let MiroConfig {
-            access_token,
-            board_id,
-            ..
-        } = MiroConfig::new()?;
-        let client = reqwest::Client::new();
-
-        let response = client
-            .post(format!("https://api.miro.com/v2/boards/{board_id}/frames"))
-            .body(
-                json!({
+        let miro_client = MiroClient::new()?;
+        miro_client
+            .post_json(
+                "frames",
+                &json!({
                      "data": {
                           "format": "custom",
                           "title": frame_title,
@@ -295,14 +282,9 @@ mod api {
                         "width": width,
                         "height": height
                    }
-                })
-                .to_string(),
+                }),
             )
-            .header(CONTENT_TYPE, "application/json")
-            .header(AUTHORIZATION, format!("Bearer {access_token}"))
-            .send()
-            .await;
-        MiroConfig::parse_response_from_miro(response)
+            .await
     }
 
     // returns the frame url
@@ -375,49 +357,22 @@ mod api {
         x_position: i64,
         y_position: i64,
     ) -> MiroApiResult {
-        let MiroConfig {
-            access_token,
-            board_id,
-            ..
-        } = MiroConfig::new()?;
-        let client = reqwest::Client::new();
-        let response = client
-            .patch(format!(
-                "https://api.miro.com/v2/boards/{board_id}/frames/{frame_id}",
-            ))
-            .body(
-                json!({
-                    "position": {
-                        "x": x_position,
-                        "y": y_position,
-                        "origin": "center",
-                    },
-                })
-                .to_string(),
-            )
-            .header(CONTENT_TYPE, "application/json")
-            .header(AUTHORIZATION, format!("Bearer {access_token}"))
-            .send()
-            .await;
-        MiroConfig::parse_response_from_miro(response)
+        let miro_client = MiroClient::new()?;
+        let body = MiroItemUpdateRequest {
+            parent: None,
+            position: Some(MiroPosition::new_centered(x_position, y_position)),
+            geometry: None,
+        };
+        miro_client
+            .patch_json(&format!("frames/{frame_id}"), &body)
+            .await
     }
 
     pub async fn get_items_within_frame(frame_id: &str) -> MiroApiResult {
-        let MiroConfig {
-            access_token,
-            board_id,
-            ..
-        } = MiroConfig::new()?;
-        let client = reqwest::Client::new();
-        let response = client
-            .get(format!(
-                "https://api.miro.com/v2/boards/{board_id}/items?parent_item_id={frame_id}"
-            ))
-            .header(CONTENT_TYPE, "application/json")
-            .header(AUTHORIZATION, format!("Bearer {access_token}"))
-            .send()
-            .await;
-        MiroConfig::parse_response_from_miro(response)
+        let miro_client = MiroClient::new()?;
+        miro_client
+            .get(&format!("items?parent_item_id={frame_id}"))
+            .await
     }
 
     // pub async fn update_frame_position(
@@ -1,16 +1,8 @@
 use crate::batbelt::miro::item::MiroItem;
-use crate::batbelt::miro::{MiroConfig, MiroItemType};
+use crate::batbelt::miro::MiroItemType;
 
-use error_stack::{FutureExt, IntoReport, Result, ResultExt};
-use reqwest;
-use reqwest::header::CONTENT_TYPE;
-use reqwest::{
-    header::AUTHORIZATION,
-    multipart::{self, Form},
-};
+use error_stack::{IntoReport, Result, ResultExt};
 use serde_json::*;
-use tokio::fs::File;
-use tokio_util::codec::{BytesCodec, FramedRead};
 
 use super::MiroError;
 
@@ -161,40 +153,24 @@ impl MiroImage {
         self.x_position = x_position;
         self.y_position = y_position;
     }
+    /// Resizes the image keeping its aspect ratio, the height is updated from the response
+    pub async fn update_width(&mut self, width: u64) -> Result<(), MiroError> {
+        let api_response = api::update_image_width(&self.item_id, width).await?;
+        self.parse_api_response(api_response).await
+    }
 }
 
 mod api {
 
+    use crate::batbelt::miro::client::{
+        MiroClient, MiroGeometry, MiroItemUpdateRequest, MiroParent, MiroPosition,
+    };
     use crate::batbelt::miro::MiroApiResult;
 
     use super::*;
     pub async fn create_image_from_device(file_path: &str) -> MiroApiResult {
-        let MiroConfig {
-            access_token,
-            board_id,
-            ..
-        } = MiroConfig::new()?;
-        let file_name = file_path.split('/').last().unwrap().to_string();
-        let file = File::open(file_path).await.unwrap();
-        // read file body stream
-        let stream = FramedRead::new(file, BytesCodec::new());
-        let file_body = reqwest::Body::wrap_stream(stream);
-
-        //make form part of file
-        let some_file = multipart::Part::stream(file_body)
-            .file_name(file_name.clone())
-            .mime_str("text/plain")
-            .unwrap();
-        //create the multipart form
-        let form = Form::new().part("resource", some_file);
-        let client = reqwest::Client::new();
-        let response = client
-            .post(format!("https://api.miro.com/v2/boards/{board_id}/images"))
-            .multipart(form)
-            .header(AUTHORIZATION, format!("Bearer {access_token}"))
-            .send()
-            .await;
-        MiroConfig::parse_response_from_miro(response)
+        let miro_client = MiroClient::new()?;
+        miro_client.post_file("images", file_path).await
     }
     pub async fn create_image_item_using_url(
         source_url: &str,
@@ -203,16 +179,11 @@ mod api {
         y_position: i64,
         height: u64,
     ) -> MiroApiResult {
-        let MiroConfig {
-            access_token,
-            board_id,
-            ..
-        } = MiroConfig::new()?;
-        let client = reqwest::Client::new();
-        let response = client
-            .post(format!("https://api.miro.com/v2/boards/{board_id}/images"))
-            .body(
-                json!({
+        let miro_client = MiroClient::new()?;
+        miro_client
+            .post_json(
+                "images",
+                &json!({
                     "data": {
                         "url": source_url
                    },
@@ -227,45 +198,16 @@ mod api {
                    "parent": {
                         "id": parent_id
                    }
-                })
-                .to_string(),
+                }),
             )
-            .header(CONTENT_TYPE, "application/json")
-            .header(AUTHORIZATION, format!("Bearer {access_token}"))
-            .send()
-            .await;
-        MiroConfig::parse_response_from_miro(response)
+            .await
     }
 
     pub async fn update_image_from_device(file_path: &str, item_id: &str) -> MiroApiResult {
-        let MiroConfig {
-            access_token,
-            board_id,
-            ..
-        } = MiroConfig::new()?;
-        let file_name = file_path.clone().split('/').last().unwrap().to_string();
-        let file = File::open(file_path.clone()).await.unwrap();
-        // read file body stream
-        let stream = FramedRead::new(file, BytesCodec::new());
-        let file_body = reqwest::Body::wrap_stream(stream);
-
-        //make form part of file
-        let some_file = multipart::Part::stream(file_body)
-            .file_name(file_name.clone())
-            .mime_str("text/plain")
-            .unwrap();
-        //create the multipart form
-        let form = Form::new().part("resource", some_file);
-        let client = reqwest::Client::new();
-        let response = client
-            .patch(format!(
-                "https://api.miro.com/v2/boards/{board_id}/images/{item_id}"
-            ))
-            .multipart(form)
-            .header(AUTHORIZATION, format!("Bearer {access_token}"))
-            .send()
-            .await;
-        MiroConfig::parse_response_from_miro(response)
+        let miro_client = MiroClient::new()?;
+        miro_client
+            .patch_file(&format!("images/{item_id}"), file_path)
+            .await
     }
 
     // // uploads the image in file_path to the board
@@ -315,33 +257,30 @@ mod api {
         x_position: i64,
         y_position: i64,
     ) -> MiroApiResult {
-        let MiroConfig {
-            access_token,
-            board_id,
-            ..
-        } = MiroConfig::new()?;
-        let client = reqwest::Client::new();
-        let response = client
-            .patch(format!(
-                "https://api.miro.com/v2/boards/{board_id}/images/{item_id}",
-            ))
-            .body(
-                json!({
-                    "parent": {
-                        "id": parent_id
-                    },
-                    "position": {
-                        "x": x_position,
-                        "y": y_position,
-                        "origin": "center",
-                    },
-                })
-                .to_string(),
-            )
-            .header(CONTENT_TYPE, "application/json")
-            .header(AUTHORIZATION, format!("Bearer {access_token}"))
-            .send()
-            .await;
-        MiroConfig::parse_response_from_miro(response)
+        let miro_client = MiroClient::new()?;
+        let body = MiroItemUpdateRequest {
+            parent: Some(MiroParent {
+                id: parent_id.to_string(),
+            }),
+            position: Some(MiroPosition::new_centered(x_position, y_position)),
+            geometry: None,
+        };
+        miro_client
+            .patch_json(&format!("images/{item_id}"), &body)
+            .await
+    }
+    pub async fn update_image_width(item_id: &str, width: u64) -> MiroApiResult {
+        let miro_client = MiroClient::new()?;
+        let body = MiroItemUpdateRequest {
+            parent: None,
+            position: None,
+            geometry: Some(MiroGeometry {
+                width: Some(width as f64),
+                height: None,
+            }),
+        };
+        miro_client
+            .patch_json(&format!("images/{item_id}"), &body)
+            .await
     }
 }
@@ -1,7 +1,8 @@
-use crate::batbelt::miro::{MiroConfig, MiroItemType};
+use crate::batbelt::miro::client::{
+    MiroClient, MiroItemResponse, MiroItemUpdateRequest, MiroItemsPage, MiroParent, MiroPosition,
+};
+use crate::batbelt::miro::MiroItemType;
 use error_stack::Result;
-use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
-use serde_json::json;
 
 use super::MiroError;
 use crate::batbelt::miro::MiroApiResult;
@@ -60,9 +61,32 @@ impl MiroItem {
         api::get_items_on_board(miro_item_type, cursor).await
     }
 
+    /// Every item of the board, or of the type if provided, going through all the pages
+    pub async fn get_all_items_on_board(
+        miro_item_type: Option<MiroItemType>,
+    ) -> Result<Vec<MiroItemResponse>, MiroError> {
+        let mut items = vec![];
+        let mut cursor = None;
+        loop {
+            let response = Self::get_items_on_board(miro_item_type.clone(), cursor).await?;
+            let items_page: MiroItemsPage = MiroClient::parse_response(response).await?;
+            log::debug!("MiroItem::get_items_on_board:\n {:#?}", items_page);
+            let is_last_page = items_page.size < items_page.limit || items_page.cursor.is_none();
+            cursor = items_page.cursor;
+            items.extend(items_page.data);
+            if is_last_page {
+                return Ok(items);
+            }
+        }
+    }
+
     pub async fn get_specific_item_on_board(item_id: &str) -> Result<reqwest::Response, MiroError> {
         api::get_specific_item_on_board(item_id).await
     }
+
+    pub async fn delete_item_on_board(item_id: &str) -> Result<reqwest::Response, MiroError> {
+        api::delete_item_on_board(item_id).await
+    }
 }
 
 mod api {
@@ -74,93 +98,42 @@ mod api {
         x_position: i64,
         y_position: i64,
     ) -> MiroApiResult {
-        let MiroConfig {
-            access_token,
-            board_id,
-            ..
-        } = MiroConfig::new()?;
-        // let started_file_path
-        // let x_position = x + x_move;
-        let client = reqwest::Client::new();
-        let response = client
-            .patch(format!(
-                "https://api.miro.com/v2/boards/{board_id}/items/{item_id}",
-            ))
-            .body(
-                json!({
-                    "parent": {
-                        "id": parent_id
-                    },
-                    "position": {
-                        "x": x_position,
-                        "y": y_position,
-                        "origin": "center",
-                    },
-                })
-                .to_string(),
-            )
-            .header(CONTENT_TYPE, "application/json")
-            .header(AUTHORIZATION, format!("Bearer {access_token}"))
-            .send()
-            .await;
-        MiroConfig::parse_response_from_miro(response)
+        let miro_client = MiroClient::new()?;
+        let body = MiroItemUpdateRequest {
+            parent: Some(MiroParent {
+                id: parent_id.to_string(),
+            }),
+            position: Some(MiroPosition::new_centered(x_position, y_position)),
+            geometry: None,
+        };
+        miro_client
+            .patch_json(&format!("items/{item_id}"), &body)
+            .await
     }
 
     pub async fn get_items_on_board(
         miro_item_type: Option<MiroItemType>,
         cursor: Option<String>,
     ) -> MiroApiResult {
-        let MiroConfig {
-            access_token,
-            board_id,
-            ..
-        } = MiroConfig::new()?;
-        let client = reqwest::Client::new();
-        let url = if let Some(item_type) = miro_item_type {
-            let item_type_string = item_type.to_string();
-            format!(
-                "https://api.miro.com/v2/boards/{board_id}/items?limit=50&type={}{}",
-                item_type_string,
-                if cursor.is_some() {
-                    format!("&cursor={}", cursor.unwrap())
-                } else {
-                    "".to_string()
-                }
-            )
-        } else {
-            format!(
-                "https://api.miro.com/v2/boards/{board_id}/items?limit=50{}",
-                if cursor.is_some() {
-                    format!("&cursor={}", cursor.unwrap())
-                } else {
-                    "".to_string()
-                }
-            )
-        };
-        let response = client
-            .get(url)
-            .header(CONTENT_TYPE, "application/json")
-            .header(AUTHORIZATION, format!("Bearer {access_token}"))
-            .send()
-            .await;
-        MiroConfig::parse_response_from_miro(response)
+        let miro_client = MiroClient::new()?;
+        let type_query = miro_item_type
+            .map(|item_type| format!("&type={}", item_type.to_string()))
+            .unwrap_or_default();
+        let cursor_query = cursor
+            .map(|cursor| format!("&cursor={}", cursor))
+            .unwrap_or_default();
+        miro_client
+            .get(&format!("items?limit=50{type_query}{cursor_query}"))
+            .await
     }
 
     pub async fn get_specific_item_on_board(item_id: &str) -> MiroApiResult {
-        let MiroConfig {
-            access_token,
-            board_id,
-            ..
-        } = MiroConfig::new()?;
-        let client = reqwest::Client::new();
-        let response = client
-            .get(format!(
-                "https://api.miro.com/v2/boards/{board_id}/items/{item_id}"
-            ))
-            .header(CONTENT_TYPE, "application/json")
-            .header(AUTHORIZATION, format!("Bearer {access_token}"))
-            .send()
-            .await;
-        MiroConfig::parse_response_from_miro(response)
+        let miro_client = MiroClient::new()?;
+        miro_client.get(&format!("items/{item_id}")).await
+    }
+
+    pub async fn delete_item_on_board(item_id: &str) -> MiroApiResult {
+        let miro_client = MiroClient::new()?;
+        miro_client.delete(&format!("items/{item_id}")).await
     }
 }
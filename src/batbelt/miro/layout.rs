@@ -0,0 +1,196 @@
+use crate::batbelt::miro::frame::{MiroCodeOverhaulConfig, MIRO_FRAME_HEIGHT, MIRO_FRAME_WIDTH};
+use crate::batbelt::miro::{MiroError, MiroResult};
+use crate::config::BatConfig;
+use error_stack::{IntoReport, ResultExt};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+pub const MIRO_LAYOUT_MARGIN: i64 = 100;
+pub const MIRO_SIGNER_SPACING: i64 = 270;
+pub const MIRO_SIGNER_WIDTH: u64 = 374;
+// distance from the signer sticky note to the user figure on its left
+pub const MIRO_SIGNER_FIGURE_OFFSET: i64 = 400;
+pub const MIRO_SIGNER_FIGURE_WIDTH: u64 = 200;
+pub const MIRO_SIGNER_FIGURE_URL: &str = "https://mirostatic.com/app/static/12079327f83ff492.svg";
+
+/// A named place of the code-overhaul frame and the content that fills it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MiroLayoutSlot {
+    pub name: String,
+    pub content: MiroCodeOverhaulConfig,
+    pub x_position: i64,
+    pub y_position: i64,
+    /// width of the deployed image, the height follows the aspect ratio
+    #[serde(default)]
+    pub width: Option<u64>,
+}
+
+/// Layout of the code-overhaul frames, declared as the [miro_layout] table of the Bat.toml or
+/// in the file of miro_layout_file. Contents without a slot use the default positions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MiroFrameLayout {
+    pub frame_width: u64,
+    pub frame_height: u64,
+    /// ignores the slot positions of the screenshots and packs them by their actual size
+    pub auto_layout: bool,
+    pub slots: Vec<MiroLayoutSlot>,
+}
+
+impl Default for MiroFrameLayout {
+    fn default() -> Self {
+        Self {
+            frame_width: MIRO_FRAME_WIDTH,
+            frame_height: MIRO_FRAME_HEIGHT,
+            auto_layout: false,
+            slots: vec![],
+        }
+    }
+}
+
+impl MiroFrameLayout {
+    pub fn get_layout() -> MiroResult<Self> {
+        let bat_config = BatConfig::get_config().change_context(MiroError)?;
+        if !bat_config.miro_layout_file.is_empty() {
+            let layout_content = fs::read_to_string(&bat_config.miro_layout_file)
+                .into_report()
+                .change_context(MiroError)
+                .attach_printable(format!(
+                    "Error reading the Miro layout file {}",
+                    bat_config.miro_layout_file
+                ))?;
+            return Self::new_from_toml(&layout_content);
+        }
+        Ok(bat_config.miro_layout.unwrap_or_default())
+    }
+
+    pub fn new_from_toml(layout_content: &str) -> MiroResult<Self> {
+        toml::from_str(layout_content)
+            .into_report()
+            .change_context(MiroError)
+            .attach_printable("Error parsing the Miro frame layout")
+    }
+
+    pub fn get_slot(&self, content: &MiroCodeOverhaulConfig) -> Option<&MiroLayoutSlot> {
+        self.slots.iter().find(|slot| slot.content == *content)
+    }
+
+    pub fn get_position(&self, content: &MiroCodeOverhaulConfig) -> (i64, i64) {
+        match self.get_slot(content) {
+            Some(slot) => (slot.x_position, slot.y_position),
+            None => content.get_default_positions(self.frame_width, self.frame_height),
+        }
+    }
+
+    pub fn get_width(&self, content: &MiroCodeOverhaulConfig) -> Option<u64> {
+        self.get_slot(content).and_then(|slot| slot.width)
+    }
+
+    /// Moves the center of an item of the given size so the item is inside of the frame
+    pub fn clamp_position(&self, position: (i64, i64), item_size: (u64, u64)) -> (i64, i64) {
+        let clamp_axis = |position: i64, item_length: u64, frame_length: u64| {
+            let min_position = item_length as i64 / 2;
+            let max_position = (frame_length as i64 - item_length as i64 / 2).max(min_position);
+            position.clamp(min_position, max_position)
+        };
+        (
+            clamp_axis(position.0, item_size.0, self.frame_width),
+            clamp_axis(position.1, item_size.1, self.frame_height),
+        )
+    }
+
+    /// Sticky note and user figure positions of the signer at signer_index. Both are moved to
+    /// keep the figure, on the left of the sticky note, inside of the frame
+    pub fn get_signer_positions(&self, signer_index: usize) -> ((i64, i64), (i64, i64)) {
+        let (x_position, y_position) = self.get_position(&MiroCodeOverhaulConfig::Signers);
+        let y_position = y_position + signer_index as i64 * MIRO_SIGNER_SPACING;
+        let (figure_x_position, figure_y_position) = self.clamp_position(
+            (x_position - MIRO_SIGNER_FIGURE_OFFSET, y_position),
+            (MIRO_SIGNER_FIGURE_WIDTH, MIRO_SIGNER_FIGURE_WIDTH),
+        );
+        (
+            (
+                figure_x_position + MIRO_SIGNER_FIGURE_OFFSET,
+                figure_y_position,
+            ),
+            (figure_x_position, figure_y_position),
+        )
+    }
+
+    /// Places the items left to right in rows, starting at the right of the signers, so they
+    /// don't overlap. Returns the centered positions, clamped to the frame, in the order of
+    /// item_sizes
+    pub fn pack_items(&self, item_sizes: &[(u64, u64)]) -> Vec<(i64, i64)> {
+        let ((signers_x_position, _), _) = self.get_signer_positions(0);
+        let start_x = signers_x_position + MIRO_SIGNER_WIDTH as i64 / 2 + MIRO_LAYOUT_MARGIN;
+        let max_x = self.frame_width as i64 - MIRO_LAYOUT_MARGIN;
+        let (mut x_position, mut y_position, mut row_height) = (start_x, MIRO_LAYOUT_MARGIN, 0);
+        let mut positions = vec![];
+        for (width, height) in item_sizes {
+            let (width, height) = (*width as i64, *height as i64);
+            if x_position > start_x && x_position + width > max_x {
+                x_position = start_x;
+                y_position += row_height + MIRO_LAYOUT_MARGIN;
+                row_height = 0;
+            }
+            positions.push(self.clamp_position(
+                (x_position + width / 2, y_position + height / 2),
+                (width as u64, height as u64),
+            ));
+            x_position += width + MIRO_LAYOUT_MARGIN;
+            row_height = row_height.max(height);
+        }
+        positions
+    }
+}
+
+#[test]
+fn test_frame_layout_slots_and_packing() {
+    let frame_layout = MiroFrameLayout::new_from_toml(
+        r#"
+        frame_width = 3000
+        auto_layout = true
+
+        [[slots]]
+        name = "signers"
+        content = "Signers"
+        x_position = 300
+        y_position = 200
+
+        [[slots]]
+        name = "ep"
+        content = "EntryPoint"
+        x_position = 1000
+        y_position = 400
+        width = 800
+        "#,
+    )
+    .unwrap();
+    assert_eq!(frame_layout.frame_height, MIRO_FRAME_HEIGHT);
+    assert_eq!(
+        frame_layout.get_position(&MiroCodeOverhaulConfig::EntryPoint),
+        (1000, 400)
+    );
+    assert_eq!(
+        frame_layout.get_width(&MiroCodeOverhaulConfig::EntryPoint),
+        Some(800)
+    );
+    // no slot, default position for a 3000 wide frame
+    assert_eq!(
+        frame_layout.get_position(&MiroCodeOverhaulConfig::Handler),
+        (2500, MIRO_FRAME_HEIGHT as i64 * 3 / 4)
+    );
+    // the figure would be at -100, so both are moved right
+    assert_eq!(
+        frame_layout.get_signer_positions(1),
+        ((500, 470), (100, 470))
+    );
+
+    // start_x = 500 + 187 + 100 = 787, the third item doesn't fit in the first row and the
+    // fourth one is moved up to stay in the frame
+    let positions = frame_layout.pack_items(&[(1000, 400), (1000, 600), (1000, 200), (800, 2000)]);
+    assert_eq!(
+        positions,
+        vec![(1287, 300), (2387, 400), (1287, 900), (2287, 1600)]
+    );
+}
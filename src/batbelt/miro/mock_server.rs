@@ -0,0 +1,380 @@
+use crate::batbelt::miro::client::{MiroGeometry, MiroItemResponse, MiroParent, MiroPosition};
+use crate::batbelt::miro::{MiroError, MiroResult};
+use error_stack::{IntoReport, ResultExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+pub const MIRO_MOCK_PAGE_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MiroMockRequest {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+}
+
+#[derive(Debug, Default)]
+struct MiroMockState {
+    items: Vec<MiroItemResponse>,
+    requests: Vec<MiroMockRequest>,
+    next_id: u64,
+    forced_failures: Vec<u16>,
+}
+
+/// Local stand-in for the Miro REST API, it records every created item so the miro commands can
+/// be tested without network access
+#[derive(Debug, Clone)]
+pub struct MiroMockServer {
+    pub base_url: String,
+    state: Arc<Mutex<MiroMockState>>,
+}
+
+impl MiroMockServer {
+    /// Starts listening on 127.0.0.1, port 0 picks a free port
+    pub async fn start(port: u16) -> MiroResult<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .await
+            .into_report()
+            .change_context(MiroError)
+            .attach_printable(format!(
+                "Error binding the Miro mock server to port {}",
+                port
+            ))?;
+        let address = listener
+            .local_addr()
+            .into_report()
+            .change_context(MiroError)?;
+        let state = Arc::new(Mutex::new(MiroMockState {
+            next_id: 3458764500000000000,
+            ..Default::default()
+        }));
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let connection_state = server_state.clone();
+                tokio::spawn(async move {
+                    if let Err(error) = Self::handle_connection(stream, connection_state).await {
+                        log::error!("Miro mock server connection error: {:?}", error);
+                    }
+                });
+            }
+        });
+        Ok(Self {
+            base_url: format!("http://{}", address),
+            state,
+        })
+    }
+
+    pub fn get_created_items(&self) -> Vec<MiroItemResponse> {
+        self.state.lock().unwrap().items.clone()
+    }
+
+    pub fn get_requests(&self) -> Vec<MiroMockRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// The next `count` requests are answered with `status`, to exercise the client retries
+    pub fn fail_next_requests(&self, status: u16, count: usize) {
+        self.state
+            .lock()
+            .unwrap()
+            .forced_failures
+            .extend(vec![status; count]);
+    }
+
+    async fn handle_connection(
+        mut stream: TcpStream,
+        state: Arc<Mutex<MiroMockState>>,
+    ) -> std::io::Result<()> {
+        let mut buffer = vec![];
+        let mut chunk = [0u8; 8192];
+        let header_end = loop {
+            let read = stream.read(&mut chunk).await?;
+            if read == 0 {
+                return Ok(());
+            }
+            buffer.extend_from_slice(&chunk[..read]);
+            if let Some(position) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+                break position + 4;
+            }
+        };
+        let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+        let mut head_lines = head.lines();
+        let mut request_line = head_lines.next().unwrap_or_default().split_whitespace();
+        let method = request_line.next().unwrap_or_default().to_string();
+        let path = request_line.next().unwrap_or_default().to_string();
+        let content_length = head_lines
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        while buffer.len() < header_end + content_length {
+            let read = stream.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            buffer.extend_from_slice(&chunk[..read]);
+        }
+        let body = String::from_utf8_lossy(&buffer[header_end..]).to_string();
+
+        let (status, response_body) = {
+            let mut state = state.lock().unwrap();
+            state.requests.push(MiroMockRequest {
+                method: method.clone(),
+                path: path.clone(),
+                body: body.clone(),
+            });
+            if state.forced_failures.is_empty() {
+                state.route(&method, &path, &body)
+            } else {
+                let status = state.forced_failures.remove(0);
+                (
+                    status,
+                    json!({ "status": status, "message": "Mocked failure" }),
+                )
+            }
+        };
+        let response_body = if response_body.is_null() {
+            "".to_string()
+        } else {
+            response_body.to_string()
+        };
+        let response = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            Self::get_reason_phrase(status),
+            response_body.len(),
+            response_body
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    }
+
+    fn get_reason_phrase(status: u16) -> &'static str {
+        match status {
+            200 => "OK",
+            201 => "Created",
+            204 => "No Content",
+            404 => "Not Found",
+            429 => "Too Many Requests",
+            500 => "Internal Server Error",
+            502 => "Bad Gateway",
+            503 => "Service Unavailable",
+            _ => "Unknown",
+        }
+    }
+}
+
+impl MiroMockState {
+    fn route(&mut self, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        // /v2/boards/{board_id}/{resource}[/{item_id}]
+        let segments = path
+            .trim_matches('/')
+            .split('/')
+            .skip(3)
+            .collect::<Vec<_>>();
+        let body_value = serde_json::from_str::<Value>(body).unwrap_or(Value::Null);
+        match (method, segments.as_slice()) {
+            ("POST", [resource]) => self.create_item(resource, &body_value),
+            ("PATCH", [_, item_id]) => self.update_item(item_id, &body_value),
+            ("GET", ["items"]) => self.get_items(query),
+            ("DELETE", [_, item_id]) => self.delete_item(item_id),
+            ("GET", [_, item_id]) => match self.items.iter().find(|item| item.id == *item_id) {
+                Some(item) => (200, json!(item)),
+                None => Self::not_found(item_id),
+            },
+            _ => (404, json!({ "status": 404, "message": "Unknown route" })),
+        }
+    }
+
+    fn create_item(&mut self, resource: &str, body: &Value) -> (u16, Value) {
+        self.next_id += 1;
+        let item_type = resource.trim_end_matches('s').to_string();
+        let position = serde_json::from_value::<MiroPosition>(body["position"].clone())
+            .unwrap_or(MiroPosition::new_centered(0, 0));
+        let geometry = serde_json::from_value::<MiroGeometry>(body["geometry"].clone()).unwrap_or(
+            MiroGeometry {
+                width: None,
+                height: None,
+            },
+        );
+        let item = MiroItemResponse {
+            id: self.next_id.to_string(),
+            item_type,
+            data: body["data"].clone(),
+            geometry: Some(MiroGeometry {
+                width: geometry.width.or(Some(100.0)),
+                height: geometry.height.or(Some(100.0)),
+            }),
+            position: Some(position),
+            parent: serde_json::from_value::<MiroParent>(body["parent"].clone()).ok(),
+        };
+        self.items.push(item.clone());
+        (201, json!(item))
+    }
+
+    fn update_item(&mut self, item_id: &str, body: &Value) -> (u16, Value) {
+        let item = match self.items.iter_mut().find(|item| item.id == item_id) {
+            Some(item) => item,
+            None => return Self::not_found(item_id),
+        };
+        if let Ok(position) = serde_json::from_value::<MiroPosition>(body["position"].clone()) {
+            item.position = Some(position);
+        }
+        if let Ok(parent) = serde_json::from_value::<MiroParent>(body["parent"].clone()) {
+            item.parent = Some(parent);
+        }
+        if let Ok(geometry) = serde_json::from_value::<MiroGeometry>(body["geometry"].clone()) {
+            // like images on Miro, a new width keeps the aspect ratio
+            if let (Some(width), Some(current_geometry)) = (geometry.width, item.geometry.as_mut())
+            {
+                if let (Some(current_width), Some(current_height)) =
+                    (current_geometry.width, current_geometry.height)
+                {
+                    current_geometry.height = Some(current_height * width / current_width);
+                }
+                current_geometry.width = Some(width);
+            }
+        }
+        if !body["data"].is_null() {
+            item.data = body["data"].clone();
+        }
+        (200, json!(item))
+    }
+
+    fn delete_item(&mut self, item_id: &str) -> (u16, Value) {
+        match self.items.iter().position(|item| item.id == item_id) {
+            Some(position) => {
+                self.items.remove(position);
+                (204, Value::Null)
+            }
+            None => Self::not_found(item_id),
+        }
+    }
+
+    fn get_items(&self, query: &str) -> (u16, Value) {
+        let query_value = |key: &str| {
+            query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.to_string())
+        };
+        let item_type = query_value("type");
+        let parent_item_id = query_value("parent_item_id");
+        let offset = query_value("cursor")
+            .and_then(|cursor| cursor.parse::<usize>().ok())
+            .unwrap_or(0);
+        // connectors are not board items for the Miro API
+        let items = self
+            .items
+            .iter()
+            .filter(|item| item.item_type != "connector")
+            .filter(|item| item_type.is_none() || item_type.as_ref() == Some(&item.item_type))
+            .filter(|item| {
+                parent_item_id.is_none()
+                    || item.parent.as_ref().map(|parent| &parent.id) == parent_item_id.as_ref()
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let page = items
+            .iter()
+            .skip(offset)
+            .take(MIRO_MOCK_PAGE_LIMIT)
+            .cloned()
+            .collect::<Vec<_>>();
+        let next_offset = offset + page.len();
+        let cursor = if next_offset < items.len() {
+            Value::String(next_offset.to_string())
+        } else {
+            Value::Null
+        };
+        (
+            200,
+            json!({
+                "data": page,
+                "size": page.len(),
+                "limit": MIRO_MOCK_PAGE_LIMIT,
+                "total": items.len(),
+                "cursor": cursor,
+            }),
+        )
+    }
+
+    fn not_found(item_id: &str) -> (u16, Value) {
+        (
+            404,
+            json!({ "status": 404, "message": format!("Item {} not found", item_id) }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod mock_server_test {
+    use super::*;
+    use crate::batbelt::miro::client::{MiroClient, MiroItemsPage};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_create_and_list_items() {
+        let mock_server = MiroMockServer::start(0).await.unwrap();
+        let miro_client = MiroClient::new_with_base_url(&mock_server.base_url, "token", "board");
+        let response = miro_client
+            .post_json(
+                "frames",
+                &json!({ "data": { "title": "deposit" }, "geometry": { "width": 5600, "height": 2600 } }),
+            )
+            .await
+            .unwrap();
+        let frame: MiroItemResponse = MiroClient::parse_response(response).await.unwrap();
+        assert_eq!(frame.item_type, "frame");
+        miro_client
+            .post_json(
+                "sticky_notes",
+                &json!({ "data": { "content": "signer" }, "parent": { "id": frame.id } }),
+            )
+            .await
+            .unwrap();
+        let response = miro_client
+            .get(&format!("items?parent_item_id={}", frame.id))
+            .await
+            .unwrap();
+        let items_page: MiroItemsPage = MiroClient::parse_response(response).await.unwrap();
+        assert_eq!(items_page.size, 1);
+        assert_eq!(items_page.data[0].item_type, "sticky_note");
+        assert_eq!(mock_server.get_created_items().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_client_retries_on_server_errors() {
+        let mock_server = MiroMockServer::start(0).await.unwrap();
+        mock_server.fail_next_requests(429, 1);
+        mock_server.fail_next_requests(503, 1);
+        let miro_client = MiroClient::new_with_base_url(&mock_server.base_url, "token", "board")
+            .with_retries(3, Duration::from_millis(1));
+        let response = miro_client.get("items?limit=50").await.unwrap();
+        assert!(response.status().is_success());
+        assert_eq!(mock_server.get_requests().len(), 3);
+
+        // a POST is retried on 429, but not on 5xx, since the item could be created anyway
+        mock_server.fail_next_requests(429, 1);
+        let response = miro_client
+            .post_json("shapes", &json!({ "data": { "content": "retried" } }))
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+        assert_eq!(mock_server.get_requests().len(), 5);
+        mock_server.fail_next_requests(503, 1);
+        let response = miro_client
+            .post_json("shapes", &json!({ "data": { "content": "not retried" } }))
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 503);
+        assert_eq!(mock_server.get_requests().len(), 6);
+        assert_eq!(mock_server.get_created_items().len(), 1);
+    }
+}
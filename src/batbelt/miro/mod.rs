@@ -4,16 +4,20 @@ use std::fmt;
 use crate::config::*;
 use normalize_url::normalizer;
 use reqwest;
-use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
 
 use serde_json::{self, Value};
 
+pub mod client;
 pub mod connector;
 pub mod frame;
 pub mod image;
 pub mod item;
+pub mod layout;
+pub mod mock_server;
+pub mod overview;
 pub mod shape;
 pub mod sticky_note;
+pub mod sync;
 
 use error_stack::{Report, Result, ResultExt};
 
@@ -32,6 +36,7 @@ pub struct MiroConfig {
     access_token: String,
     board_id: String,
     board_url: String,
+    api_base_url: String,
 }
 
 pub type MiroApiResult = Result<reqwest::Response, MiroError>;
@@ -46,10 +51,16 @@ impl MiroConfig {
         let access_token = bat_auditor_config.miro_oauth_access_token;
         let board_url = bat_config.miro_board_url;
         let board_id = Self::get_miro_board_id(board_url.clone())?;
+        let api_base_url = if bat_auditor_config.miro_api_base_url.is_empty() {
+            client::MIRO_API_BASE_URL.to_string()
+        } else {
+            bat_auditor_config.miro_api_base_url
+        };
         Ok(MiroConfig {
             access_token,
             board_id,
             board_url,
+            api_base_url,
         })
     }
 
@@ -170,7 +181,7 @@ impl MiroObject {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum MiroItemType {
     AppCard,
     Card,
@@ -199,17 +210,22 @@ impl MiroItemType {
     }
 
     pub fn from_str(type_str: &str) -> MiroItemType {
+        Self::try_from_str(type_str).unwrap_or_else(|| unimplemented!())
+    }
+
+    /// None for the types bat-cli doesn't deploy, like mind map nodes
+    pub fn try_from_str(type_str: &str) -> Option<MiroItemType> {
         match type_str {
-            "app_card" => MiroItemType::AppCard,
-            "card" => MiroItemType::Card,
-            "document" => MiroItemType::Document,
-            "embed" => MiroItemType::Embed,
-            "frame" => MiroItemType::Frame,
-            "image" => MiroItemType::Image,
-            "shape" => MiroItemType::Shape,
-            "sticky_note" => MiroItemType::StickyNote,
-            "text" => MiroItemType::Text,
-            _ => unimplemented!(),
+            "app_card" => Some(MiroItemType::AppCard),
+            "card" => Some(MiroItemType::Card),
+            "document" => Some(MiroItemType::Document),
+            "embed" => Some(MiroItemType::Embed),
+            "frame" => Some(MiroItemType::Frame),
+            "image" => Some(MiroItemType::Image),
+            "shape" => Some(MiroItemType::Shape),
+            "sticky_note" => Some(MiroItemType::StickyNote),
+            "text" => Some(MiroItemType::Text),
+            _ => None,
         }
     }
 }
@@ -276,6 +292,28 @@ impl MiroColor {
         }
     }
 
+    /// The closest hex color, for the diagrams drawn outside of Miro
+    pub fn to_hex(&self) -> &str {
+        match self {
+            MiroColor::Gray => "#f5f6f8",
+            MiroColor::LightYellow => "#fff9b1",
+            MiroColor::Yellow => "#f5d128",
+            MiroColor::Orange => "#ff9d48",
+            MiroColor::LightGreen => "#d5f692",
+            MiroColor::Green => "#c9df56",
+            MiroColor::DarkGreen => "#93d275",
+            MiroColor::Cyan => "#67c6c0",
+            MiroColor::LightPink => "#ffcee0",
+            MiroColor::Pink => "#ea94bb",
+            MiroColor::Violet => "#c6a2d2",
+            MiroColor::Red => "#f0939d",
+            MiroColor::LightBlue => "#a6ccf5",
+            MiroColor::Blue => "#6cd8fa",
+            MiroColor::DarkBlue => "#9ea9ff",
+            MiroColor::Black => "#000000",
+        }
+    }
+
     pub fn get_colors_vec() -> Vec<String> {
         vec![
             "gray".to_string(),
@@ -0,0 +1,451 @@
+use crate::batbelt::metadata::BatMetadata;
+use crate::batbelt::miro::connector::create_connector;
+use crate::batbelt::miro::frame::MiroFrame;
+use crate::batbelt::miro::shape::{MiroShape, MiroShapeStyle};
+use crate::batbelt::miro::sticky_note::MiroStickyNote;
+use crate::batbelt::miro::{MiroColor, MiroError, MiroResult};
+use crate::batbelt::parser::entrypoint_parser::EntrypointParser;
+use crate::batbelt::parser::solana_account_parser::SolanaAccountType;
+use crate::batbelt::path::BatFolder;
+use crate::batbelt::templates::finding_template::FindingTemplateDefinition;
+use crate::batbelt::BatEnumerator;
+use error_stack::{IntoReport, ResultExt};
+use regex::Regex;
+use std::collections::HashMap;
+use std::{fmt, fs};
+use strum::IntoEnumIterator;
+
+pub const MIRO_OVERVIEW_FRAME_TITLE: &str = "audit overview";
+pub const MIRO_OVERVIEW_MARGIN: i64 = 200;
+pub const MIRO_OVERVIEW_COLUMN_WIDTH: i64 = 1200;
+pub const MIRO_OVERVIEW_ROW_HEIGHT: i64 = 350;
+pub const MIRO_OVERVIEW_ITEM_WIDTH: i64 = 800;
+pub const MIRO_OVERVIEW_ITEM_HEIGHT: i64 = 250;
+
+#[derive(Debug, PartialEq, Clone, Copy, strum_macros::Display, strum_macros::EnumIter)]
+pub enum CodeOverhaulStatus {
+    ToReview,
+    Started,
+    Finished,
+}
+
+impl BatEnumerator for CodeOverhaulStatus {}
+
+impl CodeOverhaulStatus {
+    pub fn get_bat_folder(&self) -> BatFolder {
+        match self {
+            CodeOverhaulStatus::ToReview => BatFolder::CodeOverhaulToReview,
+            CodeOverhaulStatus::Started => BatFolder::CodeOverhaulStarted,
+            CodeOverhaulStatus::Finished => BatFolder::CodeOverhaulFinished,
+        }
+    }
+
+    pub fn get_color(&self) -> MiroColor {
+        match self {
+            CodeOverhaulStatus::ToReview => MiroColor::Gray,
+            CodeOverhaulStatus::Started => MiroColor::Yellow,
+            CodeOverhaulStatus::Finished => MiroColor::Green,
+        }
+    }
+}
+
+/// Severity of a finding, one of the severities declared on finding_template.toml
+#[derive(Debug, PartialEq, Clone)]
+pub struct FindingSeverity {
+    pub name: String,
+    /// position among the non-informational severities, None for the informational ones
+    pub rank: Option<usize>,
+}
+
+impl fmt::Display for FindingSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl FindingSeverity {
+    /// Severity from the **Severity:** line of a finding file, None if it is not declared on the
+    /// finding template
+    pub fn from_finding_content(
+        finding_content: &str,
+        finding_template_definition: &FindingTemplateDefinition,
+    ) -> Option<Self> {
+        let severity = finding_content
+            .lines()
+            .find(|line| line.contains("**Severity:**"))?
+            .replace("**Severity:**", "");
+        let name = finding_template_definition
+            .severities
+            .iter()
+            .find(|declared_severity| declared_severity.eq_ignore_ascii_case(severity.trim()))?
+            .clone();
+        let rank = if finding_template_definition.is_informational(&name) {
+            None
+        } else {
+            finding_template_definition
+                .severities
+                .iter()
+                .filter(|declared_severity| {
+                    !finding_template_definition.is_informational(declared_severity)
+                })
+                .position(|declared_severity| declared_severity == &name)
+        };
+        Some(Self { name, rank })
+    }
+
+    pub fn get_color(&self) -> MiroColor {
+        match self.rank {
+            Some(0) => MiroColor::Red,
+            Some(1) => MiroColor::Orange,
+            Some(2) => MiroColor::Yellow,
+            Some(_) => MiroColor::LightYellow,
+            None => MiroColor::LightBlue,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct MiroOverviewEntryPoint {
+    pub name: String,
+    pub status: CodeOverhaulStatus,
+    pub frame_id: Option<String>,
+    /// program state accounts of the context accounts struct
+    pub context_account_structs: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct MiroOverviewFinding {
+    pub title: String,
+    pub file_name: String,
+    pub severity: FindingSeverity,
+    /// entry points mentioned by the finding
+    pub entry_point_names: Vec<String>,
+}
+
+impl MiroOverviewFinding {
+    pub fn new_from_content(
+        file_name: &str,
+        finding_content: &str,
+        entry_point_names: &[String],
+        finding_template_definition: &FindingTemplateDefinition,
+    ) -> Option<Self> {
+        let severity =
+            FindingSeverity::from_finding_content(finding_content, finding_template_definition)?;
+        let title = finding_content
+            .lines()
+            .find_map(|line| line.strip_prefix("## "))
+            .unwrap_or(file_name)
+            .trim()
+            .to_string();
+        let entry_point_names = entry_point_names
+            .iter()
+            .filter(|entry_point_name| {
+                Regex::new(&format!(r"\b{}\b", regex::escape(entry_point_name)))
+                    .map(|entry_point_regex| entry_point_regex.is_match(finding_content))
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect();
+        Some(Self {
+            title,
+            file_name: file_name.to_string(),
+            severity,
+            entry_point_names,
+        })
+    }
+}
+
+/// Summary of the audit: the entry points with their code-overhaul status, the accepted findings
+/// and the context accounts shared between entry points
+#[derive(Debug, Clone)]
+pub struct MiroOverview {
+    pub entry_points: Vec<MiroOverviewEntryPoint>,
+    pub findings: Vec<MiroOverviewFinding>,
+}
+
+impl MiroOverview {
+    pub fn new_from_audit() -> MiroResult<Self> {
+        let bat_metadata = BatMetadata::read_metadata().change_context(MiroError)?;
+        let entry_point_names =
+            EntrypointParser::get_entrypoint_names(true).change_context(MiroError)?;
+        let mut co_statuses = HashMap::new();
+        for co_status in CodeOverhaulStatus::iter() {
+            for co_file_name in co_status
+                .get_bat_folder()
+                .get_all_files_names(false, None, None)
+                .change_context(MiroError)?
+            {
+                co_statuses.insert(co_file_name.trim_end_matches(".md").to_string(), co_status);
+            }
+        }
+        let mut entry_points = vec![];
+        for entry_point_name in entry_point_names.clone() {
+            let context_accounts_metadata = EntrypointParser::new_from_name(&entry_point_name)
+                .ok()
+                .and_then(|entrypoint_parser| {
+                    bat_metadata
+                        .get_context_accounts_metadata_by_struct_source_code_metadata_id(
+                            entrypoint_parser.context_accounts.metadata_id,
+                        )
+                        .ok()
+                });
+            let mut context_account_structs = context_accounts_metadata
+                .map(|ca_metadata| {
+                    ca_metadata
+                        .context_accounts_info
+                        .into_iter()
+                        .filter(|ca_info| {
+                            ca_info.solana_account_type == SolanaAccountType::ProgramStateAccount
+                        })
+                        .map(|ca_info| ca_info.account_struct_name)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            context_account_structs.sort();
+            context_account_structs.dedup();
+            let frame_id = bat_metadata
+                .miro
+                .code_overhaul
+                .iter()
+                .find(|co_metadata| co_metadata.entry_point_name == entry_point_name)
+                .map(|co_metadata| co_metadata.miro_frame_id.clone())
+                .filter(|frame_id| !frame_id.is_empty());
+            entry_points.push(MiroOverviewEntryPoint {
+                status: co_statuses
+                    .get(&entry_point_name)
+                    .cloned()
+                    .unwrap_or(CodeOverhaulStatus::ToReview),
+                name: entry_point_name,
+                frame_id,
+                context_account_structs,
+            });
+        }
+        let finding_template_definition =
+            FindingTemplateDefinition::read_definition().change_context(MiroError)?;
+        let mut findings = vec![];
+        for finding_dir_entry in BatFolder::FindingsAccepted
+            .get_all_files_dir_entries(true, None, None)
+            .change_context(MiroError)?
+        {
+            let file_name = finding_dir_entry.file_name().to_str().unwrap().to_string();
+            let finding_content = fs::read_to_string(finding_dir_entry.path())
+                .into_report()
+                .change_context(MiroError)?;
+            match MiroOverviewFinding::new_from_content(
+                &file_name,
+                &finding_content,
+                &entry_point_names,
+                &finding_template_definition,
+            ) {
+                Some(finding) => findings.push(finding),
+                None => log::warn!("Severity not found in the accepted finding {}", file_name),
+            }
+        }
+        Ok(Self {
+            entry_points,
+            findings,
+        })
+    }
+
+    /// Context account structs used by more than one entry point
+    pub fn get_shared_structs(&self) -> Vec<String> {
+        let mut struct_names = self
+            .entry_points
+            .iter()
+            .flat_map(|entry_point| entry_point.context_account_structs.clone())
+            .collect::<Vec<_>>();
+        struct_names.sort();
+        let mut shared_structs = struct_names
+            .iter()
+            .filter(|struct_name| {
+                struct_names
+                    .iter()
+                    .filter(|other_name| other_name == struct_name)
+                    .count()
+                    > 1
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        shared_structs.dedup();
+        shared_structs
+    }
+
+    /// Width and height of the frame, with a column for the entry points, one for the shared
+    /// structs and one for the findings
+    pub fn get_frame_size(&self) -> (u64, u64) {
+        let rows = self
+            .entry_points
+            .len()
+            .max(self.get_shared_structs().len())
+            .max(self.findings.len())
+            .max(1) as i64;
+        (
+            (MIRO_OVERVIEW_COLUMN_WIDTH * 3 + MIRO_OVERVIEW_MARGIN * 2) as u64,
+            (MIRO_OVERVIEW_ROW_HEIGHT * rows + MIRO_OVERVIEW_MARGIN * 2) as u64,
+        )
+    }
+
+    fn get_item_position(column: i64, row: usize) -> (i64, i64) {
+        (
+            MIRO_OVERVIEW_MARGIN
+                + MIRO_OVERVIEW_COLUMN_WIDTH * column
+                + MIRO_OVERVIEW_COLUMN_WIDTH / 2,
+            MIRO_OVERVIEW_MARGIN
+                + MIRO_OVERVIEW_ROW_HEIGHT * row as i64
+                + MIRO_OVERVIEW_ROW_HEIGHT / 2,
+        )
+    }
+
+    /// Creates the shapes, sticky notes and connectors inside of the overview frame
+    pub async fn deploy(&self, overview_frame: &MiroFrame) -> MiroResult<()> {
+        let mut entry_point_ids = HashMap::new();
+        for (row, entry_point) in self.entry_points.iter().enumerate() {
+            let entry_point_title = match &entry_point.frame_id {
+                Some(frame_id) => format!(
+                    "<a href=\"{}\">{}</a>",
+                    MiroFrame::get_frame_url_by_frame_id(frame_id)?,
+                    entry_point.name
+                ),
+                None => entry_point.name.clone(),
+            };
+            let (x_position, y_position) = Self::get_item_position(0, row);
+            let shape_id = MiroShape::new(
+                x_position as i32,
+                y_position as i32,
+                MIRO_OVERVIEW_ITEM_WIDTH as i32,
+                MIRO_OVERVIEW_ITEM_HEIGHT as i32,
+                format!(
+                    "<p><strong>{}</strong></p><p>{}</p>",
+                    entry_point_title, entry_point.status
+                ),
+            )
+            .create_shape_in_frame(
+                MiroShapeStyle::new_from_hex_fill_color(entry_point.status.get_color().to_hex()),
+                &overview_frame.item_id,
+            )
+            .await?;
+            entry_point_ids.insert(entry_point.name.clone(), shape_id);
+        }
+
+        let mut struct_ids = HashMap::new();
+        for (row, struct_name) in self.get_shared_structs().into_iter().enumerate() {
+            let (x_position, y_position) = Self::get_item_position(1, row);
+            let shape_id = MiroShape::new(
+                x_position as i32,
+                y_position as i32,
+                MIRO_OVERVIEW_ITEM_WIDTH as i32,
+                MIRO_OVERVIEW_ITEM_HEIGHT as i32,
+                format!("<p><strong>{}</strong></p>", struct_name),
+            )
+            .create_shape_in_frame(
+                MiroShapeStyle::new_from_hex_border_color(MiroColor::Black.to_hex()),
+                &overview_frame.item_id,
+            )
+            .await?;
+            struct_ids.insert(struct_name, shape_id);
+        }
+
+        for entry_point in &self.entry_points {
+            for struct_name in &entry_point.context_account_structs {
+                if let Some(struct_id) = struct_ids.get(struct_name) {
+                    create_connector(&entry_point_ids[&entry_point.name], struct_id, None).await?;
+                }
+            }
+        }
+
+        for (row, finding) in self.findings.iter().enumerate() {
+            let (x_position, y_position) = Self::get_item_position(2, row);
+            let mut finding_sticky_note = MiroStickyNote::new(
+                &format!(
+                    "<p><strong>{}</strong></p><p>{}</p>",
+                    finding.title, finding.severity
+                ),
+                finding.severity.get_color(),
+                &overview_frame.item_id,
+                x_position,
+                y_position,
+                MIRO_OVERVIEW_ITEM_WIDTH as u64,
+                0,
+            );
+            finding_sticky_note.deploy().await?;
+            for entry_point_name in &finding.entry_point_names {
+                if let Some(entry_point_id) = entry_point_ids.get(entry_point_name) {
+                    create_connector(entry_point_id, &finding_sticky_note.item_id, None).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_overview_findings_and_shared_structs() {
+    let entry_point_names = vec!["deposit".to_string(), "withdraw".to_string()];
+    let finding_template_definition = FindingTemplateDefinition {
+        severities: ["Critical", "High", "Medium", "Low", "Informational"]
+            .map(|severity| severity.to_string())
+            .to_vec(),
+        ..Default::default()
+    };
+    let finding = MiroOverviewFinding::new_from_content(
+        "1-missing-owner-check.md",
+        "## Missing owner check\n\n**Severity:** High\n\n**Status:** Open\n\n- withdraw_all\n- deposit\n",
+        &entry_point_names,
+        &finding_template_definition,
+    )
+    .unwrap();
+    assert_eq!(finding.title, "Missing owner check");
+    assert_eq!(finding.severity.to_string(), "High");
+    assert_eq!(finding.severity.get_color().to_str(), "orange");
+    let critical_severity = FindingSeverity::from_finding_content(
+        "**Severity:** critical",
+        &finding_template_definition,
+    )
+    .unwrap();
+    assert_eq!(critical_severity.to_string(), "Critical");
+    assert_eq!(critical_severity.get_color().to_str(), "red");
+    assert_eq!(
+        FindingSeverity::from_finding_content(
+            "**Severity:** Informational",
+            &finding_template_definition
+        )
+        .unwrap()
+        .get_color()
+        .to_str(),
+        "light_blue"
+    );
+    assert!(FindingSeverity::from_finding_content(
+        "**Severity:** Critical",
+        &FindingTemplateDefinition::default()
+    )
+    .is_none());
+    // withdraw_all is not the withdraw entry point
+    assert_eq!(finding.entry_point_names, vec!["deposit".to_string()]);
+    assert!(MiroOverviewFinding::new_from_content(
+        "notes.md",
+        "## Notes",
+        &entry_point_names,
+        &finding_template_definition
+    )
+    .is_none());
+
+    let new_entry_point = |name: &str, structs: &[&str]| MiroOverviewEntryPoint {
+        name: name.to_string(),
+        status: CodeOverhaulStatus::ToReview,
+        frame_id: None,
+        context_account_structs: structs.iter().map(|s| s.to_string()).collect(),
+    };
+    let overview = MiroOverview {
+        entry_points: vec![
+            new_entry_point("deposit", &["Pool", "Vault"]),
+            new_entry_point("withdraw", &["Vault", "Pool"]),
+            new_entry_point("initialize", &["Config", "Pool"]),
+        ],
+        findings: vec![finding],
+    };
+    assert_eq!(
+        overview.get_shared_structs(),
+        vec!["Pool".to_string(), "Vault".to_string()]
+    );
+    assert_eq!(overview.get_frame_size(), (4000, 1450));
+}
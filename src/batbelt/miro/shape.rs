@@ -1,6 +1,4 @@
-use crate::batbelt::miro::{helpers::get_id_from_response, MiroConfig};
 use error_stack::Result;
-use reqwest;
 use serde_json::*;
 
 use super::MiroError;
@@ -62,6 +60,13 @@ impl MiroShapeStyle {
             color: "#1a1a1a".to_string(),
         }
     }
+
+    pub fn new_from_hex_fill_color(fill_color: &str) -> Self {
+        MiroShapeStyle {
+            fill_color: fill_color.to_string(),
+            ..Self::new_from_hex_border_color("#1a1a1a")
+        }
+    }
 }
 #[derive(Debug, Clone)]
 pub struct MiroShape {
@@ -87,33 +92,26 @@ impl MiroShape {
         &self,
         miro_shape_style: MiroShapeStyle,
         frame_id: &str,
-    ) -> Result<(), MiroError> {
-        api::create_shape(self.clone(), miro_shape_style, frame_id).await?;
-        Ok(())
+    ) -> Result<String, MiroError> {
+        api::create_shape(self.clone(), miro_shape_style, frame_id).await
     }
 }
 
 mod api {
 
     use super::*;
-    use error_stack::IntoReport;
-    use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+    use crate::batbelt::miro::client::{MiroClient, MiroItemResponse};
 
     pub async fn create_shape(
         miro_shape: MiroShape,
         miro_shape_style: MiroShapeStyle,
         miro_frame_id: &str,
     ) -> Result<String, MiroError> {
-        let MiroConfig {
-            access_token,
-            board_id,
-            ..
-        } = MiroConfig::new()?;
-        let client = reqwest::Client::new();
-        let response = client
-            .post(format!("https://api.miro.com/v2/boards/{board_id}/shapes",))
-            .body(
-                json!({
+        let miro_client = MiroClient::new()?;
+        let response = miro_client
+            .post_json(
+                "shapes",
+                &json!({
                     "data": {
                         "content": miro_shape.content,
                         "shape": "rectangle"
@@ -143,15 +141,10 @@ mod api {
                    "parent": {
                         "id": miro_frame_id
                    }
-                })
-                .to_string(),
+                }),
             )
-            .header(CONTENT_TYPE, "application/json")
-            .header(AUTHORIZATION, format!("Bearer {access_token}"))
-            .send()
-            .await
-            .into_report();
-        let id = get_id_from_response(response).await?;
-        Ok(id)
+            .await?;
+        let shape_response: MiroItemResponse = MiroClient::parse_response(response).await?;
+        Ok(shape_response.id)
     }
 }
@@ -1,7 +1,7 @@
-use crate::batbelt::miro::{MiroColor, MiroConfig, MiroItemType};
+use crate::batbelt::miro::client::MiroClient;
+use crate::batbelt::miro::{MiroColor, MiroItemType};
 
 use error_stack::{IntoReport, Result};
-use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
 use serde_json::{json, Value};
 
 use super::MiroError;
@@ -113,19 +113,11 @@ mod api {
         y_position: i64,
         width: u64,
     ) -> MiroApiResult {
-        let MiroConfig {
-            access_token,
-            board_id,
-            ..
-        } = MiroConfig::new()?;
-        // let x_position = x + x_move;
-        let client = reqwest::Client::new();
-        let response = client
-            .post(format!(
-                "https://api.miro.com/v2/boards/{board_id}/sticky_notes",
-            ))
-            .body(
-                json!({
+        let miro_client = MiroClient::new()?;
+        miro_client
+            .post_json(
+                "sticky_notes",
+                &json!({
                     "data": {
                         "content": content,
                         "shape": "rectangle"
@@ -144,14 +136,9 @@ mod api {
                     "parent": {
                         "id": parent_id
                     }
-                })
-                .to_string(),
+                }),
             )
-            .header(CONTENT_TYPE, "application/json")
-            .header(AUTHORIZATION, format!("Bearer {access_token}"))
-            .send()
-            .await;
-        MiroConfig::parse_response_from_miro(response)
+            .await
     }
 
     //     pub async fn create_signer_sticky_note(
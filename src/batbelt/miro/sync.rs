@@ -0,0 +1,544 @@
+use crate::batbelt::metadata::miro_metadata::MiroCodeOverhaulMetadata;
+use crate::batbelt::miro::client::MiroItemResponse;
+use crate::batbelt::miro::frame::{MiroCodeOverhaulConfig, MiroFrame};
+use crate::batbelt::miro::item::MiroItem;
+use crate::batbelt::miro::layout::MiroFrameLayout;
+use crate::batbelt::miro::{MiroItemType, MiroResult};
+use crate::batbelt::BatEnumerator;
+
+pub const MIRO_CO_FRAME_TITLE_PREFIX: &str = "co: ";
+/// Miro returns the positions as floats, so small differences are not a move
+pub const MIRO_SYNC_POSITION_TOLERANCE: f64 = 1.0;
+
+#[derive(Debug, PartialEq, Clone, Copy, strum_macros::Display, strum_macros::EnumIter)]
+pub enum MiroDriftType {
+    /// the frame of the code-overhaul metadata is not on the board
+    MissingFrame,
+    /// an image or sticky note of the code-overhaul metadata is not on the board
+    MissingItem,
+    /// the item is outside of its code-overhaul frame, or away from its position in it
+    MovedItem,
+    /// an item inside of a code-overhaul frame that is not in the metadata
+    UntrackedItem,
+    /// a code-overhaul frame on the board that is not in the metadata
+    OrphanedFrame,
+}
+
+impl BatEnumerator for MiroDriftType {}
+
+/// An item id stored in the code-overhaul metadata, with the place it was deployed to
+#[derive(Debug, PartialEq, Clone)]
+pub struct MiroTrackedItem {
+    pub item_id: String,
+    pub label: String,
+    pub item_type: MiroItemType,
+    pub co_config: MiroCodeOverhaulConfig,
+    /// position of the signer or screenshot in its group, 0 for the single screenshots
+    pub index: usize,
+    pub x_position: i64,
+    pub y_position: i64,
+    /// false for the screenshots placed by the auto layout, which depends on their sizes
+    pub check_position: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct MiroDrift {
+    pub drift_type: MiroDriftType,
+    pub entry_point_name: String,
+    pub frame_id: String,
+    pub item: Option<MiroTrackedItem>,
+    /// frame on the board that can replace a missing one
+    pub adoptable_frame_id: Option<String>,
+}
+
+impl MiroDrift {
+    pub fn get_description(&self) -> String {
+        match self.drift_type {
+            MiroDriftType::MissingFrame => match &self.adoptable_frame_id {
+                Some(adoptable_frame_id) => format!(
+                    "frame {} of {} not found, but frame {} has the same title",
+                    self.frame_id, self.entry_point_name, adoptable_frame_id
+                ),
+                None => format!(
+                    "frame {} of {} not found",
+                    self.frame_id, self.entry_point_name
+                ),
+            },
+            MiroDriftType::MissingItem | MiroDriftType::MovedItem => {
+                let item = self.item.clone().unwrap();
+                format!(
+                    "{} {} of {} {}",
+                    item.label,
+                    item.item_id,
+                    self.entry_point_name,
+                    if self.drift_type == MiroDriftType::MissingItem {
+                        "not found".to_string()
+                    } else {
+                        format!(
+                            "is not at ({}, {}) in frame {}",
+                            item.x_position, item.y_position, self.frame_id
+                        )
+                    }
+                )
+            }
+            MiroDriftType::UntrackedItem => {
+                let item = self.item.clone().unwrap();
+                format!(
+                    "{} {} in frame {} of {} is not in the Miro metadata",
+                    item.label, item.item_id, self.frame_id, self.entry_point_name
+                )
+            }
+            MiroDriftType::OrphanedFrame => format!(
+                "frame {} titled {}{} is not in the Miro metadata",
+                self.frame_id, MIRO_CO_FRAME_TITLE_PREFIX, self.entry_point_name
+            ),
+        }
+    }
+}
+
+pub struct MiroBoardSync {
+    pub drifts: Vec<MiroDrift>,
+    pub board_frames: Vec<MiroFrame>,
+}
+
+impl MiroBoardSync {
+    /// Compares the code-overhaul metadata with the frames and items on the board, listed once
+    pub async fn new_scanned(co_metadata_vec: &[MiroCodeOverhaulMetadata]) -> MiroResult<Self> {
+        let board_items = MiroItem::get_all_items_on_board(None).await?;
+        let board_frames = board_items
+            .iter()
+            .filter(|item| item.item_type == MiroItemType::Frame.to_string())
+            .cloned()
+            .map(MiroFrame::new_from_item_response)
+            .collect::<MiroResult<Vec<_>>>()?;
+        let frame_layout = MiroFrameLayout::get_layout()?;
+        let drifts = Self::get_drifts(co_metadata_vec, &frame_layout, &board_frames, &board_items);
+        Ok(Self {
+            drifts,
+            board_frames,
+        })
+    }
+
+    pub fn get_drifts(
+        co_metadata_vec: &[MiroCodeOverhaulMetadata],
+        frame_layout: &MiroFrameLayout,
+        board_frames: &[MiroFrame],
+        board_items: &[MiroItemResponse],
+    ) -> Vec<MiroDrift> {
+        let mut drifts = vec![];
+        for co_metadata in co_metadata_vec {
+            let board_frame = board_frames
+                .iter()
+                .find(|frame| frame.item_id == co_metadata.miro_frame_id);
+            if board_frame.is_none() {
+                let adoptable_frame_id =
+                    Self::get_co_frames(board_frames, &co_metadata.entry_point_name)
+                        .first()
+                        .map(|frame| frame.item_id.clone());
+                drifts.push(MiroDrift {
+                    drift_type: MiroDriftType::MissingFrame,
+                    entry_point_name: co_metadata.entry_point_name.clone(),
+                    frame_id: co_metadata.miro_frame_id.clone(),
+                    item: None,
+                    adoptable_frame_id,
+                });
+                continue;
+            }
+            let tracked_items = Self::get_tracked_items(co_metadata, frame_layout);
+            for tracked_item in tracked_items.clone() {
+                let board_item = board_items
+                    .iter()
+                    .find(|board_item| board_item.id == tracked_item.item_id);
+                let drift_type = match board_item {
+                    None => MiroDriftType::MissingItem,
+                    Some(board_item)
+                        if !Self::is_in_place(
+                            board_item,
+                            &co_metadata.miro_frame_id,
+                            &tracked_item,
+                        ) =>
+                    {
+                        MiroDriftType::MovedItem
+                    }
+                    Some(_) => continue,
+                };
+                drifts.push(MiroDrift {
+                    drift_type,
+                    entry_point_name: co_metadata.entry_point_name.clone(),
+                    frame_id: co_metadata.miro_frame_id.clone(),
+                    item: Some(tracked_item),
+                    adoptable_frame_id: None,
+                });
+            }
+            for board_item in board_items {
+                let is_in_frame = board_item.parent.as_ref().map(|parent| parent.id.as_str())
+                    == Some(co_metadata.miro_frame_id.as_str());
+                let is_tracked = tracked_items
+                    .iter()
+                    .any(|tracked_item| tracked_item.item_id == board_item.id);
+                if !is_in_frame || is_tracked {
+                    continue;
+                }
+                let Some(item_type) = MiroItemType::try_from_str(&board_item.item_type) else {
+                    continue;
+                };
+                let position = board_item.position.clone();
+                drifts.push(MiroDrift {
+                    drift_type: MiroDriftType::UntrackedItem,
+                    entry_point_name: co_metadata.entry_point_name.clone(),
+                    frame_id: co_metadata.miro_frame_id.clone(),
+                    item: Some(MiroTrackedItem {
+                        item_id: board_item.id.clone(),
+                        label: board_item.item_type.replace('_', " "),
+                        item_type,
+                        co_config: MiroCodeOverhaulConfig::EntryPoint,
+                        index: 0,
+                        x_position: position.as_ref().map_or(0, |position| position.x as i64),
+                        y_position: position.as_ref().map_or(0, |position| position.y as i64),
+                        check_position: false,
+                    }),
+                    adoptable_frame_id: None,
+                });
+            }
+        }
+        drifts.append(&mut Self::get_orphaned_frames(
+            co_metadata_vec,
+            board_frames,
+        ));
+        drifts
+    }
+
+    /// The item is in its frame and, if its position is checked, where it was deployed
+    fn is_in_place(
+        board_item: &MiroItemResponse,
+        frame_id: &str,
+        tracked_item: &MiroTrackedItem,
+    ) -> bool {
+        if board_item.parent.as_ref().map(|parent| parent.id.as_str()) != Some(frame_id) {
+            return false;
+        }
+        if !tracked_item.check_position {
+            return true;
+        }
+        match &board_item.position {
+            Some(position) => {
+                (position.x - tracked_item.x_position as f64).abs() <= MIRO_SYNC_POSITION_TOLERANCE
+                    && (position.y - tracked_item.y_position as f64).abs()
+                        <= MIRO_SYNC_POSITION_TOLERANCE
+            }
+            None => true,
+        }
+    }
+
+    pub fn get_tracked_items(
+        co_metadata: &MiroCodeOverhaulMetadata,
+        frame_layout: &MiroFrameLayout,
+    ) -> Vec<MiroTrackedItem> {
+        let mut tracked_items = vec![];
+        let images = [
+            (
+                &co_metadata.entry_point_image_id,
+                "entry point image",
+                MiroCodeOverhaulConfig::EntryPoint,
+            ),
+            (
+                &co_metadata.context_accounts_image_id,
+                "context accounts image",
+                MiroCodeOverhaulConfig::ContextAccount,
+            ),
+            (
+                &co_metadata.validations_image_id,
+                "validations image",
+                MiroCodeOverhaulConfig::Validations,
+            ),
+            (
+                &co_metadata.handler_image_id,
+                "handler image",
+                MiroCodeOverhaulConfig::Handler,
+            ),
+        ];
+        for (image_id, label, co_config) in images {
+            let (x_position, y_position) = frame_layout.get_position(&co_config);
+            tracked_items.push(MiroTrackedItem {
+                item_id: image_id.clone(),
+                label: label.to_string(),
+                item_type: MiroItemType::Image,
+                co_config,
+                index: 0,
+                x_position,
+                y_position,
+                check_position: !frame_layout.auto_layout,
+            });
+        }
+        let image_groups = [
+            (
+                &co_metadata.state_changes_image_ids,
+                "state changes image",
+                MiroCodeOverhaulConfig::StateChanges,
+            ),
+            (
+                &co_metadata.handler_parameters_image_ids,
+                "handler parameters image",
+                MiroCodeOverhaulConfig::HandlerParameters,
+            ),
+        ];
+        for (image_ids, label, co_config) in image_groups {
+            let (x_position, y_position) = frame_layout.get_position(&co_config);
+            for (image_index, image_id) in image_ids.iter().enumerate() {
+                // the screenshots of a group are deployed on top of each other
+                tracked_items.push(MiroTrackedItem {
+                    item_id: image_id.clone(),
+                    label: format!("{} {}", label, image_index + 1),
+                    item_type: MiroItemType::Image,
+                    co_config,
+                    index: image_index,
+                    x_position,
+                    y_position,
+                    check_position: false,
+                });
+            }
+        }
+        for (signer_index, signer) in co_metadata.signers.iter().enumerate() {
+            let (
+                (sticky_note_x_position, sticky_note_y_position),
+                (figure_x_position, figure_y_position),
+            ) = frame_layout.get_signer_positions(signer_index);
+            tracked_items.push(MiroTrackedItem {
+                item_id: signer.sticky_note_id.clone(),
+                label: format!("signer {} sticky note", signer_index + 1),
+                item_type: MiroItemType::StickyNote,
+                co_config: MiroCodeOverhaulConfig::Signers,
+                index: signer_index,
+                x_position: sticky_note_x_position,
+                y_position: sticky_note_y_position,
+                check_position: true,
+            });
+            tracked_items.push(MiroTrackedItem {
+                item_id: signer.user_figure_id.clone(),
+                label: format!("signer {} figure", signer_index + 1),
+                item_type: MiroItemType::Image,
+                co_config: MiroCodeOverhaulConfig::Signers,
+                index: signer_index,
+                x_position: figure_x_position,
+                y_position: figure_y_position,
+                check_position: true,
+            });
+        }
+        tracked_items
+            .into_iter()
+            .filter(|tracked_item| !tracked_item.item_id.is_empty())
+            .collect()
+    }
+
+    /// Code-overhaul frames on the board not referenced by the metadata. Frames with the title of
+    /// an entry point with a missing frame are reported as adoptable instead
+    pub fn get_orphaned_frames(
+        co_metadata_vec: &[MiroCodeOverhaulMetadata],
+        board_frames: &[MiroFrame],
+    ) -> Vec<MiroDrift> {
+        board_frames
+            .iter()
+            .filter_map(|frame| {
+                let entry_point_name = frame.title.strip_prefix(MIRO_CO_FRAME_TITLE_PREFIX)?;
+                if co_metadata_vec
+                    .iter()
+                    .any(|co_metadata| co_metadata.miro_frame_id == frame.item_id)
+                {
+                    return None;
+                }
+                let co_metadata = co_metadata_vec
+                    .iter()
+                    .find(|co_metadata| co_metadata.entry_point_name == entry_point_name);
+                let is_adoptable = match co_metadata {
+                    Some(co_metadata) => {
+                        !board_frames
+                            .iter()
+                            .any(|board_frame| board_frame.item_id == co_metadata.miro_frame_id)
+                            && Self::get_co_frames(board_frames, entry_point_name)
+                                .first()
+                                .map(|adoptable_frame| adoptable_frame.item_id.clone())
+                                == Some(frame.item_id.clone())
+                    }
+                    None => false,
+                };
+                if is_adoptable {
+                    return None;
+                }
+                Some(MiroDrift {
+                    drift_type: MiroDriftType::OrphanedFrame,
+                    entry_point_name: entry_point_name.to_string(),
+                    frame_id: frame.item_id.clone(),
+                    item: None,
+                    adoptable_frame_id: None,
+                })
+            })
+            .collect()
+    }
+
+    fn get_co_frames<'a>(
+        board_frames: &'a [MiroFrame],
+        entry_point_name: &str,
+    ) -> Vec<&'a MiroFrame> {
+        let frame_title = format!("{}{}", MIRO_CO_FRAME_TITLE_PREFIX, entry_point_name);
+        board_frames
+            .iter()
+            .filter(|frame| frame.title == frame_title)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod sync_test {
+    use super::*;
+    use crate::batbelt::metadata::miro_metadata::{SignerInfo, SignerType};
+    use crate::batbelt::miro::client::{MiroParent, MiroPosition};
+
+    fn new_co_metadata(entry_point_name: &str, miro_frame_id: &str) -> MiroCodeOverhaulMetadata {
+        MiroCodeOverhaulMetadata {
+            metadata_id: "metadata".to_string(),
+            entry_point_name: entry_point_name.to_string(),
+            miro_frame_id: miro_frame_id.to_string(),
+            images_deployed: true,
+            entry_point_image_id: "1".to_string(),
+            context_accounts_image_id: "2".to_string(),
+            validations_image_id: "".to_string(),
+            handler_image_id: "4".to_string(),
+            signers: vec![SignerInfo::new(
+                "Permissionless".to_string(),
+                "5".to_string(),
+                "6".to_string(),
+                SignerType::Permissionless,
+            )],
+            state_changes_image_ids: vec![],
+            handler_parameters_image_ids: vec![],
+        }
+    }
+
+    fn new_board_item(
+        item_id: &str,
+        item_type: &str,
+        parent_id: &str,
+        position: (i64, i64),
+    ) -> MiroItemResponse {
+        MiroItemResponse {
+            id: item_id.to_string(),
+            item_type: item_type.to_string(),
+            data: Default::default(),
+            geometry: None,
+            position: Some(MiroPosition::new_centered(position.0, position.1)),
+            parent: Some(MiroParent {
+                id: parent_id.to_string(),
+            }),
+        }
+    }
+
+    fn new_board_frame(title: &str, item_id: &str) -> MiroFrame {
+        let mut frame = MiroFrame::new(title, 0, 0, 0, 0);
+        frame.item_id = item_id.to_string();
+        frame
+    }
+
+    #[test]
+    fn test_get_tracked_items_and_orphaned_frames() {
+        let co_metadata_vec = vec![
+            new_co_metadata("deposit", "100"),
+            new_co_metadata("withdraw", "200"),
+        ];
+        let tracked_items =
+            MiroBoardSync::get_tracked_items(&co_metadata_vec[0], &MiroFrameLayout::default());
+        assert_eq!(tracked_items.len(), 5);
+        assert_eq!(tracked_items[3].label, "signer 1 sticky note");
+
+        let board_frames = vec![
+            new_board_frame("co: deposit", "100"),
+            new_board_frame("co: deposit", "101"),
+            new_board_frame("co: withdraw", "201"),
+            new_board_frame("co: swap", "300"),
+            new_board_frame("notes", "400"),
+        ];
+        let orphaned_frames = MiroBoardSync::get_orphaned_frames(&co_metadata_vec, &board_frames);
+        let orphaned_frame_ids = orphaned_frames
+            .iter()
+            .map(|drift| drift.frame_id.clone())
+            .collect::<Vec<_>>();
+        // 201 replaces the missing withdraw frame, so it's adoptable and not orphaned
+        assert_eq!(
+            orphaned_frame_ids,
+            vec!["101".to_string(), "300".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_get_drifts() {
+        let co_metadata_vec = vec![
+            new_co_metadata("deposit", "100"),
+            new_co_metadata("withdraw", "200"),
+        ];
+        let frame_layout = MiroFrameLayout::default();
+        let tracked_items = MiroBoardSync::get_tracked_items(&co_metadata_vec[0], &frame_layout);
+        let get_position = |item_id: &str| {
+            let tracked_item = tracked_items
+                .iter()
+                .find(|tracked_item| tracked_item.item_id == item_id)
+                .unwrap();
+            (tracked_item.x_position, tracked_item.y_position)
+        };
+        let (sticky_note_x, sticky_note_y) = get_position("5");
+        let board_items = vec![
+            new_board_item("1", "image", "100", get_position("1")),
+            // 2 was deleted, 4 was dragged to another frame
+            new_board_item("4", "image", "300", get_position("4")),
+            new_board_item(
+                "5",
+                "sticky_note",
+                "100",
+                (sticky_note_x + 50, sticky_note_y),
+            ),
+            new_board_item("6", "image", "100", get_position("6")),
+            new_board_item("7", "sticky_note", "100", (0, 0)),
+            new_board_item("8", "mindmap_node", "100", (0, 0)),
+        ];
+        let board_frames = vec![new_board_frame("co: deposit", "100")];
+        let drifts =
+            MiroBoardSync::get_drifts(&co_metadata_vec, &frame_layout, &board_frames, &board_items);
+        let drift_summaries = drifts
+            .iter()
+            .map(|drift| {
+                (
+                    drift.drift_type,
+                    drift
+                        .item
+                        .as_ref()
+                        .map(|item| item.item_id.clone())
+                        .unwrap_or(drift.frame_id.clone()),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            drift_summaries,
+            vec![
+                (MiroDriftType::MissingItem, "2".to_string()),
+                (MiroDriftType::MovedItem, "4".to_string()),
+                (MiroDriftType::MovedItem, "5".to_string()),
+                (MiroDriftType::UntrackedItem, "7".to_string()),
+                (MiroDriftType::MissingFrame, "200".to_string()),
+            ]
+        );
+
+        // the auto layout packs the screenshots, so only their frame is checked
+        let auto_layout = MiroFrameLayout {
+            auto_layout: true,
+            ..MiroFrameLayout::default()
+        };
+        let moved_screenshot = vec![new_board_item("1", "image", "100", (10, 10))];
+        let drifts = MiroBoardSync::get_drifts(
+            &co_metadata_vec[..1],
+            &auto_layout,
+            &board_frames,
+            &moved_screenshot,
+        );
+        assert!(!drifts
+            .iter()
+            .any(|drift| drift.item.as_ref().map(|item| item.item_id.as_str()) == Some("1")));
+    }
+}
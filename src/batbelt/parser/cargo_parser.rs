@@ -0,0 +1,589 @@
+use crate::batbelt::parser::{ParserError, ParserResult};
+use crate::batbelt::path::BatFile;
+use crate::config::BatConfig;
+
+use error_stack::{IntoReport, ResultExt};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CargoDependency {
+    /// name used on the source code
+    pub crate_name: String,
+    /// name of the package on crates.io, differs from crate_name for renamed dependencies
+    pub package_name: String,
+    pub version_requirement: String,
+    pub locked_version: Option<String>,
+    /// every version of the package in the Cargo.lock, more than one when other crates depend
+    /// on a different major
+    #[serde(default)]
+    pub locked_versions: Vec<String>,
+    pub is_workspace_crate: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CrateAdvisory {
+    pub crate_name: String,
+    /// comma separated comparators, i.e. ">=0.25.0, <0.27.0"
+    pub affected_versions: String,
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+struct CrateAdvisoriesFile {
+    #[serde(default)]
+    advisories: Vec<CrateAdvisory>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CargoParser {
+    pub cargo_toml_path: String,
+    pub cargo_lock_path: Option<String>,
+    pub dependencies: Vec<CargoDependency>,
+}
+
+impl CargoParser {
+    pub fn new_from_program_lib() -> ParserResult<Self> {
+        let program_lib_path = BatConfig::get_config()
+            .change_context(ParserError)?
+            .program_lib_path;
+        let program_folder = Path::new(&program_lib_path)
+            .parent()
+            .and_then(|src_folder| src_folder.parent())
+            .ok_or(ParserError)
+            .into_report()
+            .attach_printable(format!(
+                "Error getting the program folder for {}",
+                program_lib_path
+            ))?;
+        Self::new_from_program_folder(program_folder)
+    }
+
+    pub fn new_from_program_folder(program_folder: &Path) -> ParserResult<Self> {
+        let cargo_toml_path = program_folder.join("Cargo.toml");
+        let cargo_toml_content = fs::read_to_string(&cargo_toml_path)
+            .into_report()
+            .change_context(ParserError)
+            .attach_printable(format!(
+                "Error reading Cargo.toml in {}",
+                cargo_toml_path.display()
+            ))?;
+        let workspace_cargo_toml_content = match Self::find_workspace_cargo_toml(program_folder) {
+            Some(workspace_cargo_toml_path) => Some(
+                fs::read_to_string(&workspace_cargo_toml_path)
+                    .into_report()
+                    .change_context(ParserError)
+                    .attach_printable(format!(
+                        "Error reading workspace Cargo.toml in {}",
+                        workspace_cargo_toml_path.display()
+                    ))?,
+            ),
+            None => None,
+        };
+        let cargo_lock_path = Self::find_cargo_lock(program_folder);
+        let locked_packages = match &cargo_lock_path {
+            Some(lock_path) => {
+                let cargo_lock_content = fs::read_to_string(lock_path)
+                    .into_report()
+                    .change_context(ParserError)?;
+                Self::parse_cargo_lock(&cargo_lock_content)?
+            }
+            None => vec![],
+        };
+        let dependencies =
+            Self::parse_cargo_toml(&cargo_toml_content, workspace_cargo_toml_content.as_deref())?
+                .into_iter()
+                .map(|mut dependency| {
+                    dependency.locked_versions = locked_packages
+                        .iter()
+                        .filter(|(package_name, _)| {
+                            Self::normalize_crate_name(package_name) == dependency.package_name
+                        })
+                        .map(|(_, version)| version.clone())
+                        .collect();
+                    dependency.locked_version = Self::get_locked_version(
+                        &dependency.locked_versions,
+                        &dependency.version_requirement,
+                    );
+                    dependency
+                })
+                .collect::<Vec<_>>();
+        Ok(Self {
+            cargo_toml_path: cargo_toml_path.to_str().unwrap().to_string(),
+            cargo_lock_path: cargo_lock_path.map(|path| path.to_str().unwrap().to_string()),
+            dependencies,
+        })
+    }
+
+    pub fn get_dependency(&self, crate_name: &str) -> Option<CargoDependency> {
+        let crate_name = Self::normalize_crate_name(crate_name);
+        self.dependencies
+            .iter()
+            .find(|dependency| dependency.crate_name == crate_name)
+            .cloned()
+    }
+
+    /// Resolves the crate of a call path (like token::transfer) by using the use statements of the file
+    pub fn resolve_call_crate(
+        &self,
+        call_path: &str,
+        use_aliases: &HashMap<String, String>,
+    ) -> Option<CargoDependency> {
+        let root_segment = call_path.split("::").next().unwrap_or_default();
+        if let Some(dependency) = self.get_dependency(root_segment) {
+            return Some(dependency);
+        }
+        use_aliases
+            .get(root_segment)
+            .and_then(|crate_name| self.get_dependency(crate_name))
+    }
+
+    /// Reads the advisories registered in BatAdvisories.toml, empty if the file does not exist
+    pub fn get_crate_advisories() -> ParserResult<Vec<CrateAdvisory>> {
+        let advisories_file = BatFile::CrateAdvisories;
+        if !advisories_file.file_exists().change_context(ParserError)? {
+            return Ok(vec![]);
+        }
+        let advisories_content = advisories_file
+            .read_content(false)
+            .change_context(ParserError)?;
+        let advisories: CrateAdvisoriesFile = toml::from_str(&advisories_content)
+            .into_report()
+            .change_context(ParserError)
+            .attach_printable("Error parsing BatAdvisories.toml")?;
+        Ok(advisories.advisories)
+    }
+
+    pub fn get_matching_advisories(
+        dependency: &CargoDependency,
+        advisories: &[CrateAdvisory],
+    ) -> Vec<CrateAdvisory> {
+        let version = match &dependency.locked_version {
+            Some(locked_version) => locked_version.clone(),
+            None => dependency
+                .version_requirement
+                .trim_start_matches(['=', '^', '~'])
+                .to_string(),
+        };
+        advisories
+            .iter()
+            .filter(|advisory| {
+                Self::normalize_crate_name(&advisory.crate_name) == dependency.package_name
+                    && Self::version_matches(&version, &advisory.affected_versions)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Dependencies declared with workspace = true are resolved against the
+    /// [workspace.dependencies] of the workspace Cargo.toml, if any
+    pub fn parse_cargo_toml(
+        cargo_toml_content: &str,
+        workspace_cargo_toml_content: Option<&str>,
+    ) -> ParserResult<Vec<CargoDependency>> {
+        let cargo_toml: toml::Value = toml::from_str(cargo_toml_content)
+            .into_report()
+            .change_context(ParserError)
+            .attach_printable("Error parsing Cargo.toml")?;
+        let dependencies_table = match cargo_toml.get("dependencies") {
+            Some(toml::Value::Table(table)) => table.clone(),
+            _ => return Ok(vec![]),
+        };
+        let workspace_dependencies_table = match workspace_cargo_toml_content {
+            Some(workspace_content) => {
+                let workspace_cargo_toml: toml::Value = toml::from_str(workspace_content)
+                    .into_report()
+                    .change_context(ParserError)
+                    .attach_printable("Error parsing workspace Cargo.toml")?;
+                match workspace_cargo_toml
+                    .get("workspace")
+                    .and_then(|workspace| workspace.get("dependencies"))
+                {
+                    Some(toml::Value::Table(table)) => table.clone(),
+                    _ => toml::value::Table::new(),
+                }
+            }
+            None => toml::value::Table::new(),
+        };
+        let dependencies = dependencies_table
+            .into_iter()
+            .map(|(dependency_name, dependency_value)| {
+                let inherits_workspace = dependency_value
+                    .get("workspace")
+                    .and_then(|workspace| workspace.as_bool())
+                    .unwrap_or(false);
+                let dependency_value = match workspace_dependencies_table.get(&dependency_name) {
+                    Some(workspace_value) if inherits_workspace => workspace_value.clone(),
+                    _ => dependency_value,
+                };
+                let (package_name, version_requirement, is_workspace_crate) =
+                    Self::get_dependency_value_info(&dependency_name, dependency_value);
+                CargoDependency {
+                    crate_name: Self::normalize_crate_name(&dependency_name),
+                    package_name: Self::normalize_crate_name(&package_name),
+                    version_requirement,
+                    locked_version: None,
+                    locked_versions: vec![],
+                    is_workspace_crate,
+                }
+            })
+            .collect::<Vec<_>>();
+        Ok(dependencies)
+    }
+
+    /// Returns the (package name, version requirement, is workspace crate) of a dependency value
+    fn get_dependency_value_info(
+        dependency_name: &str,
+        dependency_value: toml::Value,
+    ) -> (String, String, bool) {
+        match dependency_value {
+            toml::Value::String(version) => (dependency_name.to_string(), version, false),
+            toml::Value::Table(table) => (
+                table
+                    .get("package")
+                    .and_then(|package| package.as_str())
+                    .unwrap_or(dependency_name)
+                    .to_string(),
+                table
+                    .get("version")
+                    .and_then(|version| version.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                table.contains_key("path"),
+            ),
+            _ => (dependency_name.to_string(), "".to_string(), false),
+        }
+    }
+
+    /// Returns the (name, version) of every package in the Cargo.lock
+    pub fn parse_cargo_lock(cargo_lock_content: &str) -> ParserResult<Vec<(String, String)>> {
+        let cargo_lock: toml::Value = toml::from_str(cargo_lock_content)
+            .into_report()
+            .change_context(ParserError)
+            .attach_printable("Error parsing Cargo.lock")?;
+        let packages = match cargo_lock.get("package") {
+            Some(toml::Value::Array(packages)) => packages.clone(),
+            _ => return Ok(vec![]),
+        };
+        Ok(packages
+            .into_iter()
+            .filter_map(|package| {
+                let name = package.get("name")?.as_str()?.to_string();
+                let version = package.get("version")?.as_str()?.to_string();
+                Some((name, version))
+            })
+            .collect())
+    }
+
+    /// Maps every name imported by a use statement to the root of its path
+    pub fn get_use_aliases(file_content: &str) -> HashMap<String, String> {
+        let use_regex = Regex::new(r"(?s)\buse\s+([^;]+);").unwrap();
+        let mut use_aliases = HashMap::new();
+        for use_capture in use_regex.captures_iter(file_content) {
+            let use_tree = use_capture[1]
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            for use_path in Self::expand_use_tree(&use_tree) {
+                let use_path = use_path.trim_start_matches("::");
+                let root_segment = use_path.split("::").next().unwrap_or_default().to_string();
+                if ["crate", "self", "super", "std", "core"].contains(&root_segment.as_str())
+                    || use_path.contains('*')
+                {
+                    continue;
+                }
+                let (imported_path, alias) = match use_path.split_once(" as ") {
+                    Some((imported_path, alias)) => (imported_path, Some(alias.trim())),
+                    None => (use_path, None),
+                };
+                let mut segments = imported_path.rsplit("::").map(|segment| segment.trim());
+                let local_name = match (alias, segments.next()) {
+                    (Some(alias), _) => alias,
+                    (None, Some("self")) => segments.next().unwrap_or_default(),
+                    (None, imported_name) => imported_name.unwrap_or_default(),
+                };
+                if local_name.is_empty() || local_name == "_" {
+                    continue;
+                }
+                use_aliases.insert(local_name.to_string(), root_segment);
+            }
+        }
+        use_aliases
+    }
+
+    /// Expands a use tree into one path per imported name, i.e. a::{b::{c, d}, e} into
+    /// a::b::c, a::b::d and a::e
+    fn expand_use_tree(use_tree: &str) -> Vec<String> {
+        let use_tree = use_tree.trim();
+        let open_index = match use_tree.find('{') {
+            Some(open_index) => open_index,
+            None => return vec![use_tree.to_string()],
+        };
+        let prefix = &use_tree[..open_index];
+        let close_index = use_tree
+            .rfind('}')
+            .filter(|close_index| *close_index > open_index)
+            .unwrap_or(use_tree.len());
+        let mut nested_trees = vec![];
+        let mut depth = 0;
+        let mut tree_start = open_index + 1;
+        for (char_index, character) in use_tree[..close_index].char_indices().skip(tree_start) {
+            match character {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    nested_trees.push(&use_tree[tree_start..char_index]);
+                    tree_start = char_index + 1;
+                }
+                _ => {}
+            }
+        }
+        nested_trees.push(&use_tree[tree_start..close_index]);
+        nested_trees
+            .into_iter()
+            .filter(|nested_tree| !nested_tree.trim().is_empty())
+            .flat_map(Self::expand_use_tree)
+            .map(|use_path| format!("{}{}", prefix, use_path))
+            .collect()
+    }
+
+    pub fn normalize_crate_name(crate_name: &str) -> String {
+        crate_name.replace('-', "_")
+    }
+
+    /// The locked version matching the requirement, the highest one if many match. A single
+    /// locked version is used even if it doesn't match, i.e. for path dependencies
+    pub fn get_locked_version(
+        locked_versions: &[String],
+        version_requirement: &str,
+    ) -> Option<String> {
+        if locked_versions.len() == 1 {
+            return locked_versions.first().cloned();
+        }
+        locked_versions
+            .iter()
+            .filter(|locked_version| Self::requirement_matches(locked_version, version_requirement))
+            .max_by_key(|locked_version| Self::parse_version(locked_version))
+            .cloned()
+    }
+
+    /// Checks a version against a Cargo.toml requirement, a bare version is a caret requirement
+    pub fn requirement_matches(version: &str, version_requirement: &str) -> bool {
+        let version_requirement = version_requirement.trim();
+        if version_requirement.is_empty() || version_requirement == "*" {
+            return true;
+        }
+        if let Some(tilde_requirement) = version_requirement.strip_prefix('~') {
+            let parsed_version = Self::parse_version(version);
+            let parsed_requirement = Self::parse_version(tilde_requirement.trim());
+            return parsed_version[..2] == parsed_requirement[..2]
+                && parsed_version >= parsed_requirement;
+        }
+        if version_requirement.contains(',')
+            || ['<', '>', '=']
+                .iter()
+                .any(|operator| version_requirement.starts_with(*operator))
+        {
+            return Self::version_matches(version, version_requirement);
+        }
+        let parsed_version = Self::parse_version(version);
+        let parsed_requirement =
+            Self::parse_version(version_requirement.trim_start_matches('^').trim());
+        // the components up to the first non zero one can't change
+        let fixed_components = parsed_requirement
+            .iter()
+            .position(|component| *component != 0)
+            .map(|position| position + 1)
+            .unwrap_or(parsed_requirement.len());
+        parsed_version[..fixed_components] == parsed_requirement[..fixed_components]
+            && parsed_version >= parsed_requirement
+    }
+
+    /// Checks a version against comma separated comparators (<, <=, >, >=, =)
+    pub fn version_matches(version: &str, affected_versions: &str) -> bool {
+        let version = Self::parse_version(version);
+        affected_versions.split(',').all(|comparator| {
+            let comparator = comparator.trim();
+            let (operator, comparator_version) = ["<=", ">=", "<", ">", "="]
+                .iter()
+                .find_map(|operator| {
+                    comparator
+                        .strip_prefix(operator)
+                        .map(|comparator_version| (*operator, comparator_version))
+                })
+                .unwrap_or(("=", comparator));
+            let comparator_version = Self::parse_version(comparator_version.trim());
+            match operator {
+                "<=" => version <= comparator_version,
+                ">=" => version >= comparator_version,
+                "<" => version < comparator_version,
+                ">" => version > comparator_version,
+                _ => version == comparator_version,
+            }
+        })
+    }
+
+    fn parse_version(version: &str) -> Vec<u64> {
+        let mut parsed = version
+            .split('-')
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|number| number.trim().parse::<u64>().unwrap_or(0))
+            .collect::<Vec<_>>();
+        parsed.resize(3, 0);
+        parsed
+    }
+
+    /// The closest Cargo.toml declaring a [workspace], starting from the program folder
+    fn find_workspace_cargo_toml(program_folder: &Path) -> Option<PathBuf> {
+        program_folder
+            .ancestors()
+            .map(|folder| folder.join("Cargo.toml"))
+            .filter(|cargo_toml_path| cargo_toml_path.is_file())
+            .find(|cargo_toml_path| {
+                fs::read_to_string(cargo_toml_path)
+                    .ok()
+                    .and_then(|content| toml::from_str::<toml::Value>(&content).ok())
+                    .is_some_and(|cargo_toml| cargo_toml.get("workspace").is_some())
+            })
+    }
+
+    fn find_cargo_lock(program_folder: &Path) -> Option<PathBuf> {
+        program_folder
+            .ancestors()
+            .map(|folder| folder.join("Cargo.lock"))
+            .find(|cargo_lock_path| cargo_lock_path.is_file())
+    }
+}
+
+#[cfg(test)]
+mod cargo_parser_test {
+    use crate::batbelt::parser::cargo_parser::CargoParser;
+
+    #[test]
+    fn test_parse_cargo_toml() {
+        let cargo_toml = r#"
+[package]
+name = "test-program"
+
+[dependencies]
+anchor-lang = "0.26.0"
+anchor-spl = { version = "0.26.0", features = ["token"] }
+test-common = { path = "../common" }
+"#;
+        let dependencies = CargoParser::parse_cargo_toml(cargo_toml, None).unwrap();
+        assert_eq!(dependencies.len(), 3);
+        assert_eq!(dependencies[0].crate_name, "anchor_lang");
+        assert_eq!(dependencies[1].version_requirement, "0.26.0");
+        assert!(dependencies[2].is_workspace_crate);
+    }
+
+    #[test]
+    fn test_get_use_aliases() {
+        let file_content = "
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer as TokenTransfer,
+    transfer};
+use spl_token::instruction::close_account;
+use crate::state::Vault;
+";
+        let use_aliases = CargoParser::get_use_aliases(file_content);
+        assert_eq!(use_aliases.get("token").unwrap(), "anchor_spl");
+        assert_eq!(use_aliases.get("TokenTransfer").unwrap(), "anchor_spl");
+        assert_eq!(use_aliases.get("transfer").unwrap(), "anchor_spl");
+        assert_eq!(use_aliases.get("close_account").unwrap(), "spl_token");
+        assert!(use_aliases.get("Vault").is_none());
+    }
+
+    #[test]
+    fn test_parse_cargo_toml_workspace_dependencies() {
+        let workspace_cargo_toml = r#"
+[workspace]
+members = ["programs/*"]
+
+[workspace.dependencies]
+anchor-lang = "0.28.0"
+spl = { package = "anchor-spl", version = "0.28.0" }
+test-common = { path = "programs/common" }
+"#;
+        let cargo_toml = r#"
+[package]
+name = "test-program"
+
+[dependencies]
+anchor-lang = { workspace = true, features = ["init-if-needed"] }
+spl.workspace = true
+test-common = { workspace = true }
+borsh = "0.10.3"
+"#;
+        let dependencies =
+            CargoParser::parse_cargo_toml(cargo_toml, Some(workspace_cargo_toml)).unwrap();
+        assert_eq!(dependencies.len(), 4);
+        assert_eq!(dependencies[0].crate_name, "anchor_lang");
+        assert_eq!(dependencies[0].version_requirement, "0.28.0");
+        assert_eq!(dependencies[1].version_requirement, "0.10.3");
+        assert_eq!(dependencies[2].crate_name, "spl");
+        assert_eq!(dependencies[2].package_name, "anchor_spl");
+        assert_eq!(dependencies[2].version_requirement, "0.28.0");
+        assert!(dependencies[3].is_workspace_crate);
+
+        // without the workspace Cargo.toml the version is unknown
+        let dependencies = CargoParser::parse_cargo_toml(cargo_toml, None).unwrap();
+        assert_eq!(dependencies[0].version_requirement, "");
+    }
+
+    #[test]
+    fn test_get_use_aliases_nested_groups() {
+        let file_content = "
+use anchor_spl::{
+    token::{self, Mint, transfer as token_transfer},
+    associated_token::{AssociatedToken},
+};
+use {crate::state::Vault, spl_token::instruction::close_account};
+use mpl_token_metadata::{instruction::{create_metadata_accounts_v3, *}, ID};
+";
+        let use_aliases = CargoParser::get_use_aliases(file_content);
+        assert_eq!(use_aliases.get("token").unwrap(), "anchor_spl");
+        assert_eq!(use_aliases.get("Mint").unwrap(), "anchor_spl");
+        assert_eq!(use_aliases.get("token_transfer").unwrap(), "anchor_spl");
+        assert_eq!(use_aliases.get("AssociatedToken").unwrap(), "anchor_spl");
+        assert_eq!(use_aliases.get("close_account").unwrap(), "spl_token");
+        assert_eq!(
+            use_aliases.get("create_metadata_accounts_v3").unwrap(),
+            "mpl_token_metadata"
+        );
+        assert_eq!(use_aliases.get("ID").unwrap(), "mpl_token_metadata");
+        assert!(use_aliases.get("Vault").is_none());
+        assert_eq!(use_aliases.len(), 7);
+    }
+
+    #[test]
+    fn test_version_matches() {
+        assert!(CargoParser::version_matches("0.26.0", ">=0.25.0, <0.27.0"));
+        assert!(!CargoParser::version_matches("0.27.0", ">=0.25.0, <0.27.0"));
+        assert!(CargoParser::version_matches("1.14.6", "=1.14.6"));
+
+        assert!(CargoParser::requirement_matches("0.26.3", "0.26.0"));
+        assert!(!CargoParser::requirement_matches("0.27.0", "^0.26"));
+        assert!(CargoParser::requirement_matches("1.16.0", "1.14"));
+        assert!(!CargoParser::requirement_matches("1.15.0", "~1.14.2"));
+        let locked_versions = vec![
+            "3.5.0".to_string(),
+            "4.0.0".to_string(),
+            "4.1.2".to_string(),
+        ];
+        assert_eq!(
+            CargoParser::get_locked_version(&locked_versions, "4.0"),
+            Some("4.1.2".to_string())
+        );
+        assert_eq!(
+            CargoParser::get_locked_version(&locked_versions, "3"),
+            Some("3.5.0".to_string())
+        );
+        assert_eq!(CargoParser::get_locked_version(&locked_versions, "5"), None);
+    }
+}
@@ -6,11 +6,14 @@ use crate::batbelt::miro::MiroItemType;
 use crate::batbelt::metadata::MiroMetadata;
 use crate::batbelt::parser::{ParserError, ParserResult};
 use crate::batbelt::path::{BatFile, BatFolder};
+use crate::batbelt::silicon;
+use crate::batbelt::sonar::frontend::SourceLanguage;
 use crate::batbelt::sonar::BatSonar;
 use crate::batbelt::templates::code_overhaul_template::{
-    CodeOverhaulSection, CoderOverhaulTemplatePlaceholders,
+    get_code_overhaul_section_content, CodeOverhaulSection, CodeOverhaulTemplateDefinition,
+    CoderOverhaulTemplatePlaceholders,
 };
-use crate::batbelt::{silicon, BatEnumerator};
+use crate::batbelt::BatEnumerator;
 use crate::commands::miro_commands::{miro_command_functions, MiroCommand};
 use colored::Colorize;
 use error_stack::{IntoReport, Report, ResultExt};
@@ -35,12 +38,22 @@ pub struct CodeOverhaulSectionsContent {
     pub miro_frame_url: String,
 }
 
+/// Section declared on the code-overhaul template, with its header
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeOverhaulParsedSection {
+    pub built_in: Option<CodeOverhaulSection>,
+    pub title: String,
+    pub content: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeOverhaulParser {
     pub entry_point_name: String,
     pub co_bat_file: BatFile,
     pub signers: Vec<CodeOverhaulSigner>,
     pub section_content: CodeOverhaulSectionsContent,
+    /// every section of the template, in the template order
+    pub parsed_sections: Vec<CodeOverhaulParsedSection>,
 }
 
 impl CodeOverhaulParser {
@@ -80,6 +93,7 @@ impl CodeOverhaulParser {
                 validations: "".to_string(),
                 miro_frame_url: "".to_string(),
             },
+            parsed_sections: vec![],
         };
 
         new_co_parser.get_sections_content()?;
@@ -88,21 +102,44 @@ impl CodeOverhaulParser {
     }
 
     fn get_sections_content(&mut self) -> ParserResult<()> {
+        let bat_file_content = self
+            .co_bat_file
+            .read_content(true)
+            .change_context(ParserError)?;
+        let co_template_definition =
+            CodeOverhaulTemplateDefinition::read_definition().change_context(ParserError)?;
+        let markdown_headers = co_template_definition.get_markdown_headers();
+        let get_built_in_content = |code_overhaul_section: CodeOverhaulSection| {
+            get_code_overhaul_section_content(
+                &bat_file_content,
+                &code_overhaul_section.to_markdown_header(),
+                &markdown_headers,
+            )
+        };
         self.section_content = CodeOverhaulSectionsContent {
-            state_changes: self
-                .extract_section_content_from_co_file(CodeOverhaulSection::StateChanges)?,
-            notes: self.extract_section_content_from_co_file(CodeOverhaulSection::Notes)?,
-            signers: self.extract_section_content_from_co_file(CodeOverhaulSection::Signers)?,
-            handler_function_parameters: self.extract_section_content_from_co_file(
+            state_changes: get_built_in_content(CodeOverhaulSection::StateChanges),
+            notes: get_built_in_content(CodeOverhaulSection::Notes),
+            signers: get_built_in_content(CodeOverhaulSection::Signers),
+            handler_function_parameters: get_built_in_content(
                 CodeOverhaulSection::HandlerFunctionParameters,
-            )?,
-            context_accounts: self
-                .extract_section_content_from_co_file(CodeOverhaulSection::ContextAccounts)?,
-            validations: self
-                .extract_section_content_from_co_file(CodeOverhaulSection::Validations)?,
-            miro_frame_url: self
-                .extract_section_content_from_co_file(CodeOverhaulSection::MiroFrameUrl)?,
+            ),
+            context_accounts: get_built_in_content(CodeOverhaulSection::ContextAccounts),
+            validations: get_built_in_content(CodeOverhaulSection::Validations),
+            miro_frame_url: get_built_in_content(CodeOverhaulSection::MiroFrameUrl),
         };
+        self.parsed_sections = co_template_definition
+            .sections
+            .iter()
+            .map(|section| CodeOverhaulParsedSection {
+                built_in: section.built_in.clone(),
+                title: section.get_title(),
+                content: get_code_overhaul_section_content(
+                    &bat_file_content,
+                    &section.to_markdown_header(),
+                    &markdown_headers,
+                ),
+            })
+            .collect();
         Ok(())
     }
 
@@ -261,6 +298,16 @@ impl CodeOverhaulParser {
         Ok(())
     }
 
+    pub fn create_validations_screenshot(&self, file_name: &str) -> ParserResult<String> {
+        let content = self.get_validations_image_content()?;
+        self.create_screenshot_with_silicon(content, file_name)
+    }
+
+    pub fn create_context_accounts_screenshot(&self, file_name: &str) -> ParserResult<String> {
+        let content = self.get_context_accounts_image_content()?;
+        self.create_screenshot_with_silicon(content, file_name)
+    }
+
     fn create_screenshot_with_silicon(
         &self,
         content: String,
@@ -270,30 +317,96 @@ impl CodeOverhaulParser {
             .get_path(false)
             .change_context(ParserError)?;
 
-        let sc_path =
-            silicon::create_figure(&content, &auditor_figures_path, file_name, 0, None, false);
+        let source_language = SourceLanguage::get_program_language().change_context(ParserError)?;
+        let sc_path = silicon::create_figure(
+            &content,
+            &auditor_figures_path,
+            file_name,
+            0,
+            None,
+            false,
+            source_language,
+        );
         Ok(sc_path)
     }
 
+    /// Sections for the code-overhaul summary with their headers demoted, the custom ones keep
+    /// their checklists and the built-in ones only the audit notes
+    pub fn get_summary_sections_content(&self) -> Vec<String> {
+        self.parsed_sections
+            .iter()
+            .filter(|parsed_section| {
+                !parsed_section.content.is_empty()
+                    && match &parsed_section.built_in {
+                        Some(code_overhaul_section) => matches!(
+                            code_overhaul_section,
+                            CodeOverhaulSection::StateChanges
+                                | CodeOverhaulSection::Notes
+                                | CodeOverhaulSection::MiroFrameUrl
+                        ),
+                        None => true,
+                    }
+            })
+            .map(|parsed_section| format!("#{}", parsed_section.content))
+            .collect()
+    }
+
+    /// Content of a built-in section needed for the Miro screenshots, errors if the file does not
+    /// have it instead of deploying an empty screenshot
+    pub fn get_built_in_section_content(
+        &self,
+        code_overhaul_section: CodeOverhaulSection,
+    ) -> ParserResult<String> {
+        let section_content = match code_overhaul_section {
+            CodeOverhaulSection::StateChanges => &self.section_content.state_changes,
+            CodeOverhaulSection::Notes => &self.section_content.notes,
+            CodeOverhaulSection::Signers => &self.section_content.signers,
+            CodeOverhaulSection::HandlerFunctionParameters => {
+                &self.section_content.handler_function_parameters
+            }
+            CodeOverhaulSection::ContextAccounts => &self.section_content.context_accounts,
+            CodeOverhaulSection::Validations => &self.section_content.validations,
+            CodeOverhaulSection::MiroFrameUrl => &self.section_content.miro_frame_url,
+        };
+        if section_content.is_empty() {
+            return Err(Report::new(ParserError).attach_printable(format!(
+                "{} section not found on the code-overhaul file of {}, add it to the file and to code_overhaul_template.toml to deploy its screenshot",
+                code_overhaul_section.to_markdown_header(),
+                self.entry_point_name
+            )));
+        }
+        Ok(section_content.clone())
+    }
+
     fn get_validations_image_content(&self) -> ParserResult<String> {
         let header = "/// Validations";
-        let validations_image_content =
-            if self.section_content.validations.contains(
-                &CoderOverhaulTemplatePlaceholders::NoValidationsDetected.to_placeholder(),
-            ) {
-                CoderOverhaulTemplatePlaceholders::NoValidationsDetected.to_placeholder()
-            } else {
-                self.rust_subsection_matcher(&self.section_content.validations.clone(), true)?
-                    .join("\n\n")
-            };
+        let validations = self.get_built_in_section_content(CodeOverhaulSection::Validations)?;
+        let validations_image_content = if validations
+            .contains(&CoderOverhaulTemplatePlaceholders::NoValidationsDetected.to_placeholder())
+        {
+            CoderOverhaulTemplatePlaceholders::NoValidationsDetected.to_placeholder()
+        } else {
+            self.rust_subsection_matcher(&validations, true)?
+                .join("\n\n")
+        };
         let content = format!("{}\n\n{}", header, validations_image_content);
         Ok(content)
     }
 
     fn get_context_accounts_image_content(&self) -> ParserResult<String> {
         let header = "/// Context accounts";
-        let context_accounts_image_content =
-            self.rust_subsection_matcher(&self.section_content.context_accounts, false)?[0].clone();
+        let context_accounts =
+            self.get_built_in_section_content(CodeOverhaulSection::ContextAccounts)?;
+        let context_accounts_image_content = self
+            .rust_subsection_matcher(&context_accounts, false)?
+            .first()
+            .cloned()
+            .ok_or(ParserError)
+            .into_report()
+            .attach_printable(format!(
+                "context accounts code block not found on the code-overhaul file of {}",
+                self.entry_point_name
+            ))?;
         let ca_formatted = self.format_trailing_whitespaces(&context_accounts_image_content);
         let content = format!("{}\n\n{}", header, ca_formatted);
         Ok(content)
@@ -326,43 +439,6 @@ impl CodeOverhaulParser {
         Ok(())
     }
 
-    fn extract_section_content_from_co_file(
-        &self,
-        code_overhaul_section: CodeOverhaulSection,
-    ) -> ParserResult<String> {
-        let bat_file_content = self
-            .co_bat_file
-            .read_content(true)
-            .change_context(ParserError)?;
-        let section_header = code_overhaul_section.to_markdown_header();
-        let next_section_header = if code_overhaul_section.get_index_of_type_vec() + 1
-            < CodeOverhaulSection::get_type_vec().len()
-        {
-            CodeOverhaulSection::from_index(code_overhaul_section.get_index_of_type_vec() + 1)
-                .to_markdown_header()
-        } else {
-            "".to_string()
-        };
-        log::debug!("{bat_file_content}");
-        log::debug!("{section_header}");
-        log::debug!("{next_section_header}");
-        let section_content_regex = Regex::new(&format!(
-            r#"({})[\s\S]+({})"#,
-            section_header, next_section_header
-        ))
-        .into_report()
-        .change_context(ParserError)?;
-        let section_content = section_content_regex
-            .find(&bat_file_content)
-            .ok_or(ParserError)
-            .into_report()?
-            .as_str()
-            .trim_end_matches(&next_section_header)
-            .trim()
-            .to_string();
-        Ok(section_content)
-    }
-
     fn format_trailing_whitespaces(&self, content: &str) -> String {
         let content_lines = content.lines();
         let trailing_ws_first_line =
@@ -386,7 +462,12 @@ impl CodeOverhaulParser {
         content: &str,
         use_separator: bool,
     ) -> ParserResult<Vec<String>> {
-        let rust_regex = Regex::new(r#"```rust([\s\S]*?)```"#)
+        let code_block_languages = SourceLanguage::get_type_vec()
+            .iter()
+            .map(|source_language| source_language.get_markdown_language())
+            .collect::<Vec<_>>()
+            .join("|");
+        let rust_regex = Regex::new(&format!(r#"```(?:{})([\s\S]*?)```"#, code_block_languages))
             .into_report()
             .change_context(ParserError)?;
         let mut max_trailing_ws = 0;
@@ -421,3 +502,61 @@ impl CodeOverhaulParser {
         Ok(vec![])
     }
 }
+
+#[test]
+fn test_code_overhaul_parser_sections() {
+    let parsed_section = |built_in: Option<CodeOverhaulSection>, title: &str, content: &str| {
+        CodeOverhaulParsedSection {
+            built_in,
+            title: title.to_string(),
+            content: content.to_string(),
+        }
+    };
+    let co_parser = CodeOverhaulParser {
+        entry_point_name: "withdraw".to_string(),
+        co_bat_file: BatFile::CodeOverhaulFinished {
+            file_name: "withdraw".to_string(),
+        },
+        signers: vec![],
+        section_content: CodeOverhaulSectionsContent {
+            state_changes: "# State changes:\n\n- Withdraws the vault".to_string(),
+            notes: "".to_string(),
+            signers: "".to_string(),
+            handler_function_parameters: "".to_string(),
+            context_accounts: "".to_string(),
+            validations: "# Validations:\n\n- NO_VALIDATIONS_DETECTED".to_string(),
+            miro_frame_url: "".to_string(),
+        },
+        parsed_sections: vec![
+            parsed_section(
+                Some(CodeOverhaulSection::StateChanges),
+                "State changes",
+                "# State changes:\n\n- Withdraws the vault",
+            ),
+            parsed_section(
+                Some(CodeOverhaulSection::Validations),
+                "Validations",
+                "# Validations:\n\n- NO_VALIDATIONS_DETECTED",
+            ),
+            parsed_section(
+                None,
+                "Reentrancy via CPI",
+                "# Reentrancy via CPI:\n\n- [x] state is updated before the CPI",
+            ),
+            parsed_section(Some(CodeOverhaulSection::Notes), "Notes", ""),
+        ],
+    };
+    assert_eq!(
+        co_parser.get_summary_sections_content(),
+        vec![
+            "## State changes:\n\n- Withdraws the vault".to_string(),
+            "## Reentrancy via CPI:\n\n- [x] state is updated before the CPI".to_string(),
+        ]
+    );
+    assert!(co_parser
+        .get_built_in_section_content(CodeOverhaulSection::Validations)
+        .is_ok());
+    assert!(co_parser
+        .get_built_in_section_content(CodeOverhaulSection::Signers)
+        .is_err());
+}
@@ -5,7 +5,6 @@ use crate::batbelt::metadata::functions_source_code_metadata::{
 use crate::batbelt::metadata::structs_source_code_metadata::{
     StructMetadataType, StructSourceCodeMetadata,
 };
-use crate::batbelt::sonar::{BatSonar, SonarResultType};
 
 use crate::config::BatConfig;
 
@@ -15,7 +14,11 @@ use std::fs;
 use crate::batbelt::metadata::entrypoint_metadata::EntrypointMetadata;
 use crate::batbelt::metadata::{BatMetadata, BatMetadataParser, SourceCodeMetadata};
 
+use crate::batbelt::parser::macro_parser::{MacroParser, ProgramEntrypoint};
+use crate::batbelt::parser::native_parser::NativeParser;
 use crate::batbelt::parser::ParserError;
+use crate::batbelt::path::BatFolder;
+use crate::batbelt::sonar::frontend::SourceLanguage;
 
 #[derive(Clone, Debug)]
 pub struct EntrypointParser {
@@ -70,16 +73,33 @@ impl EntrypointParser {
             });
         };
 
-        let entrypoint_section = BatMetadata::read_metadata()
+        if NativeParser::is_native_program()? {
+            return Self::new_native_from_name(entrypoint_name);
+        }
+
+        // Solidity entry points are qualified with the contract name
+        let program_language =
+            SourceLanguage::get_program_language().change_context(ParserError)?;
+        let frontend = program_language.get_frontend();
+        let mut entrypoint_section = vec![];
+        for func_meta in BatMetadata::read_metadata()
             .change_context(ParserError)?
             .source_code
             .functions_source_code
             .into_iter()
             .filter(|func_meta| {
-                func_meta.name == entrypoint_name
-                    && func_meta.function_type == FunctionMetadataType::EntryPoint
+                func_meta.function_type == FunctionMetadataType::EntryPoint
+                    && entrypoint_name.rsplit('.').next() == Some(func_meta.name.as_str())
             })
-            .collect::<Vec<_>>();
+        {
+            if frontend
+                .get_entrypoint_name(&func_meta)
+                .change_context(ParserError)?
+                == entrypoint_name
+            {
+                entrypoint_section.push(func_meta);
+            }
+        }
 
         if entrypoint_section.len() != 1 {
             return Err(Report::new(ParserError)
@@ -101,7 +121,7 @@ impl EntrypointParser {
         let handlers =
             SourceCodeMetadata::get_filtered_functions(None, Some(FunctionMetadataType::Handler))
                 .change_context(ParserError)?;
-        let context_name = Self::get_context_name(entrypoint_name).unwrap();
+        let context_name = Self::get_context_name(entrypoint_name)?;
 
         let handler = handlers.into_iter().find(|function_metadata| {
             let function_source_code = function_metadata.to_source_code_parser(None);
@@ -119,7 +139,15 @@ impl EntrypointParser {
         .change_context(ParserError)?;
         let context_accounts = structs_metadata
             .iter()
-            .find(|struct_metadata| struct_metadata.name == context_name)
+            .find(|struct_metadata| {
+                struct_metadata.name == context_name
+                    && struct_metadata.path == entrypoint_function.path
+            })
+            .or_else(|| {
+                structs_metadata
+                    .iter()
+                    .find(|struct_metadata| struct_metadata.name == context_name)
+            })
             .ok_or(ParserError)
             .into_report()
             .attach_printable(format!(
@@ -149,73 +177,169 @@ impl EntrypointParser {
         })
     }
 
-    pub fn get_entrypoint_names(sorted: bool) -> Result<Vec<String>, ParserError> {
-        let BatConfig {
-            program_lib_path, ..
-        } = BatConfig::get_config().change_context(ParserError)?;
+    /// Native programs don't have a Context, so the processor of the instruction is the entry
+    /// point function and the accounts are taken from its next_account_info calls
+    fn new_native_from_name(entrypoint_name: &str) -> Result<Self, ParserError> {
+        let instruction = NativeParser::get_program_instruction(entrypoint_name)?;
+        let entrypoint_function = NativeParser::get_processor_function(&instruction)?;
+        let context_name = NativeParser::get_context_accounts_name(&instruction.variant_name);
+        let context_accounts = SourceCodeMetadata::get_filtered_structs(
+            Some(context_name.clone()),
+            Some(StructMetadataType::ContextAccounts),
+        )
+        .change_context(ParserError)?
+        .into_iter()
+        .find(|struct_metadata| struct_metadata.name == context_name)
+        .ok_or(ParserError)
+        .into_report()
+        .attach_printable(format!(
+            "Error context_accounts struct by name {} for entrypoint_name: {}",
+            context_name, entrypoint_name
+        ))?;
+        let ep_metadata = EntrypointMetadata {
+            name: entrypoint_name.to_string(),
+            metadata_id: BatMetadata::create_metadata_id(),
+            handler_id: None,
+            context_accounts_id: context_accounts.metadata_id.clone(),
+            entrypoint_function_id: entrypoint_function.metadata_id.clone(),
+        };
 
-        let bat_sonar = BatSonar::new_from_path(
-            &program_lib_path,
-            Some("#[program"),
-            SonarResultType::Function,
-        );
-        let mut entrypoints_names: Vec<String> = bat_sonar
-            .results
-            .iter()
-            .map(|entrypoint| entrypoint.name.clone())
-            .collect();
+        ep_metadata
+            .update_metadata_file()
+            .change_context(ParserError)?;
+
+        Ok(Self {
+            name: entrypoint_name.to_string(),
+            handler: None,
+            context_accounts,
+            entry_point_function: entrypoint_function,
+        })
+    }
+
+    pub fn get_entrypoint_names(sorted: bool) -> Result<Vec<String>, ParserError> {
+        let mut entrypoints_names: Vec<String> = if NativeParser::is_native_program()? {
+            NativeParser::get_program_instructions()?
+                .into_iter()
+                .map(|instruction| instruction.entrypoint_name)
+                .collect()
+        } else if SourceLanguage::get_program_language().change_context(ParserError)?
+            != SourceLanguage::Rust
+        {
+            Self::get_frontend_entrypoint_names()?
+        } else {
+            Self::get_program_entrypoints()?
+                .into_iter()
+                .map(|entrypoint| entrypoint.name)
+                .collect()
+        };
         if sorted {
             entrypoints_names.sort();
         }
         Ok(entrypoints_names)
     }
 
+    /// Entry points of the program files written in the language of the program lib file
+    fn get_frontend_entrypoint_names() -> Result<Vec<String>, ParserError> {
+        let program_language =
+            SourceLanguage::get_program_language().change_context(ParserError)?;
+        let frontend = program_language.get_frontend();
+        let mut entrypoints_names = vec![];
+        for dir_entry in BatFolder::ProgramPath
+            .get_all_files_dir_entries(true, None, None)
+            .change_context(ParserError)?
+        {
+            let file_path = dir_entry.path().to_str().unwrap().to_string();
+            if SourceLanguage::from_path(&file_path) != Some(program_language) {
+                continue;
+            }
+            let file_content = fs::read_to_string(&file_path)
+                .into_report()
+                .change_context(ParserError)
+                .attach_printable(format!("Error reading file in {}", file_path))?;
+            for entrypoint_name in frontend
+                .get_entrypoint_names(&file_content)
+                .change_context(ParserError)?
+            {
+                if !entrypoints_names.contains(&entrypoint_name) {
+                    entrypoints_names.push(entrypoint_name);
+                }
+            }
+        }
+        Ok(entrypoints_names)
+    }
+
+    pub fn get_program_entrypoints() -> Result<Vec<ProgramEntrypoint>, ParserError> {
+        let BatConfig {
+            program_lib_path,
+            entry_point_macros,
+            ..
+        } = BatConfig::get_config().change_context(ParserError)?;
+        let lib_file = fs::read_to_string(&program_lib_path)
+            .into_report()
+            .change_context(ParserError)
+            .attach_printable(format!("Error reading lib file in {}", program_lib_path))?;
+        Ok(MacroParser::get_program_entrypoints(
+            &lib_file,
+            &entry_point_macros,
+        ))
+    }
+
     pub fn get_all_contexts_names() -> Vec<String> {
+        if NativeParser::is_native_program().unwrap() {
+            return NativeParser::get_program_instructions()
+                .unwrap()
+                .into_iter()
+                .map(|instruction| {
+                    NativeParser::get_context_accounts_name(&instruction.variant_name)
+                })
+                .collect();
+        }
         let entrypoints_names = Self::get_entrypoint_names(false).unwrap();
 
         entrypoints_names
             .into_iter()
-            .map(|ep_name| Self::get_context_name(&ep_name).unwrap())
+            .filter_map(|ep_name| Self::get_context_name(&ep_name).ok())
             .collect::<Vec<_>>()
     }
 
     pub fn get_context_name(entrypoint_name: &str) -> Result<String, ParserError> {
+        if NativeParser::is_native_program()? {
+            let instruction = NativeParser::get_program_instruction(entrypoint_name)?;
+            return Ok(NativeParser::get_context_accounts_name(
+                &instruction.variant_name,
+            ));
+        }
+        let program_language =
+            SourceLanguage::get_program_language().change_context(ParserError)?;
+        if program_language != SourceLanguage::Rust {
+            return program_language
+                .get_frontend()
+                .get_context_accounts_name(entrypoint_name)
+                .change_context(ParserError);
+        }
         let BatConfig {
             program_lib_path, ..
         } = BatConfig::get_config().change_context(ParserError)?;
         let lib_file = fs::read_to_string(program_lib_path).unwrap();
         let lib_file_lines: Vec<&str> = lib_file.lines().collect();
-        let entrypoint_index = lib_file
-            .lines()
-            .position(|line| {
-                if line.contains("pub fn") {
-                    let function_name = line
-                        .split('(')
-                        .next()
-                        .unwrap()
-                        .split('<')
-                        .next()
-                        .unwrap()
-                        .split_whitespace()
-                        .last()
-                        .unwrap();
-                    function_name == entrypoint_name.replace(".md", "")
-                } else {
-                    false
-                }
-            })
-            .unwrap();
-        let canditate_lines = vec![
-            lib_file_lines[entrypoint_index],
-            lib_file_lines[entrypoint_index + 1],
-        ];
-
-        // if is not in the same line as the entrypoint name, is in the next line
-        let context_line = if canditate_lines[0].contains("Context<") {
-            canditate_lines[0]
-        } else {
-            canditate_lines[1]
-        };
+        let entrypoint = Self::get_program_entrypoints()?
+            .into_iter()
+            .find(|entrypoint| entrypoint.name == entrypoint_name.replace(".md", ""))
+            .ok_or(ParserError)
+            .into_report()
+            .attach_printable(format!("Entry point {} not found", entrypoint_name))?;
+
+        // the Context is on the signature, which could be split in many lines
+        let context_line = lib_file_lines[entrypoint.start_line_index..=entrypoint.end_line_index]
+            .iter()
+            .take_while(|line| !line.contains("->") || line.contains("Context<"))
+            .find(|line| line.contains("Context<"))
+            .ok_or(ParserError)
+            .into_report()
+            .attach_printable(format!(
+                "Context not found for entry point {}",
+                entrypoint_name
+            ))?;
 
         // replace all the extra strings to get the Context name
         let parsed_context_name = context_line
@@ -0,0 +1,1022 @@
+use crate::batbelt::git::get_branch_name_of_auditor;
+use crate::batbelt::git::repository::BatRepository;
+use crate::batbelt::parser::{ParserError, ParserResult};
+use crate::batbelt::path::BatFolder;
+use crate::batbelt::BatEnumerator;
+use crate::config::{BatAuditorConfig, BatConfig};
+use error_stack::{IntoReport, ResultExt};
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// Findings above this similarity are reported as likely duplicates
+pub const FINDING_DUPLICATE_THRESHOLD: f64 = 0.5;
+/// Line of the finding file with its permanent id, like `**Id:** VAU-07`
+pub const FINDING_ID_TITLE: &str = "**Id:**";
+/// Prefix of the ids when the project name has no letters or digits
+pub const FINDING_DEFAULT_ID_PREFIX: &str = "BAT";
+pub const FINDING_SEVERITY_TITLE: &str = "**Severity:**";
+const FINDING_STATUS_TITLE: &str = "**Status:**";
+pub const FINDING_REMEDIATION_SECTION: &str = "Remediation";
+pub const FINDING_POC_SECTION: &str = "Proof of concept";
+const FINDING_FIX_COMMIT_TITLE: &str = "- Fix commit:";
+const FINDING_FIX_REVIEWER_TITLE: &str = "- Reviewer:";
+const FINDING_FIX_COMMENT_TITLE: &str = "- Comment:";
+
+#[derive(Debug, Clone, Copy, PartialEq, strum_macros::Display, strum_macros::EnumIter)]
+#[strum(serialize_all = "kebab-case")]
+pub enum FindingFolderStatus {
+    ToReview,
+    Accepted,
+    Rejected,
+}
+
+/// Result of the fix review, written on the Status line of the finding
+#[derive(Debug, Clone, Copy, PartialEq, strum_macros::Display, strum_macros::EnumIter, Default)]
+pub enum FindingFixStatus {
+    #[default]
+    Resolved,
+    #[strum(serialize = "Partially resolved")]
+    PartiallyResolved,
+    Acknowledged,
+    #[strum(serialize = "Won't fix")]
+    WontFix,
+}
+
+impl BatEnumerator for FindingFixStatus {}
+
+/// Fix review of an accepted finding, kept on its Remediation section
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FindingFixReview {
+    pub status: String,
+    pub fix_commit: String,
+    pub reviewer: String,
+    pub comment: String,
+}
+
+/// Finding file of any auditor, parsed to compare it with the rest
+#[derive(Debug, Clone, PartialEq)]
+pub struct FindingParser {
+    pub file_name: String,
+    pub file_path: String,
+    pub auditor_name: String,
+    pub status: FindingFolderStatus,
+    /// empty for findings created before the ids
+    pub id: String,
+    pub title: String,
+    pub severity: String,
+    /// value of the Status line, Open until the fix review
+    pub report_status: String,
+    /// None until the fix review
+    pub fix_review: Option<FindingFixReview>,
+    pub description: String,
+    pub evidence: String,
+    pub affected_resources: Vec<String>,
+    pub content: String,
+    /// branch of another auditor the finding was read from, None if it's on the working tree
+    pub branch_name: Option<String>,
+}
+
+impl FindingParser {
+    pub fn new_from_content(
+        file_path: &str,
+        auditor_name: &str,
+        status: FindingFolderStatus,
+        content: &str,
+    ) -> Self {
+        let title = content
+            .lines()
+            .find_map(|line| line.strip_prefix("## "))
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        let get_line_value = |line_title: &str| {
+            content
+                .lines()
+                .find_map(|line| line.trim().strip_prefix(line_title))
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        let affected_resources = get_finding_section(content, "Affected resources")
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("- "))
+            .map(|resource| resource.trim().to_string())
+            .filter(|resource| {
+                !resource.is_empty() && resource != "N/A" && resource != "Add affected resources"
+            })
+            .collect();
+        let remediation = get_finding_section(content, FINDING_REMEDIATION_SECTION);
+        let get_remediation_value = |line_title: &str| {
+            remediation
+                .lines()
+                .find_map(|line| line.trim().strip_prefix(line_title))
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        let report_status = get_line_value(FINDING_STATUS_TITLE);
+        let fix_review = if remediation.is_empty() {
+            None
+        } else {
+            Some(FindingFixReview {
+                status: report_status.clone(),
+                fix_commit: get_remediation_value(FINDING_FIX_COMMIT_TITLE),
+                reviewer: get_remediation_value(FINDING_FIX_REVIEWER_TITLE),
+                comment: get_remediation_value(FINDING_FIX_COMMENT_TITLE),
+            })
+        };
+        Self {
+            file_name: Path::new(file_path)
+                .file_stem()
+                .map(|file_stem| file_stem.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            file_path: file_path.to_string(),
+            auditor_name: auditor_name.to_string(),
+            status,
+            id: get_line_value(FINDING_ID_TITLE),
+            title,
+            severity: get_line_value(FINDING_SEVERITY_TITLE),
+            report_status,
+            fix_review,
+            description: get_finding_section(content, "Description"),
+            evidence: get_finding_section(content, "Evidence"),
+            affected_resources,
+            content: content.to_string(),
+            branch_name: None,
+        }
+    }
+
+    /// Findings of every auditor of the Bat.toml. The ones of the other auditors are read from
+    /// the last commit of their branches, local or fetched, and from the working tree if they
+    /// have no branch
+    pub fn get_all_auditors_findings() -> ParserResult<Vec<Self>> {
+        let bat_config = BatConfig::get_config().change_context(ParserError)?;
+        let current_auditor_name = BatAuditorConfig::get_config()
+            .change_context(ParserError)?
+            .auditor_name;
+        let notes_path = BatFolder::Notes
+            .get_path(false)
+            .change_context(ParserError)?;
+        let bat_repository = BatRepository::open().ok();
+        let mut findings = vec![];
+        for auditor_name in &bat_config.auditor_names {
+            if auditor_name != &current_auditor_name {
+                if let Some(bat_repository) = &bat_repository {
+                    if let Some(branch_findings) =
+                        Self::get_branch_findings(bat_repository, &notes_path, auditor_name)?
+                    {
+                        findings.extend(branch_findings);
+                        continue;
+                    }
+                }
+            }
+            for status in [
+                FindingFolderStatus::ToReview,
+                FindingFolderStatus::Accepted,
+                FindingFolderStatus::Rejected,
+            ] {
+                let folder_path =
+                    format!("{}/{}-notes/findings/{}", notes_path, auditor_name, status);
+                if !Path::new(&folder_path).is_dir() {
+                    continue;
+                }
+                let mut file_paths = fs::read_dir(&folder_path)
+                    .into_report()
+                    .change_context(ParserError)?
+                    .filter_map(|dir_entry| dir_entry.ok().map(|dir_entry| dir_entry.path()))
+                    .filter(|file_path| {
+                        file_path
+                            .extension()
+                            .and_then(|extension| extension.to_str())
+                            == Some("md")
+                    })
+                    .collect::<Vec<_>>();
+                file_paths.sort();
+                for file_path in file_paths {
+                    let content = fs::read_to_string(&file_path)
+                        .into_report()
+                        .change_context(ParserError)?;
+                    findings.push(Self::new_from_content(
+                        &file_path.to_string_lossy(),
+                        auditor_name,
+                        status,
+                        &content,
+                    ));
+                }
+            }
+        }
+        Ok(findings)
+    }
+
+    /// None if the auditor has no branch on the repository
+    fn get_branch_findings(
+        bat_repository: &BatRepository,
+        notes_path: &str,
+        auditor_name: &str,
+    ) -> ParserResult<Option<Vec<Self>>> {
+        let branch_name = get_branch_name_of_auditor(auditor_name).change_context(ParserError)?;
+        let findings_folder_path = format!(
+            "{}/{}-notes/findings",
+            bat_repository
+                .get_relative_path(notes_path)
+                .change_context(ParserError)?,
+            auditor_name
+        );
+        let branch_files = match bat_repository
+            .get_branch_files(&branch_name, &findings_folder_path)
+            .change_context(ParserError)?
+        {
+            Some(branch_files) => branch_files,
+            None => return Ok(None),
+        };
+        let mut findings = vec![];
+        for status in [
+            FindingFolderStatus::ToReview,
+            FindingFolderStatus::Accepted,
+            FindingFolderStatus::Rejected,
+        ] {
+            let status_folder_path = format!("{}/{}/", findings_folder_path, status);
+            let mut status_files = branch_files
+                .iter()
+                .filter_map(|(file_path, content)| {
+                    file_path
+                        .strip_prefix(&status_folder_path)
+                        .filter(|file_name| !file_name.contains('/') && file_name.ends_with(".md"))
+                        .map(|file_name| (file_name, content))
+                })
+                .collect::<Vec<_>>();
+            status_files.sort();
+            for (file_name, content) in status_files {
+                // same path as the working tree one, to show it on the tables
+                let mut finding = Self::new_from_content(
+                    &format!(
+                        "{}/{}-notes/findings/{}/{}",
+                        notes_path, auditor_name, status, file_name
+                    ),
+                    auditor_name,
+                    status,
+                    content,
+                );
+                finding.branch_name = Some(branch_name.clone());
+                findings.push(finding);
+            }
+        }
+        Ok(Some(findings))
+    }
+
+    /// From 0 to 1, weighting the shared affected resources, title and description words
+    pub fn get_similarity(&self, other: &Self) -> f64 {
+        let title_similarity = get_jaccard_index(
+            &get_significant_words(&self.title),
+            &get_significant_words(&other.title),
+        );
+        let description_similarity = get_jaccard_index(
+            &get_significant_words(&self.description),
+            &get_significant_words(&other.description),
+        );
+        let normalize_resources = |finding: &Self| {
+            finding
+                .affected_resources
+                .iter()
+                .map(|resource| normalize_affected_resource(resource))
+                .collect::<BTreeSet<_>>()
+        };
+        let (resources, other_resources) = (normalize_resources(self), normalize_resources(other));
+        if resources.is_empty() || other_resources.is_empty() {
+            return (title_similarity + description_similarity) / 2.0;
+        }
+        0.4 * get_jaccard_index(&resources, &other_resources)
+            + 0.3 * title_similarity
+            + 0.3 * description_similarity
+    }
+
+    /// Content of the finding with the evidence and the missing affected resources of the
+    /// duplicate, credited to its auditor. The evidence of a duplicate is only added once
+    pub fn get_merged_content(&self, duplicate: &Self) -> String {
+        let mut evidence = self.evidence.clone();
+        let evidence_title = format!(
+            "#### Evidence by {} ({}) {{-}}",
+            duplicate.auditor_name, duplicate.file_name
+        );
+        if !duplicate.evidence.is_empty() && !evidence.contains(&evidence_title) {
+            evidence = format!(
+                "{}\n\n{}\n\n{}",
+                evidence, evidence_title, duplicate.evidence
+            );
+        }
+        let mut affected_resources = self.affected_resources.clone();
+        for resource in &duplicate.affected_resources {
+            if !affected_resources.contains(resource) {
+                affected_resources.push(resource.clone());
+            }
+        }
+        let content = replace_finding_section(&self.content, "Evidence", &evidence);
+        if affected_resources.is_empty() {
+            return content;
+        }
+        replace_finding_section(
+            &content,
+            "Affected resources",
+            &affected_resources
+                .iter()
+                .map(|resource| format!("- {}", resource))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+}
+
+/// Groups of likely duplicates, as indexes of findings. Only the groups with a finding of
+/// auditor_name and a finding to review of any auditor are returned, the accepted ones were
+/// already checked and the rejected ones are ignored
+pub fn get_finding_duplicate_groups(
+    findings: &[FindingParser],
+    auditor_name: &str,
+) -> Vec<Vec<usize>> {
+    let mut group_ids = (0..findings.len()).collect::<Vec<_>>();
+    fn get_group_id(group_ids: &mut [usize], index: usize) -> usize {
+        let mut root = index;
+        while group_ids[root] != root {
+            root = group_ids[root];
+        }
+        group_ids[index] = root;
+        root
+    }
+    let is_rejected = |index: usize| findings[index].status == FindingFolderStatus::Rejected;
+    for index in 0..findings.len() {
+        for other_index in index + 1..findings.len() {
+            if !is_rejected(index)
+                && !is_rejected(other_index)
+                && findings[index].get_similarity(&findings[other_index])
+                    >= FINDING_DUPLICATE_THRESHOLD
+            {
+                let (root, other_root) = (
+                    get_group_id(&mut group_ids, index),
+                    get_group_id(&mut group_ids, other_index),
+                );
+                group_ids[other_root] = root;
+            }
+        }
+    }
+    let mut groups: Vec<Vec<usize>> = vec![];
+    let mut roots: Vec<usize> = vec![];
+    for index in 0..findings.len() {
+        let root = get_group_id(&mut group_ids, index);
+        match roots.iter().position(|group_root| *group_root == root) {
+            Some(position) => groups[position].push(index),
+            None => {
+                roots.push(root);
+                groups.push(vec![index]);
+            }
+        }
+    }
+    groups
+        .into_iter()
+        .filter(|group| {
+            group.len() > 1
+                && group
+                    .iter()
+                    .any(|index| findings[*index].auditor_name == auditor_name)
+                && group
+                    .iter()
+                    .any(|index| findings[*index].status == FindingFolderStatus::ToReview)
+        })
+        .collect()
+}
+
+/// Upper case initials of the project name, like VAU for vault-audit
+pub fn get_finding_id_prefix(project_name: &str) -> String {
+    let prefix = project_name
+        .trim_end_matches("-audit")
+        .chars()
+        .filter(|character| character.is_ascii_alphanumeric())
+        .take(3)
+        .collect::<String>()
+        .to_uppercase();
+    // project names without letters or digits would give ids like -01
+    if prefix.is_empty() {
+        FINDING_DEFAULT_ID_PREFIX.to_string()
+    } else {
+        prefix
+    }
+}
+
+/// Number of ids like VAU-07, None for the ones without it
+pub fn get_finding_id_number(finding_id: &str) -> Option<usize> {
+    finding_id
+        .rsplit_once('-')
+        .and_then(|(_, number)| number.parse::<usize>().ok())
+}
+
+/// Following the biggest id of any auditor, rejected findings included so no id is reused
+pub fn get_next_finding_id(findings: &[FindingParser], prefix: &str) -> String {
+    let next_number = findings
+        .iter()
+        .filter(|finding| {
+            finding
+                .id
+                .strip_prefix(prefix)
+                .is_some_and(|number| number.starts_with('-'))
+        })
+        .filter_map(|finding| get_finding_id_number(&finding.id))
+        .max()
+        .unwrap_or_default()
+        + 1;
+    format!("{}-{:02}", prefix, next_number)
+}
+
+/// Sets the id line below the title of the finding
+pub fn set_finding_id(content: &str, finding_id: &str) -> String {
+    let id_line = format!("{} {}", FINDING_ID_TITLE, finding_id);
+    let mut lines = content
+        .lines()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    if let Some(id_line_index) = lines
+        .iter()
+        .position(|line| line.trim().starts_with(FINDING_ID_TITLE))
+    {
+        lines[id_line_index] = id_line;
+    } else {
+        let title_index = lines
+            .iter()
+            .position(|line| line.starts_with("## "))
+            .map(|index| index + 1)
+            .unwrap_or_default();
+        lines.splice(title_index..title_index, ["".to_string(), id_line]);
+    }
+    let trailing_new_line = if content.ends_with('\n') { "\n" } else { "" };
+    format!("{}{}", lines.join("\n"), trailing_new_line)
+}
+
+/// Ids shared by more than one finding, like when two auditors created a finding at the same
+/// time on their branches
+pub fn get_duplicated_finding_ids(findings: &[FindingParser]) -> Vec<String> {
+    let mut seen_ids: Vec<&str> = vec![];
+    let mut duplicated_ids = vec![];
+    for finding in findings.iter().filter(|finding| !finding.id.is_empty()) {
+        if seen_ids.contains(&finding.id.as_str()) {
+            if !duplicated_ids.contains(&finding.id) {
+                duplicated_ids.push(finding.id.clone());
+            }
+        } else {
+            seen_ids.push(&finding.id);
+        }
+    }
+    duplicated_ids
+}
+
+/// Findings with an id sorted for the report, with the report number of the accepted ones. They
+/// are numbered by severity, in the order of the finding template, then by id
+pub fn get_report_numbered_findings<'a>(
+    findings: &'a [FindingParser],
+    severities: &[String],
+) -> Vec<(Option<usize>, &'a FindingParser)> {
+    let get_severity_order = |finding: &FindingParser| {
+        severities
+            .iter()
+            .position(|severity| severity.eq_ignore_ascii_case(&finding.severity))
+            .unwrap_or(severities.len())
+    };
+    let mut sorted_findings = findings
+        .iter()
+        .filter(|finding| !finding.id.is_empty())
+        .collect::<Vec<_>>();
+    sorted_findings.sort_by(|finding, other| {
+        (finding.status != FindingFolderStatus::Accepted)
+            .cmp(&(other.status != FindingFolderStatus::Accepted))
+            .then(get_severity_order(finding).cmp(&get_severity_order(other)))
+            .then(
+                get_finding_id_number(&finding.id)
+                    .cmp(&get_finding_id_number(&other.id))
+                    .then(finding.id.cmp(&other.id)),
+            )
+    });
+    sorted_findings
+        .into_iter()
+        .enumerate()
+        .map(|(index, finding)| {
+            let report_number = if finding.status == FindingFolderStatus::Accepted {
+                Some(index + 1)
+            } else {
+                None
+            };
+            (report_number, finding)
+        })
+        .collect()
+}
+
+/// Escapes the pipes and collapses the new lines, so the value stays in a single table cell
+pub fn get_table_cell(value: &str) -> String {
+    value
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+/// Markdown table from the permanent ids to the report numbers and the current files
+pub fn get_finding_ids_table(findings: &[FindingParser], severities: &[String]) -> String {
+    let rows = get_report_numbered_findings(findings, severities)
+        .into_iter()
+        .map(|(report_number, finding)| {
+            format!(
+                "| {} | {} | {} | {} | {} | {} | {} |",
+                get_table_cell(&finding.id),
+                report_number
+                    .map(|number| number.to_string())
+                    .unwrap_or("-".to_string()),
+                get_table_cell(&finding.severity),
+                get_table_cell(&finding.title),
+                finding.status,
+                get_table_cell(&finding.auditor_name),
+                get_table_cell(&finding.file_path)
+            )
+        })
+        .collect::<Vec<_>>();
+    format!(
+        "# Finding ids\n\n\
+        <!-- generated by bat-cli finding update-ids, the report numbers change with the severities -->\n\n\
+        | Id | Report number | Severity | Title | Status | Auditor | File |\n\
+        | :-: | :-----------: | :------: | :---- | :----: | :-----: | :--- |\n\
+        {}\n",
+        rows.join("\n")
+    )
+}
+
+/// Remediation table of the report, with the fix review of each accepted finding
+pub fn get_remediation_table(findings: &[FindingParser], severities: &[String]) -> String {
+    let rows = get_report_numbered_findings(findings, severities)
+        .into_iter()
+        .filter_map(|(report_number, finding)| {
+            let fix_review = finding.fix_review.clone().unwrap_or_default();
+            report_number.map(|number| {
+                format!(
+                    "| {} | {} | {} | {} | {} | {} | {} |",
+                    number,
+                    get_table_cell(&finding.id),
+                    get_table_cell(&finding.title),
+                    get_table_cell(&finding.severity),
+                    get_table_cell(&finding.report_status),
+                    get_table_cell(&fix_review.fix_commit),
+                    get_table_cell(&fix_review.comment)
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    format!(
+        "# Remediation\n\n\
+        <!-- generated by bat-cli finding review-fix -->\n\n\
+        | # | Id | Title | Severity | Status | Fix commit | Comment |\n\
+        | :-: | :-: | :---- | :------: | :----: | :--------- | :------ |\n\
+        {}\n",
+        rows.join("\n")
+    )
+}
+
+/// Sets the Status line and the Remediation section, added at the end of the file the first
+/// time
+pub fn set_finding_fix_review(content: &str, fix_review: &FindingFixReview) -> String {
+    let content = content
+        .lines()
+        .map(|line| {
+            if line.trim().starts_with(FINDING_STATUS_TITLE) {
+                format!("{} {}", FINDING_STATUS_TITLE, fix_review.status)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let remediation = format!(
+        "{} {}\n{} {}\n{} {}",
+        FINDING_FIX_COMMIT_TITLE,
+        fix_review.fix_commit,
+        FINDING_FIX_REVIEWER_TITLE,
+        fix_review.reviewer,
+        FINDING_FIX_COMMENT_TITLE,
+        fix_review.comment
+    );
+    if get_finding_section_range(&content, FINDING_REMEDIATION_SECTION).is_some() {
+        return format!(
+            "{}\n",
+            replace_finding_section(&content, FINDING_REMEDIATION_SECTION, &remediation)
+        );
+    }
+    format!(
+        "{}\n\n### {} {{-}}\n\n{}\n",
+        content.trim_end(),
+        FINDING_REMEDIATION_SECTION,
+        remediation
+    )
+}
+
+/// Adds a figure linked to the source code to the Evidence section, after the figures already
+/// added and replacing the placeholders of the template
+pub fn add_finding_evidence_figure(
+    content: &str,
+    figure_file_name: &str,
+    location: &str,
+    permalink: &str,
+) -> String {
+    let placeholder_regex = Regex::new(
+        r#"(?s)<figure[^>]*>\s*<img[^>]*src="\.\./\.\./figures/(?:finding-name|observation)-\d+\.png"\s*/>\s*</figure>\s*"#,
+    )
+    .unwrap();
+    let evidence = get_finding_section(content, "Evidence");
+    let evidence = placeholder_regex.replace_all(&evidence, "").to_string();
+    let caption = if permalink.is_empty() {
+        format!("`{}`", location)
+    } else {
+        format!("<a href=\"{}\">{}</a>", permalink, location)
+    };
+    let figure = format!(
+        "<figure style=\"display:block\">\n    \
+        <img style=\"max-width:100%\" src=\"../../figures/{}\"/>\n    \
+        <figcaption>{}</figcaption>\n\
+        </figure>",
+        figure_file_name, caption
+    );
+    let evidence = match evidence.rfind("</figure>") {
+        Some(index) => {
+            let (figures, text) = evidence.split_at(index + "</figure>".len());
+            format!("{}\n{}{}", figures, figure, text)
+        }
+        None if evidence.trim().is_empty() => figure,
+        None => format!("{}\n\n{}", figure, evidence),
+    };
+    replace_finding_section(content, "Evidence", &evidence)
+}
+
+/// Links the PoC file from a Proof of concept section, added after the Evidence section the
+/// first time
+pub fn set_finding_poc_link(content: &str, poc_file_name: &str) -> String {
+    let poc_link = format!("- [{}](../../poc/{})", poc_file_name, poc_file_name);
+    if get_finding_section_range(content, FINDING_POC_SECTION).is_some() {
+        return replace_finding_section(content, FINDING_POC_SECTION, &poc_link);
+    }
+    let lines = content.lines().collect::<Vec<_>>();
+    let poc_section = vec![
+        format!("### {} {{-}}", FINDING_POC_SECTION),
+        "".to_string(),
+        poc_link,
+        "".to_string(),
+    ];
+    let new_lines = match get_finding_section_range(content, "Evidence") {
+        Some((_, end)) if end < lines.len() => {
+            let mut new_lines = lines[..end]
+                .iter()
+                .map(|line| line.to_string())
+                .collect::<Vec<_>>();
+            new_lines.extend(poc_section);
+            new_lines.extend(lines[end..].iter().map(|line| line.to_string()));
+            new_lines
+        }
+        _ => {
+            let mut new_lines = content
+                .trim_end()
+                .lines()
+                .map(|line| line.to_string())
+                .collect::<Vec<_>>();
+            new_lines.push("".to_string());
+            new_lines.extend(poc_section);
+            new_lines.pop();
+            new_lines
+        }
+    };
+    format!("{}\n", new_lines.join("\n"))
+}
+
+/// Content of a `### <title> {-}` section of a finding file, without the title
+pub fn get_finding_section(content: &str, section_title: &str) -> String {
+    match get_finding_section_range(content, section_title) {
+        Some((start, end)) => content.lines().collect::<Vec<_>>()[start..end]
+            .join("\n")
+            .trim()
+            .to_string(),
+        None => "".to_string(),
+    }
+}
+
+/// Replaces the content of a `### <title> {-}` section, content is returned as is if the
+/// section is missing
+pub fn replace_finding_section(
+    content: &str,
+    section_title: &str,
+    section_content: &str,
+) -> String {
+    let (start, end) = match get_finding_section_range(content, section_title) {
+        Some(range) => range,
+        None => return content.to_string(),
+    };
+    let lines = content.lines().collect::<Vec<_>>();
+    let mut new_lines = lines[..start].to_vec();
+    new_lines.push("");
+    new_lines.extend(section_content.trim().lines());
+    if end < lines.len() {
+        new_lines.push("");
+        new_lines.extend(&lines[end..]);
+    }
+    let trailing_new_line = if content.ends_with('\n') { "\n" } else { "" };
+    format!("{}{}", new_lines.join("\n"), trailing_new_line)
+}
+
+// lines after the title, up to the next ## or ### title
+fn get_finding_section_range(content: &str, section_title: &str) -> Option<(usize, usize)> {
+    let lines = content.lines().collect::<Vec<_>>();
+    let title_index = lines.iter().position(|line| {
+        line.strip_prefix("### ")
+            .map(|title| title.trim().trim_end_matches("{-}").trim() == section_title)
+            .unwrap_or(false)
+    })?;
+    let end = lines
+        .iter()
+        .skip(title_index + 1)
+        .position(|line| line.starts_with("### ") || line.starts_with("## "))
+        .map(|position| position + title_index + 1)
+        .unwrap_or(lines.len());
+    Some((title_index + 1, end))
+}
+
+fn get_significant_words(text: &str) -> BTreeSet<String> {
+    text.to_lowercase()
+        .split(|character: char| !character.is_alphanumeric())
+        .filter(|word| word.len() > 2)
+        .map(|word| word.to_string())
+        .collect()
+}
+
+// the name of the linked item, so the same function linked with other lines matches
+fn normalize_affected_resource(resource: &str) -> String {
+    let resource = match (resource.find('['), resource.find("](")) {
+        (Some(start), Some(end)) if start < end => &resource[start + 1..end],
+        _ => resource,
+    };
+    resource.replace('`', "").trim().to_lowercase()
+}
+
+fn get_jaccard_index(set: &BTreeSet<String>, other_set: &BTreeSet<String>) -> f64 {
+    let union_count = set.union(other_set).count();
+    if union_count == 0 {
+        return 0.0;
+    }
+    set.intersection(other_set).count() as f64 / union_count as f64
+}
+
+#[test]
+fn test_finding_duplicate_groups_and_merge() {
+    let new_finding_content = |title: &str, description: &str, resource: &str| {
+        format!(
+            "## {title}\n\n**Severity:** High\n\n### Description {{-}}\n\n{description}\n\n### Evidence {{-}}\n\nEvidence of {title}\n\n### Recommendation {{-}}\n\nAdd recommendations\n\n### Affected resources {{-}}\n\n- {resource}\n\n### Reference {{-}}\n\n- N/A\n"
+        )
+    };
+    let findings = vec![
+        FindingParser::new_from_content(
+            "notes/alice-notes/findings/accepted/1-missing_owner_check.md",
+            "alice",
+            FindingFolderStatus::Accepted,
+            &new_finding_content(
+                "Missing owner check on withdraw",
+                "The vault owner is not validated on withdraw",
+                "[`withdraw`](https://github.com/org/vault/blob/641bdb7/src/lib.rs#L10-L20)",
+            ),
+        ),
+        FindingParser::new_from_content(
+            "notes/bob-notes/findings/to-review/1-withdraw_owner_not_checked.md",
+            "bob",
+            FindingFolderStatus::ToReview,
+            &new_finding_content(
+                "Withdraw owner not checked",
+                "The owner of the vault is not checked on withdraw",
+                "[`withdraw`](https://github.com/org/vault/blob/641bdb7/src/lib.rs#L12)",
+            ),
+        ),
+        FindingParser::new_from_content(
+            "notes/bob-notes/findings/to-review/3-fee_overflow.md",
+            "bob",
+            FindingFolderStatus::ToReview,
+            &new_finding_content(
+                "Fee overflow",
+                "The fee multiplication can overflow",
+                "[`compute_fee`](https://github.com/org/vault/blob/641bdb7/src/fee.rs#L3)",
+            ),
+        ),
+    ];
+    assert!(findings[0].get_similarity(&findings[1]) >= FINDING_DUPLICATE_THRESHOLD);
+    assert!(findings[0].get_similarity(&findings[2]) < FINDING_DUPLICATE_THRESHOLD);
+    assert_eq!(
+        get_finding_duplicate_groups(&findings, "bob"),
+        vec![vec![0, 1]]
+    );
+    // alice can keep her accepted finding and merge the evidence of bob
+    assert_eq!(
+        get_finding_duplicate_groups(&findings, "alice"),
+        vec![vec![0, 1]]
+    );
+    assert!(get_finding_duplicate_groups(&findings, "carol").is_empty());
+
+    let merged_content = findings[0].get_merged_content(&findings[1]);
+    assert!(merged_content.contains(
+        "Evidence of Missing owner check on withdraw\n\n#### Evidence by bob (1-withdraw_owner_not_checked) {-}\n\nEvidence of Withdraw owner not checked\n\n### Recommendation {-}"
+    ));
+    assert_eq!(
+        get_finding_section(&merged_content, "Affected resources")
+            .lines()
+            .count(),
+        2
+    );
+    assert!(merged_content.ends_with("### Reference {-}\n\n- N/A\n"));
+    let merged_finding = FindingParser::new_from_content(
+        &findings[0].file_path,
+        "alice",
+        FindingFolderStatus::Accepted,
+        &merged_content,
+    );
+    assert_eq!(
+        merged_finding.get_merged_content(&findings[1]),
+        merged_content
+    );
+}
+
+#[test]
+fn test_finding_ids_table() {
+    assert_eq!(get_finding_id_prefix("vault-audit"), "VAU");
+    let new_finding = |file_path: &str, status: FindingFolderStatus, id: &str, severity: &str| {
+        FindingParser::new_from_content(
+            file_path,
+            "alice",
+            status,
+            &set_finding_id(
+                &format!("## {file_path}\n\n**Severity:** {severity}\n\n### Description {{-}}\n"),
+                id,
+            ),
+        )
+    };
+    let mut findings = vec![
+        new_finding("1-a.md", FindingFolderStatus::Accepted, "VAU-03", "Low"),
+        new_finding("2-b.md", FindingFolderStatus::Rejected, "VAU-04", "High"),
+        new_finding("3-c.md", FindingFolderStatus::Accepted, "VAU-01", "High"),
+        new_finding("4-d.md", FindingFolderStatus::ToReview, "VAU-02", "Medium"),
+    ];
+    assert_eq!(findings[0].id, "VAU-03");
+    assert!(findings[0]
+        .content
+        .starts_with("## 1-a.md\n\n**Id:** VAU-03\n\n**Severity:** Low"));
+    assert_eq!(
+        set_finding_id(&findings[0].content, "VAU-09"),
+        findings[0].content.replace("VAU-03", "VAU-09")
+    );
+    assert_eq!(get_next_finding_id(&findings, "VAU"), "VAU-05");
+    assert!(get_duplicated_finding_ids(&findings).is_empty());
+
+    let severities =
+        ["High", "Medium", "Low", "Informational"].map(|severity| severity.to_string());
+    let ids_table = get_finding_ids_table(&findings, &severities);
+    assert!(ids_table.contains("| VAU-01 | 1 | High | 3-c.md | accepted | alice | 3-c.md |"));
+    assert!(ids_table.contains("| VAU-03 | 2 | Low |"));
+    assert!(ids_table.contains("| VAU-02 | - | Medium |"));
+
+    findings.push(new_finding(
+        "5-e.md",
+        FindingFolderStatus::ToReview,
+        "VAU-02",
+        "Low",
+    ));
+    assert_eq!(get_duplicated_finding_ids(&findings), vec!["VAU-02"]);
+
+    // ids are compared by their number
+    let numbered_findings = vec![
+        new_finding("1-f.md", FindingFolderStatus::Accepted, "VAU-100", "High"),
+        new_finding("1-g.md", FindingFolderStatus::Accepted, "VAU-99", "High"),
+    ];
+    assert_eq!(
+        get_report_numbered_findings(&numbered_findings, &severities)
+            .into_iter()
+            .map(|(report_number, finding)| (report_number, finding.id.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(Some(1), "VAU-99"), (Some(2), "VAU-100")]
+    );
+    assert_eq!(get_next_finding_id(&numbered_findings, "VAU"), "VAU-101");
+    assert_eq!(get_finding_id_prefix("--audit"), "BAT");
+    assert_eq!(get_finding_id_prefix("_.-"), "BAT");
+}
+
+#[test]
+fn test_finding_fix_review() {
+    let content = "## Missing owner check\n\n**Id:** VAU-01\n\n**Severity:** High\n\n**Status:** Open\n\n### Description {-}\n\nThe owner is not checked\n\n### Reference {-}\n\n- N/A\n";
+    let mut fix_review = FindingFixReview {
+        status: FindingFixStatus::PartiallyResolved.to_string(),
+        fix_commit: "641bdb7".to_string(),
+        reviewer: "alice".to_string(),
+        comment: "only checked on withdraw".to_string(),
+    };
+    let reviewed_content = set_finding_fix_review(content, &fix_review);
+    assert!(reviewed_content.contains("**Status:** Partially resolved\n"));
+    assert!(reviewed_content.ends_with(
+        "- N/A\n\n### Remediation {-}\n\n- Fix commit: 641bdb7\n- Reviewer: alice\n- Comment: only checked on withdraw\n"
+    ));
+    let finding = FindingParser::new_from_content(
+        "1-missing_owner_check.md",
+        "alice",
+        FindingFolderStatus::Accepted,
+        &reviewed_content,
+    );
+    assert_eq!(finding.fix_review, Some(fix_review.clone()));
+
+    // a second review replaces the first one
+    fix_review.status = FindingFixStatus::Resolved.to_string();
+    let second_review_content = set_finding_fix_review(&reviewed_content, &fix_review);
+    assert_eq!(second_review_content.matches("### Remediation").count(), 1);
+    assert!(get_remediation_table(
+        &[FindingParser::new_from_content(
+            "1-missing_owner_check.md",
+            "alice",
+            FindingFolderStatus::Accepted,
+            &second_review_content,
+        )],
+        &["High".to_string()]
+    )
+    .contains(
+        "| 1 | VAU-01 | Missing owner check | High | Resolved | 641bdb7 | only checked on withdraw |"
+    ));
+
+    // pipes and multi line comments can't break the table
+    fix_review.comment = "checked with `a || b`".to_string();
+    let piped_content = set_finding_fix_review(&reviewed_content, &fix_review);
+    let mut piped_finding = FindingParser::new_from_content(
+        "1-missing_owner_check.md",
+        "alice",
+        FindingFolderStatus::Accepted,
+        &piped_content,
+    );
+    piped_finding.title = "Missing owner\ncheck".to_string();
+    let remediation_table = get_remediation_table(&[piped_finding], &["High".to_string()]);
+    assert!(remediation_table.contains(
+        "| 1 | VAU-01 | Missing owner check | High | Resolved | 641bdb7 | checked with `a \\|\\| b` |"
+    ));
+    assert_eq!(get_table_cell(" a | b\n\n c "), "a \\| b c");
+}
+
+#[test]
+fn test_add_finding_evidence_figure() {
+    let content = r#"## Missing owner check
+
+### Evidence {-}
+
+<figure style="display:block">
+    <img style="max-width:100%" src="../../figures/finding-name-1.png"/>
+</figure>
+<figure style="display:block">
+    <img style="max-width:100%" src="../../figures/finding-name-2.png"/>
+</figure>
+
+Add a description of the evidence here
+
+### Recommendation {-}
+
+Add recommendations
+"#;
+    let content = add_finding_evidence_figure(
+        content,
+        "missing_owner_check-1.png",
+        "withdraw",
+        "https://github.com/org/vault/blob/641bdb7/src/lib.rs#L10-L20",
+    );
+    let content =
+        add_finding_evidence_figure(&content, "missing_owner_check-2.png", "lib.rs:30-35", "");
+    assert!(!content.contains("finding-name-"));
+    assert_eq!(
+        get_finding_section(&content, "Evidence"),
+        r#"<figure style="display:block">
+    <img style="max-width:100%" src="../../figures/missing_owner_check-1.png"/>
+    <figcaption><a href="https://github.com/org/vault/blob/641bdb7/src/lib.rs#L10-L20">withdraw</a></figcaption>
+</figure>
+<figure style="display:block">
+    <img style="max-width:100%" src="../../figures/missing_owner_check-2.png"/>
+    <figcaption>`lib.rs:30-35`</figcaption>
+</figure>
+
+Add a description of the evidence here"#
+    );
+    assert!(content.ends_with("### Recommendation {-}\n\nAdd recommendations\n"));
+}
+
+#[test]
+fn test_set_finding_poc_link() {
+    let content = "## Missing owner check\n\n### Evidence {-}\n\nAdd a description of the evidence here\n\n### Recommendation {-}\n\nAdd recommendations\n";
+    let content = set_finding_poc_link(content, "missing_owner_check.rs");
+    assert_eq!(
+        content,
+        "## Missing owner check\n\n### Evidence {-}\n\nAdd a description of the evidence here\n\n### Proof of concept {-}\n\n- [missing_owner_check.rs](../../poc/missing_owner_check.rs)\n\n### Recommendation {-}\n\nAdd recommendations\n"
+    );
+    // a new PoC replaces the link
+    let content = set_finding_poc_link(&content, "missing_owner_check.ts");
+    assert_eq!(content.matches("### Proof of concept").count(), 1);
+    assert!(content.contains(
+        "- [missing_owner_check.ts](../../poc/missing_owner_check.ts)\n\n### Recommendation"
+    ));
+}
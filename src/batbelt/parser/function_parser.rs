@@ -5,11 +5,14 @@ use crate::batbelt::metadata::{BatMetadata, BatMetadataParser, MetadataId};
 use crate::batbelt::parser::ParserError;
 
 use crate::batbelt::metadata::function_dependencies_metadata::{
-    FunctionDependenciesMetadata, FunctionDependencyInfo,
+    ExternalCrateDependencyInfo, FunctionDependenciesMetadata, FunctionDependencyInfo,
 };
+use crate::batbelt::metadata::trait_metadata::TraitMetadata;
+use crate::batbelt::parser::cargo_parser::CargoParser;
 
-use error_stack::{Report, Result, ResultExt};
+use error_stack::{IntoReport, Report, Result, ResultExt};
 use regex::Regex;
+use std::fs;
 
 #[derive(Clone, Debug)]
 pub struct FunctionDependencyParser {
@@ -33,7 +36,9 @@ pub struct FunctionParser {
     pub body: String,
     pub parameters: Vec<FunctionParameterParser>,
     pub dependencies: Vec<MetadataId>,
+    pub ambiguous_dependencies: Vec<MetadataId>,
     pub external_dependencies: Vec<String>,
+    pub external_crate_dependencies: Vec<ExternalCrateDependencyInfo>,
 }
 
 impl FunctionParser {
@@ -51,7 +56,9 @@ impl FunctionParser {
             body: "".to_string(),
             parameters: vec![],
             dependencies: vec![],
+            ambiguous_dependencies: vec![],
             external_dependencies: vec![],
+            external_crate_dependencies: vec![],
         };
         new_function_parser.get_function_signature();
         new_function_parser.get_function_body();
@@ -68,12 +75,22 @@ impl FunctionParser {
                     .into_iter()
                     .map(|func_dep| func_dep.function_metadata_id)
                     .collect();
+                new_function_parser.ambiguous_dependencies = function_dep_metadata
+                    .clone()
+                    .dependencies
+                    .into_iter()
+                    .filter(|func_dep| func_dep.ambiguous_dispatch)
+                    .map(|func_dep| func_dep.function_metadata_id)
+                    .collect();
                 new_function_parser.external_dependencies =
                     function_dep_metadata.external_dependencies;
+                new_function_parser.external_crate_dependencies =
+                    function_dep_metadata.external_crate_dependencies;
                 return Ok(new_function_parser);
             }
             Err(_) => {
                 new_function_parser.get_function_dependencies()?;
+                new_function_parser.get_external_crate_dependencies()?;
                 log::debug!(
                     "new_function_parser_with_dependencies:\n{:#?}",
                     new_function_parser
@@ -96,10 +113,14 @@ impl FunctionParser {
                         .into_iter()
                         .map(|func_meta| FunctionDependencyInfo {
                             function_name: func_meta.name.clone(),
+                            ambiguous_dispatch: new_function_parser
+                                .ambiguous_dependencies
+                                .contains(&func_meta.metadata_id),
                             function_metadata_id: func_meta.metadata_id,
                         })
                         .collect::<Vec<_>>(),
                     new_function_parser.external_dependencies.clone(),
+                    new_function_parser.external_crate_dependencies.clone(),
                 );
                 function_dependencies_metadata
                     .update_metadata_file()
@@ -139,6 +160,7 @@ impl FunctionParser {
         // only not external
         let trait_metadata_vec = bat_metadata
             .traits
+            .clone()
             .into_iter()
             .filter(|t_metadata| !t_metadata.external_trait)
             .collect::<Vec<_>>();
@@ -182,6 +204,35 @@ impl FunctionParser {
             }
         }
 
+        // method calls, resolved through the impl blocks (trait and inherent) registered in the metadata
+        let caller_impl_to =
+            Self::get_caller_impl_to(&bat_metadata.traits, &self.function_metadata.metadata_id);
+        for (method_name, is_self_call) in Self::get_method_call_names(&self.body) {
+            if method_name == self.name {
+                continue;
+            }
+            // only calls on self can be narrowed to the impl block of the caller
+            let candidates = Self::get_method_call_candidates(
+                &method_name,
+                &bat_metadata.traits,
+                if is_self_call {
+                    caller_impl_to.clone()
+                } else {
+                    None
+                },
+            );
+            log::debug!("method_call {} candidates:\n{:#?}", method_name, candidates);
+            let is_ambiguous = candidates.len() > 1;
+            for candidate in candidates {
+                if is_ambiguous && !self.ambiguous_dependencies.contains(&candidate) {
+                    self.ambiguous_dependencies.push(candidate.clone());
+                }
+                if !dependency_function_metadata_id_vec.contains(&candidate) {
+                    dependency_function_metadata_id_vec.push(candidate);
+                }
+            }
+        }
+
         let dependency_regex = Regex::new(r"[A-Za-z0-9_]+\(([A-Za-z0-9_:.&, ()]*)\)").unwrap(); //[A-Za-z0-9_]+\(([A-Za-z0-9_,():\s])*\)$
         let dependency_function_names_vec = dependency_regex
             .find_iter(&body_clone)
@@ -235,10 +286,133 @@ impl FunctionParser {
                 self.external_dependencies.push(dependency_function_name);
             }
         }
-        self.dependencies = dependency_function_metadata_id_vec;
+        let mut dependencies: Vec<MetadataId> = vec![];
+        for dependency_metadata_id in dependency_function_metadata_id_vec {
+            if !dependencies.contains(&dependency_metadata_id) {
+                dependencies.push(dependency_metadata_id);
+            }
+        }
+        self.dependencies = dependencies;
         Ok(())
     }
 
+    fn get_external_crate_dependencies(&mut self) -> Result<(), ParserError> {
+        let cargo_parser = match CargoParser::new_from_program_lib() {
+            Ok(cargo_parser) => cargo_parser,
+            Err(error) => {
+                log::warn!(
+                    "Cargo.toml not available for external crates:\n{:#?}",
+                    error
+                );
+                return Ok(());
+            }
+        };
+        let file_content = fs::read_to_string(&self.function_metadata.path)
+            .into_report()
+            .change_context(ParserError)?;
+        let use_aliases = CargoParser::get_use_aliases(&file_content);
+        let call_path_regex =
+            Regex::new(r"[A-Za-z_][A-Za-z0-9_]*(?:::[A-Za-z_][A-Za-z0-9_]*)*(?:::<[^>]*>)?\(")
+                .unwrap();
+        for call_match in call_path_regex.find_iter(&self.body) {
+            // method calls can't be resolved to a crate
+            if self.body[..call_match.start()].ends_with('.') {
+                continue;
+            }
+            let call_path = Self::get_function_name_from_signature(call_match.as_str())
+                .trim_end_matches("::")
+                .to_string();
+            if let Some(dependency) = cargo_parser.resolve_call_crate(&call_path, &use_aliases) {
+                let external_crate_dependency = ExternalCrateDependencyInfo {
+                    call_path,
+                    crate_name: dependency.crate_name,
+                    crate_version: dependency
+                        .locked_version
+                        .unwrap_or(dependency.version_requirement),
+                    is_workspace_crate: dependency.is_workspace_crate,
+                };
+                if !self
+                    .external_crate_dependencies
+                    .contains(&external_crate_dependency)
+                {
+                    self.external_crate_dependencies
+                        .push(external_crate_dependency);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the impl_to of the impl block containing the function, if any
+    fn get_caller_impl_to(
+        trait_metadata_vec: &[TraitMetadata],
+        function_metadata_id: &MetadataId,
+    ) -> Option<String> {
+        trait_metadata_vec.iter().find_map(|trait_metadata| {
+            if trait_metadata.impl_functions.iter().any(|impl_func| {
+                &impl_func.function_source_code_metadata_id == function_metadata_id
+            }) {
+                Some(trait_metadata.impl_to.clone())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns every impl function that could be dispatched by a method call, ignoring external traits.
+    /// Candidates are narrowed to caller_impl_to when it's given and one of them matches
+    fn get_method_call_candidates(
+        method_name: &str,
+        trait_metadata_vec: &[TraitMetadata],
+        caller_impl_to: Option<String>,
+    ) -> Vec<MetadataId> {
+        let method_suffix = format!("::{}", method_name);
+        let candidates = trait_metadata_vec
+            .iter()
+            .filter(|trait_metadata| !trait_metadata.external_trait)
+            .flat_map(|trait_metadata| {
+                trait_metadata
+                    .impl_functions
+                    .iter()
+                    .filter(|impl_func| impl_func.trait_signature.ends_with(&method_suffix))
+                    .map(|impl_func| {
+                        (
+                            trait_metadata.impl_to.clone(),
+                            impl_func.function_source_code_metadata_id.clone(),
+                        )
+                    })
+            })
+            .collect::<Vec<_>>();
+        if let Some(impl_to) = caller_impl_to {
+            let self_candidates = candidates
+                .iter()
+                .filter(|(candidate_impl_to, _)| candidate_impl_to == &impl_to)
+                .map(|(_, metadata_id)| metadata_id.clone())
+                .collect::<Vec<_>>();
+            if !self_candidates.is_empty() {
+                return self_candidates;
+            }
+        }
+        candidates
+            .into_iter()
+            .map(|(_, metadata_id)| metadata_id)
+            .collect()
+    }
+
+    /// Returns the (name, is_self_call) of the method calls, a self call is self.method() or
+    /// Self::method(), not a call on a field of self
+    pub fn get_method_call_names(function_body: &str) -> Vec<(String, bool)> {
+        let method_call_regex =
+            Regex::new(r"(\bself\.|\bSelf::|\.)([A-Za-z_][A-Za-z0-9_]*)(?:::<[^>]*>)?\(").unwrap();
+        let mut method_names = method_call_regex
+            .captures_iter(function_body)
+            .map(|method_capture| (method_capture[2].to_string(), &method_capture[1] != "."))
+            .collect::<Vec<_>>();
+        method_names.sort();
+        method_names.dedup();
+        method_names
+    }
+
     fn get_function_signature(&mut self) {
         let function_signature = self.content.clone();
         let function_signature = function_signature
@@ -345,6 +519,98 @@ impl FunctionParser {
     }
 }
 
+#[test]
+fn test_get_method_call_names() {
+    let test_body = "
+    ctx.accounts.vault.validate(&ctx.accounts.authority)?;
+    let amount = Self::compute_amount(args.amount);
+    self.state.update::<u64>(amount);
+    self.check();
+    transfer(ctx.accounts.to_transfer_context(), amount)?;";
+    let method_names = FunctionParser::get_method_call_names(test_body);
+    assert_eq!(
+        method_names,
+        vec![
+            ("check".to_string(), true),
+            ("compute_amount".to_string(), true),
+            ("to_transfer_context".to_string(), false),
+            ("update".to_string(), false),
+            ("validate".to_string(), false)
+        ]
+    );
+}
+
+#[test]
+fn test_get_method_call_candidates() {
+    use crate::batbelt::metadata::trait_metadata::TraitMetadataFunction;
+    use crate::batbelt::metadata::traits_source_code_metadata::TraitMetadataType;
+
+    let new_trait_metadata = |impl_from: &str, impl_to: &str, external_trait: bool| {
+        TraitMetadata::new(
+            format!("{}{}", impl_from, impl_to),
+            impl_from.to_string(),
+            format!("{}{}_source_code", impl_from, impl_to),
+            vec![TraitMetadataFunction {
+                function_source_code_metadata_id: format!("{}_{}_validate", impl_from, impl_to),
+                trait_signature: format!("{}::validate", impl_to),
+            }],
+            TraitMetadataType::Implementation,
+            external_trait,
+            impl_from.to_string(),
+            impl_to.to_string(),
+        )
+    };
+    // trait impl
+    let trait_impl = new_trait_metadata("Validate", "Vault", false);
+    // inherent impl
+    let inherent_impl = new_trait_metadata("", "Pool", false);
+    // external trait impl, ignored
+    let external_trait_impl = new_trait_metadata("Clone", "Vault", true);
+    let trait_metadata_vec = vec![trait_impl, inherent_impl, external_trait_impl];
+
+    let candidates =
+        FunctionParser::get_method_call_candidates("validate", &trait_metadata_vec[..1], None);
+    assert_eq!(candidates, vec!["Validate_Vault_validate".to_string()]);
+
+    let candidates =
+        FunctionParser::get_method_call_candidates("validate", &trait_metadata_vec[1..], None);
+    assert_eq!(candidates, vec!["_Pool_validate".to_string()]);
+
+    // ambiguous_dispatch, both Vault and Pool could be called
+    let candidates =
+        FunctionParser::get_method_call_candidates("validate", &trait_metadata_vec, None);
+    assert_eq!(
+        candidates,
+        vec![
+            "Validate_Vault_validate".to_string(),
+            "_Pool_validate".to_string()
+        ]
+    );
+
+    // self calls are narrowed to the caller impl_to
+    let candidates = FunctionParser::get_method_call_candidates(
+        "validate",
+        &trait_metadata_vec,
+        Some("Pool".to_string()),
+    );
+    assert_eq!(candidates, vec!["_Pool_validate".to_string()]);
+
+    // a call on another receiver inside impl Vault keeps the Pool candidate
+    let method_names = FunctionParser::get_method_call_names("other_account.validate()?;");
+    assert_eq!(method_names, vec![("validate".to_string(), false)]);
+    let caller_impl_to = Some("Vault".to_string());
+    let candidates = FunctionParser::get_method_call_candidates(
+        "validate",
+        &trait_metadata_vec,
+        if method_names[0].1 {
+            caller_impl_to
+        } else {
+            None
+        },
+    );
+    assert!(candidates.contains(&"_Pool_validate".to_string()));
+}
+
 // #[test]
 // fn test_get_function_information() {
 //     let test_function = "/// Validates a given key and its permissions.
@@ -0,0 +1,231 @@
+use regex::Regex;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProgramEntrypoint {
+    pub name: String,
+    pub start_line_index: usize,
+    pub end_line_index: usize,
+    pub is_macro_generated: bool,
+}
+
+pub struct MacroParser;
+
+impl MacroParser {
+    /// Returns the program id declared with declare_id!
+    pub fn get_declare_id(file_content: &str) -> Option<String> {
+        let declare_id_regex = Regex::new(r#"declare_id!\s*\(\s*"([A-Za-z0-9]+)"\s*\)"#).unwrap();
+        declare_id_regex
+            .captures(file_content)
+            .map(|declare_id_capture| declare_id_capture[1].to_string())
+    }
+
+    /// Returns the (start, end) line indexes of the module annotated with #[program]
+    pub fn get_program_module_range(file_content: &str) -> Option<(usize, usize)> {
+        let lines = file_content.lines().collect::<Vec<_>>();
+        let program_attribute_index = lines
+            .iter()
+            .position(|line| line.trim_start().starts_with("#[program"))?;
+        let module_index = lines
+            .iter()
+            .enumerate()
+            .skip(program_attribute_index)
+            .find(|(_, line)| line.contains("mod "))
+            .map(|(line_index, _)| line_index)?;
+        let end_line_index = Self::get_closing_line_index(&lines, module_index, true)?;
+        Some((module_index, end_line_index))
+    }
+
+    /// Gets the entry points of the #[program] module, this is, the top level pub fn and the
+    /// invocations of the registered entry point macros
+    pub fn get_program_entrypoints(
+        file_content: &str,
+        entry_point_macros: &[String],
+    ) -> Vec<ProgramEntrypoint> {
+        let lines = file_content.lines().collect::<Vec<_>>();
+        let (module_start, module_end) = match Self::get_program_module_range(file_content) {
+            None => return vec![],
+            Some(range) => range,
+        };
+        let function_regex = Regex::new(r"^\s*pub\s+fn\s+([A-Za-z0-9_]+)").unwrap();
+        let macro_regexes = entry_point_macros
+            .iter()
+            .map(|macro_name| {
+                Regex::new(&format!(
+                    r"^\s*{}!\s*[\(\{{\[]\s*(?:pub\s+)?(?:fn\s+)?([A-Za-z_][A-Za-z0-9_]*)",
+                    regex::escape(macro_name.trim_end_matches('!'))
+                ))
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let mut entrypoints = vec![];
+        let mut depth = 0;
+        for line_index in module_start..module_end {
+            let line = lines[line_index];
+            // only items placed directly on the module
+            if depth == 1 {
+                if let Some(function_capture) = function_regex.captures(line) {
+                    if let Some(end_line_index) =
+                        Self::get_closing_line_index(&lines, line_index, true)
+                    {
+                        entrypoints.push(ProgramEntrypoint {
+                            name: function_capture[1].to_string(),
+                            start_line_index: line_index,
+                            end_line_index,
+                            is_macro_generated: false,
+                        });
+                    }
+                } else if let Some(macro_capture) = macro_regexes
+                    .iter()
+                    .find_map(|macro_regex| macro_regex.captures(line))
+                {
+                    entrypoints.push(ProgramEntrypoint {
+                        name: macro_capture[1].to_string(),
+                        start_line_index: line_index,
+                        end_line_index: Self::get_closing_line_index(&lines, line_index, false)
+                            .unwrap_or(line_index),
+                        is_macro_generated: true,
+                    });
+                }
+            }
+            depth += line.matches(['{', '(', '[']).count() as i64;
+            depth -= line.matches(['}', ')', ']']).count() as i64;
+        }
+        entrypoints
+    }
+
+    /// Checks the attributes of a struct, returns None if the struct doesn't have derive or
+    /// attribute macros, so the caller can fall back to other heuristics
+    pub fn struct_derives_accounts(
+        file_content: &str,
+        struct_start_line_index: usize,
+        context_accounts_macros: &[String],
+    ) -> Option<bool> {
+        let lines = file_content.lines().collect::<Vec<_>>();
+        let mut attribute_lines = vec![];
+        let mut line_index = struct_start_line_index;
+        while line_index > 0 {
+            line_index -= 1;
+            let line = lines[line_index].trim();
+            if line.is_empty() || line.ends_with('}') || line.ends_with(';') || line.ends_with('{')
+            {
+                break;
+            }
+            attribute_lines.insert(0, line);
+        }
+        let attributes_content = attribute_lines
+            .into_iter()
+            .filter(|line| !line.starts_with("//"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let derive_regex = Regex::new(r"derive\s*\(([^)]*)\)").unwrap();
+        let attribute_regex = Regex::new(r"#\[\s*([A-Za-z_][A-Za-z0-9_:]*)").unwrap();
+        let derived_names = derive_regex
+            .captures_iter(&attributes_content)
+            .flat_map(|derive_capture| {
+                derive_capture[1]
+                    .split(',')
+                    .map(|derived| derived.trim().split("::").last().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|derived| !derived.is_empty())
+            .collect::<Vec<_>>();
+        let attribute_names = attribute_regex
+            .captures_iter(&attributes_content)
+            .map(|attribute_capture| attribute_capture[1].to_string())
+            .filter(|attribute| attribute != "derive")
+            .collect::<Vec<_>>();
+        if derived_names.is_empty() && attribute_names.is_empty() {
+            return None;
+        }
+        let is_context_accounts = derived_names
+            .iter()
+            .chain(attribute_names.iter())
+            .any(|name| {
+                name == "Accounts"
+                    || context_accounts_macros
+                        .iter()
+                        .any(|macro_name| macro_name.trim_start_matches("#[") == name)
+            });
+        Some(is_context_accounts)
+    }
+
+    /// Returns the line index where the delimiters opened at start_line_index are closed.
+    /// Functions and modules are delimited by braces, macro invocations by any delimiter
+    pub fn get_closing_line_index(
+        lines: &[&str],
+        start_line_index: usize,
+        braces_only: bool,
+    ) -> Option<usize> {
+        let (opening_delimiters, closing_delimiters) = if braces_only {
+            (vec!['{'], vec!['}'])
+        } else {
+            (vec!['{', '(', '['], vec!['}', ')', ']'])
+        };
+        let mut depth = 0;
+        let mut opened = false;
+        for (line_index, line) in lines.iter().enumerate().skip(start_line_index) {
+            for character in line.chars() {
+                if opening_delimiters.contains(&character) {
+                    depth += 1;
+                    opened = true;
+                } else if closing_delimiters.contains(&character) {
+                    depth -= 1;
+                }
+            }
+            if opened && depth <= 0 {
+                return Some(line_index);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod macro_parser_test {
+    use crate::batbelt::parser::macro_parser::MacroParser;
+
+    #[test]
+    fn test_get_program_entrypoints() {
+        let lib_content = r#"use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod test_program {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        fn inner_helper() {}
+        Ok(())
+    }
+
+    instruction_handler!(deposit, Context<Deposit>);
+}
+
+#[derive(Accounts)]
+pub struct Initialize {}
+"#;
+        assert_eq!(
+            MacroParser::get_declare_id(lib_content).unwrap(),
+            "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS"
+        );
+        let entrypoints = MacroParser::get_program_entrypoints(
+            lib_content,
+            &vec!["instruction_handler".to_string()],
+        );
+        let names = entrypoints
+            .iter()
+            .map(|entrypoint| entrypoint.name.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["initialize".to_string(), "deposit".to_string()]);
+        assert!(entrypoints[1].is_macro_generated);
+        let struct_line_index = lib_content
+            .lines()
+            .position(|line| line.contains("pub struct Initialize"))
+            .unwrap();
+        assert_eq!(
+            MacroParser::struct_derives_accounts(lib_content, struct_line_index, &vec![]),
+            Some(true)
+        );
+    }
+}
@@ -4,10 +4,14 @@ use error_stack::Result;
 use std::error::Error;
 use std::fmt;
 
+pub mod cargo_parser;
 pub mod code_overhaul_parser;
 pub mod context_accounts_parser;
 pub mod entrypoint_parser;
+pub mod finding_parser;
 pub mod function_parser;
+pub mod macro_parser;
+pub mod native_parser;
 pub mod solana_account_parser;
 pub mod source_code_parser;
 pub mod trait_parser;
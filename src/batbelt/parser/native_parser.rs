@@ -0,0 +1,510 @@
+use crate::batbelt::metadata::functions_source_code_metadata::FunctionSourceCodeMetadata;
+use crate::batbelt::metadata::structs_source_code_metadata::{
+    StructMetadataType, StructSourceCodeMetadata,
+};
+use crate::batbelt::metadata::{BatMetadata, BatMetadataParser};
+use crate::batbelt::parser::context_accounts_parser::CAAccountParser;
+use crate::batbelt::parser::macro_parser::MacroParser;
+use crate::batbelt::parser::solana_account_parser::SolanaAccountType;
+use crate::batbelt::parser::{ParserError, ParserResult};
+use crate::batbelt::path::BatFolder;
+use crate::config::{BatConfig, ProgramFramework};
+use error_stack::{IntoReport, ResultExt};
+use inflector::Inflector;
+use regex::Regex;
+use std::fs;
+use std::sync::OnceLock;
+
+// the instructions don't change while a command runs, so the program files are parsed once
+static PROGRAM_INSTRUCTIONS: OnceLock<Vec<NativeInstruction>> = OnceLock::new();
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NativeInstruction {
+    pub variant_name: String,
+    pub entrypoint_name: String,
+    pub processor_name: Option<String>,
+    pub dispatch_path: String,
+    pub dispatch_line_index: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NativeAccountInfo {
+    pub account_name: String,
+    pub line_index: usize,
+    pub content: String,
+}
+
+pub struct NativeParser;
+
+impl NativeParser {
+    pub fn is_native_program() -> ParserResult<bool> {
+        let bat_config = BatConfig::get_config().change_context(ParserError)?;
+        Ok(bat_config.program_framework == ProgramFramework::Native)
+    }
+
+    /// Returns the name and the variants of the instruction enum, if the file declares one
+    pub fn get_instruction_enum(file_content: &str) -> Option<(String, Vec<String>)> {
+        let lines = file_content.lines().collect::<Vec<_>>();
+        let enum_regex = Regex::new(r"^\s*pub\s+enum\s+([A-Za-z0-9_]*Instruction)\b").unwrap();
+        let variant_regex = Regex::new(r"^\s*([A-Z][A-Za-z0-9_]*)\s*(?:[\{\(,]|$)").unwrap();
+        let (enum_line_index, enum_name) =
+            lines.iter().enumerate().find_map(|(line_index, line)| {
+                enum_regex
+                    .captures(line)
+                    .map(|enum_capture| (line_index, enum_capture[1].to_string()))
+            })?;
+        let end_line_index = MacroParser::get_closing_line_index(&lines, enum_line_index, true)?;
+        let mut variants = vec![];
+        let mut depth = 0;
+        for line in lines[enum_line_index..end_line_index].iter() {
+            // only variants placed directly on the enum, fields are skipped
+            if depth == 1 {
+                if let Some(variant_capture) = variant_regex.captures(line) {
+                    variants.push(variant_capture[1].to_string());
+                }
+            }
+            depth += line.matches(['{', '(', '[']).count() as i64;
+            depth -= line.matches(['}', ')', ']']).count() as i64;
+        }
+        Some((enum_name, variants))
+    }
+
+    /// Gets the match arms dispatching the instruction enum, with the first function called by
+    /// each arm, which is usually the instruction processor
+    pub fn get_dispatch_arms(
+        file_content: &str,
+        enum_name: &str,
+    ) -> Vec<(String, Option<String>, usize)> {
+        let arm_regex = Regex::new(&format!(
+            r"{}::([A-Za-z0-9_]+)\s*(?:\{{[^}}]*\}}|\([^)]*\))?\s*=>",
+            regex::escape(enum_name)
+        ))
+        .unwrap();
+        let call_regex =
+            Regex::new(r"(?:^|[^.\w])(?:[A-Za-z_][A-Za-z0-9_]*::)*([a-z_][a-z0-9_]*)\s*\(")
+                .unwrap();
+        let string_regex = Regex::new(r#""[^"]*""#).unwrap();
+        let arm_matches = arm_regex.captures_iter(file_content).collect::<Vec<_>>();
+        arm_matches
+            .iter()
+            .enumerate()
+            .map(|(arm_index, arm_capture)| {
+                let arm_match = arm_capture.get(0).unwrap();
+                let body_end = arm_matches
+                    .get(arm_index + 1)
+                    .map(|next_arm| next_arm.get(0).unwrap().start())
+                    .unwrap_or(file_content.len());
+                let arm_body =
+                    string_regex.replace_all(&file_content[arm_match.end()..body_end], "");
+                let processor_name = call_regex
+                    .captures(&arm_body)
+                    .map(|call_capture| call_capture[1].to_string());
+                let line_index = file_content[..arm_match.start()].lines().count();
+                (arm_capture[1].to_string(), processor_name, line_index)
+            })
+            .collect()
+    }
+
+    /// Gets the accounts taken with next_account_info from a processor function
+    pub fn get_account_infos(function_content: &str) -> Vec<NativeAccountInfo> {
+        let account_info_regex = Regex::new(
+            r"let\s+(?:mut\s+)?([A-Za-z_][A-Za-z0-9_]*)\s*(?::[^=]+)?=\s*next_account_info\s*\(",
+        )
+        .unwrap();
+        function_content
+            .lines()
+            .enumerate()
+            .filter_map(|(line_index, line)| {
+                account_info_regex
+                    .captures(line)
+                    .map(|account_capture| NativeAccountInfo {
+                        account_name: account_capture[1].to_string(),
+                        line_index,
+                        content: line.trim().to_string(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Lines of the processor function checking the signer, writable, owner or key of an account
+    pub fn get_account_validations(function_content: &str, account_name: &str) -> Vec<String> {
+        let account_check_regex = Regex::new(&format!(
+            r"\b{}\.(is_signer|is_writable|owner|key)\b",
+            regex::escape(account_name)
+        ))
+        .unwrap();
+        function_content
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| {
+                !line.starts_with("let ")
+                    && account_check_regex.is_match(line)
+                    && ["if ", "assert", "require", "==", "!="]
+                        .iter()
+                        .any(|check| line.contains(check))
+            })
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    /// Checks if the key of the account is compared against a find_program_address or
+    /// create_program_address result, directly or through the variable it was bound to
+    pub fn is_pda_account(function_content: &str, account_name: &str) -> bool {
+        let pda_binding_regex = Regex::new(
+            r"let\s+(?:mut\s+)?(?:\(\s*([A-Za-z_][A-Za-z0-9_]*)\s*,[^)]*\)|([A-Za-z_][A-Za-z0-9_]*))\s*(?::[^=]+)?=[^;]*(?:find|create)_program_address",
+        )
+        .unwrap();
+        let pda_names = pda_binding_regex
+            .captures_iter(function_content)
+            .filter_map(|pda_capture| pda_capture.get(1).or(pda_capture.get(2)))
+            .map(|pda_name| pda_name.as_str().to_string())
+            .collect::<Vec<_>>();
+        let account_key_regex =
+            Regex::new(&format!(r"\b{}\.key\b", regex::escape(account_name))).unwrap();
+        function_content
+            .split(';')
+            .filter(|statement| {
+                account_key_regex.is_match(statement)
+                    && ["==", "!=", "assert"]
+                        .iter()
+                        .any(|comparison| statement.contains(comparison))
+            })
+            .any(|statement| {
+                statement.contains("_program_address")
+                    || pda_names.iter().any(|pda_name| {
+                        Regex::new(&format!(r"\b{}\b", regex::escape(pda_name)))
+                            .unwrap()
+                            .is_match(statement)
+                    })
+            })
+    }
+
+    pub fn get_context_accounts_name(variant_name: &str) -> String {
+        format!("{}Accounts", variant_name)
+    }
+
+    pub fn get_program_instructions() -> ParserResult<Vec<NativeInstruction>> {
+        if let Some(instructions) = PROGRAM_INSTRUCTIONS.get() {
+            return Ok(instructions.clone());
+        }
+        let instructions = Self::parse_program_instructions()?;
+        Ok(PROGRAM_INSTRUCTIONS.get_or_init(|| instructions).clone())
+    }
+
+    fn parse_program_instructions() -> ParserResult<Vec<NativeInstruction>> {
+        let program_files = BatFolder::ProgramPath
+            .get_all_files_dir_entries(false, None, Some(vec![".rs".to_string()]))
+            .change_context(ParserError)?
+            .into_iter()
+            .map(|dir_entry| {
+                let path = dir_entry.path().to_str().unwrap().to_string();
+                fs::read_to_string(&path)
+                    .into_report()
+                    .change_context(ParserError)
+                    .attach_printable(format!("Error reading file in {}", path))
+                    .map(|content| (path, content))
+            })
+            .collect::<ParserResult<Vec<_>>>()?;
+        let mut instructions = vec![];
+        for (enum_name, variants) in program_files
+            .iter()
+            .filter_map(|(_, content)| Self::get_instruction_enum(content))
+        {
+            let dispatch_arms = program_files
+                .iter()
+                .flat_map(|(path, content)| {
+                    Self::get_dispatch_arms(content, &enum_name)
+                        .into_iter()
+                        .map(|arm| (path.clone(), arm))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            for variant_name in variants {
+                if let Some((dispatch_path, (_, processor_name, dispatch_line_index))) =
+                    dispatch_arms
+                        .iter()
+                        .find(|(_, (arm_variant, _, _))| arm_variant == &variant_name)
+                {
+                    instructions.push(NativeInstruction {
+                        entrypoint_name: variant_name.to_snake_case(),
+                        variant_name,
+                        processor_name: processor_name.clone(),
+                        dispatch_path: dispatch_path.clone(),
+                        dispatch_line_index: *dispatch_line_index,
+                    });
+                }
+            }
+        }
+        Ok(instructions)
+    }
+
+    pub fn get_program_instruction(entrypoint_name: &str) -> ParserResult<NativeInstruction> {
+        Self::get_program_instructions()?
+            .into_iter()
+            .find(|instruction| instruction.entrypoint_name == entrypoint_name.replace(".md", ""))
+            .ok_or(ParserError)
+            .into_report()
+            .attach_printable(format!("Instruction {} not found", entrypoint_name))
+    }
+
+    /// The processor called by the dispatch arm, or the dispatcher itself when the arm is inlined
+    pub fn get_processor_function(
+        instruction: &NativeInstruction,
+    ) -> ParserResult<FunctionSourceCodeMetadata> {
+        let functions_source_code = BatMetadata::read_metadata()
+            .change_context(ParserError)?
+            .source_code
+            .functions_source_code;
+        let processor_candidates = functions_source_code
+            .iter()
+            .filter(|function_metadata| {
+                Some(function_metadata.name.clone()) == instruction.processor_name
+            })
+            .collect::<Vec<_>>();
+        processor_candidates
+            .iter()
+            .find(|function_metadata| function_metadata.path == instruction.dispatch_path)
+            .or_else(|| processor_candidates.first())
+            .cloned()
+            .or_else(|| {
+                functions_source_code.iter().find(|function_metadata| {
+                    function_metadata.path == instruction.dispatch_path
+                        && function_metadata.start_line_index <= instruction.dispatch_line_index + 1
+                        && function_metadata.end_line_index > instruction.dispatch_line_index
+                })
+            })
+            .cloned()
+            .ok_or(ParserError)
+            .into_report()
+            .attach_printable(format!(
+                "Processor function not found for instruction {}",
+                instruction.variant_name
+            ))
+    }
+
+    /// Native programs don't have context accounts structs, so one is created for every
+    /// instruction, spanning the next_account_info lines of the processor
+    pub fn get_context_accounts_structs() -> ParserResult<Vec<StructSourceCodeMetadata>> {
+        let mut context_accounts_structs = vec![];
+        for instruction in Self::get_program_instructions()? {
+            let processor_function = Self::get_processor_function(&instruction)?;
+            let processor_content = processor_function
+                .to_source_code_parser(None)
+                .get_source_code_content();
+            let account_infos = Self::get_account_infos(&processor_content);
+            let (start_line_index, end_line_index) =
+                match (account_infos.first(), account_infos.last()) {
+                    (Some(first_account), Some(last_account)) => (
+                        processor_function.start_line_index + first_account.line_index,
+                        processor_function.start_line_index + last_account.line_index,
+                    ),
+                    _ => (
+                        processor_function.start_line_index,
+                        processor_function.start_line_index,
+                    ),
+                };
+            context_accounts_structs.push(StructSourceCodeMetadata::new(
+                processor_function.path.clone(),
+                Self::get_context_accounts_name(&instruction.variant_name),
+                StructMetadataType::ContextAccounts,
+                start_line_index,
+                end_line_index,
+                BatMetadata::create_metadata_id(),
+            ));
+        }
+        Ok(context_accounts_structs)
+    }
+
+    pub fn update_context_accounts_structs() -> ParserResult<()> {
+        let context_accounts_structs = Self::get_context_accounts_structs()?;
+        let bat_metadata = BatMetadata::read_metadata().change_context(ParserError)?;
+        let mut structs_source_code = bat_metadata
+            .source_code
+            .structs_source_code
+            .clone()
+            .into_iter()
+            .filter(|struct_metadata| {
+                !context_accounts_structs
+                    .iter()
+                    .any(|ca_struct| ca_struct.name == struct_metadata.name)
+            })
+            .collect::<Vec<_>>();
+        structs_source_code.extend(context_accounts_structs);
+        bat_metadata
+            .source_code
+            .update_structs(structs_source_code)
+            .change_context(ParserError)
+    }
+
+    /// Gets the accounts of a synthetic context accounts struct, validations are looked for in
+    /// the whole processor function
+    pub fn get_context_accounts_info(
+        ca_struct: &StructSourceCodeMetadata,
+    ) -> ParserResult<Vec<CAAccountParser>> {
+        let bat_metadata = BatMetadata::read_metadata().change_context(ParserError)?;
+        let processor_content = bat_metadata
+            .source_code
+            .functions_source_code
+            .into_iter()
+            .find(|function_metadata| {
+                function_metadata.path == ca_struct.path
+                    && function_metadata.start_line_index <= ca_struct.start_line_index
+                    && function_metadata.end_line_index >= ca_struct.end_line_index
+            })
+            .map(|function_metadata| {
+                function_metadata
+                    .to_source_code_parser(None)
+                    .get_source_code_content()
+            })
+            .unwrap_or_default();
+        let lifetime_regex = Regex::new(r"AccountInfo<\s*('[A-Za-z_]+)\s*>").unwrap();
+        let accounts_info = Self::get_account_infos(&processor_content)
+            .into_iter()
+            .map(|account_info| {
+                let validations =
+                    Self::get_account_validations(&processor_content, &account_info.account_name);
+                let is_pda = Self::is_pda_account(&processor_content, &account_info.account_name);
+                let is_signer = validations
+                    .iter()
+                    .any(|validation| validation.contains(".is_signer"));
+                let is_mut = validations
+                    .iter()
+                    .any(|validation| validation.contains(".is_writable"))
+                    || [
+                        ".try_borrow_mut",
+                        ".data.borrow_mut",
+                        ".lamports.borrow_mut",
+                    ]
+                    .iter()
+                    .any(|mut_borrow| {
+                        processor_content
+                            .contains(&format!("{}{}", account_info.account_name, mut_borrow))
+                    });
+                CAAccountParser {
+                    content: account_info.content.clone(),
+                    solana_account_type: if is_signer {
+                        SolanaAccountType::Signer
+                    } else {
+                        SolanaAccountType::Other
+                    },
+                    account_struct_name: "AccountInfo".to_string(),
+                    account_wrapper_name: "AccountInfo".to_string(),
+                    lifetime_name: lifetime_regex
+                        .captures(&account_info.content)
+                        .map(|lifetime_capture| lifetime_capture[1].to_string())
+                        .unwrap_or_default(),
+                    account_name: account_info.account_name,
+                    is_pda,
+                    is_init: false,
+                    is_mut,
+                    is_close: false,
+                    seeds: vec![],
+                    rent_exemption_account: "".to_string(),
+                    validations,
+                }
+            })
+            .collect::<Vec<_>>();
+        Ok(accounts_info)
+    }
+}
+
+#[cfg(test)]
+mod native_parser_test {
+    use crate::batbelt::parser::native_parser::NativeParser;
+
+    #[test]
+    fn test_get_native_instructions() {
+        let instruction_content = r#"
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum EscrowInstruction {
+    /// Starts the trade
+    InitEscrow {
+        amount: u64,
+    },
+    Exchange(u64),
+    Cancel,
+}
+"#;
+        let (enum_name, variants) =
+            NativeParser::get_instruction_enum(instruction_content).unwrap();
+        assert_eq!(enum_name, "EscrowInstruction");
+        assert_eq!(variants, vec!["InitEscrow", "Exchange", "Cancel"]);
+
+        let processor_content = r#"
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+        match EscrowInstruction::unpack(data)? {
+            EscrowInstruction::InitEscrow { amount } => {
+                msg!("Instruction: InitEscrow()");
+                Self::process_init_escrow(accounts, amount, program_id)
+            }
+            EscrowInstruction::Exchange(amount) => Self::process_exchange(accounts, amount),
+            EscrowInstruction::Cancel => cancel::handler(program_id, accounts),
+        }
+    }
+
+    fn process_init_escrow(accounts: &[AccountInfo], amount: u64, program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let initializer = next_account_info(account_info_iter)?;
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let temp_token_account: &AccountInfo<'a> = next_account_info(account_info_iter)?;
+        Ok(())
+    }
+}
+"#;
+        let dispatch_arms = NativeParser::get_dispatch_arms(processor_content, &enum_name);
+        let arms = dispatch_arms
+            .iter()
+            .map(|(variant, processor, _)| (variant.as_str(), processor.clone().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            arms,
+            vec![
+                ("InitEscrow", "process_init_escrow".to_string()),
+                ("Exchange", "process_exchange".to_string()),
+                ("Cancel", "handler".to_string()),
+            ]
+        );
+        let account_infos = NativeParser::get_account_infos(processor_content);
+        let account_names = account_infos
+            .iter()
+            .map(|account_info| account_info.account_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(account_names, vec!["initializer", "temp_token_account"]);
+        assert_eq!(
+            NativeParser::get_account_validations(processor_content, "initializer"),
+            vec!["if !initializer.is_signer {".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_is_pda_account() {
+        let processor_content = r#"
+fn process_deposit(accounts: &[AccountInfo], amount: u64, program_id: &Pubkey) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let depositor = next_account_info(account_info_iter)?;
+    let vault = next_account_info(account_info_iter)?;
+    let config = next_account_info(account_info_iter)?;
+    let (vault_pda, _bump) =
+        Pubkey::find_program_address(&[b"vault", depositor.key.as_ref()], program_id);
+    if vault_pda != *vault.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    assert_eq!(
+        *config.key,
+        Pubkey::create_program_address(&[b"config"], program_id)?
+    );
+    Ok(())
+}
+"#;
+        assert!(NativeParser::is_pda_account(processor_content, "vault"));
+        assert!(NativeParser::is_pda_account(processor_content, "config"));
+        // only used as a seed
+        assert!(!NativeParser::is_pda_account(
+            processor_content,
+            "depositor"
+        ));
+    }
+}
@@ -10,6 +10,7 @@ use crate::batbelt::miro::MiroItemType;
 use crate::batbelt::parser::ParserError;
 
 use crate::batbelt::silicon;
+use crate::batbelt::sonar::frontend::SourceLanguage;
 
 use crate::batbelt::{self, path::BatFolder};
 use crate::config::BatConfig;
@@ -163,6 +164,7 @@ impl SourceCodeParser {
             offset,
             options.font_size,
             options.show_line_number,
+            SourceLanguage::from_path(&self.path).unwrap_or(SourceLanguage::Rust),
         );
         Ok(png_screenshot_path)
     }
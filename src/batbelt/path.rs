@@ -30,22 +30,50 @@ pub enum BatFile {
     BatAuditorToml,
     Batlog,
     BatMetadataFile,
+    CrateAdvisories,
     ThreatModeling,
+    ThreatModelingToml,
     FindingCandidates,
+    FindingCandidatesToml,
     OpenQuestions,
+    OpenQuestionsToml,
+    OpenQuestionsExport,
+    FindingIdsTable,
+    FindingTemplateToml,
+    CodeOverhaulTemplateToml,
+    RemediationTable,
     ProgramLib,
     Readme,
     GitIgnore,
+    GitAttributes,
     PackageJson,
     RobotFile,
     CodeOverhaulSummaryFile,
-    CodeOverhaulToReview { file_name: String },
-    CodeOverhaulStarted { file_name: String },
-    CodeOverhaulFinished { file_name: String },
-    FindingToReview { file_name: String },
-    FindingAccepted { file_name: String },
-    FindingRejected { file_name: String },
-    Generic { file_path: String },
+    CodeOverhaulToReview {
+        file_name: String,
+    },
+    CodeOverhaulStarted {
+        file_name: String,
+    },
+    CodeOverhaulFinished {
+        file_name: String,
+    },
+    FindingToReview {
+        file_name: String,
+    },
+    /// file name with the extension of the PoC language
+    FindingPoc {
+        file_name: String,
+    },
+    FindingAccepted {
+        file_name: String,
+    },
+    FindingRejected {
+        file_name: String,
+    },
+    Generic {
+        file_path: String,
+    },
 }
 
 impl BatEnumerator for BatFile {}
@@ -58,12 +86,34 @@ impl BatFile {
             BatFile::Batlog => "Batlog.log".to_string(),
             BatFile::PackageJson => "./package.json".to_string(),
             BatFile::GitIgnore => "./.gitignore".to_string(),
+            BatFile::GitAttributes => "./.gitattributes".to_string(),
             BatFile::ProgramLib => {
                 BatConfig::get_config()
                     .change_context(BatPathError)?
                     .program_lib_path
             }
             BatFile::Readme => "./README.md".to_string(),
+            BatFile::FindingIdsTable => format!(
+                "{}/finding_ids.md",
+                BatFolder::Notes.get_path(canonicalize)?
+            ),
+            BatFile::FindingTemplateToml => format!(
+                "{}/finding_template.toml",
+                BatFolder::Notes.get_path(canonicalize)?
+            ),
+            BatFile::CodeOverhaulTemplateToml => format!(
+                "{}/code_overhaul_template.toml",
+                BatFolder::Notes.get_path(canonicalize)?
+            ),
+            BatFile::RemediationTable => format!(
+                "{}/remediation.md",
+                BatFolder::Notes.get_path(canonicalize)?
+            ),
+            BatFile::FindingPoc { file_name } => format!(
+                "{}/{}",
+                BatFolder::AuditorPoc.get_path(canonicalize)?,
+                file_name
+            ),
             BatFile::RobotFile => format!(
                 "{}/robot.md",
                 BatFolder::AuditorNotes.get_path(canonicalize)?
@@ -74,18 +124,42 @@ impl BatFile {
                     BatFolder::AuditorNotes.get_path(canonicalize)?
                 )
             }
+            BatFile::FindingCandidatesToml => {
+                format!(
+                    "{}/finding_candidates.toml",
+                    BatFolder::AuditorNotes.get_path(canonicalize)?
+                )
+            }
             BatFile::OpenQuestions => {
                 format!(
                     "{}/open_questions.md",
                     BatFolder::AuditorNotes.get_path(canonicalize)?
                 )
             }
+            BatFile::OpenQuestionsToml => {
+                format!(
+                    "{}/open_questions.toml",
+                    BatFolder::AuditorNotes.get_path(canonicalize)?
+                )
+            }
+            BatFile::OpenQuestionsExport => {
+                format!(
+                    "{}/open_questions_client.md",
+                    BatFolder::AuditorNotes.get_path(canonicalize)?
+                )
+            }
             BatFile::ThreatModeling => {
                 format!(
                     "{}/threat_modeling.md",
                     BatFolder::AuditorNotes.get_path(canonicalize)?
                 )
             }
+            BatFile::ThreatModelingToml => {
+                format!(
+                    "{}/threat_modeling.toml",
+                    BatFolder::AuditorNotes.get_path(canonicalize)?
+                )
+            }
             BatFile::CodeOverhaulSummaryFile => {
                 format!(
                     "{}/code_overhaul_summary.md",
@@ -135,6 +209,7 @@ impl BatFile {
                 )
             }
             BatFile::BatMetadataFile => "./BatMetadata.json".to_string(),
+            BatFile::CrateAdvisories => "./BatAdvisories.toml".to_string(),
             BatFile::Generic { file_path } => file_path.clone(),
         };
 
@@ -239,6 +314,7 @@ pub enum BatFolder {
     CodeOverhaulFinished,
     AuditorNotes,
     AuditorFigures,
+    AuditorPoc,
     Notes,
 }
 
@@ -268,9 +344,14 @@ impl BatFolder {
                     BatFolder::AuditorNotes.get_path(canonicalize)?
                 )
             }
-            BatFolder::ProgramPath => bat_config
-                .program_lib_path
-                .trim_end_matches("/lib.rs")
+            BatFolder::AuditorPoc => {
+                format!("{}/poc", BatFolder::AuditorNotes.get_path(canonicalize)?)
+            }
+            // the folder of the lib file, which is not always a lib.rs for other languages
+            BatFolder::ProgramPath => Path::new(&bat_config.program_lib_path)
+                .parent()
+                .and_then(|program_folder| program_folder.to_str())
+                .unwrap_or_default()
                 .to_string(),
             BatFolder::FindingsFolderPath => {
                 format!("{}/findings", BatFolder::AuditorNotes.get_path(true)?)
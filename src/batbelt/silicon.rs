@@ -1,3 +1,4 @@
+use crate::batbelt::sonar::frontend::SourceLanguage;
 use std::fs;
 
 pub fn create_figure(
@@ -7,6 +8,7 @@ pub fn create_figure(
     offset: usize,
     font_size: Option<usize>,
     show_line_number: bool,
+    source_language: SourceLanguage,
 ) -> String {
     // write the temporary markdown file
     let (dest_md_path, dest_png_path) = get_dest_md_and_png_path(file_name, dest_folder_path);
@@ -18,6 +20,7 @@ pub fn create_figure(
         offset,
         font_size,
         show_line_number,
+        source_language,
     );
     fs::remove_file(dest_md_path).unwrap();
     dest_png_path
@@ -36,6 +39,7 @@ fn take_silicon_snapshot(
     offset: usize,
     font_size: Option<usize>,
     show_line_number: bool,
+    source_language: SourceLanguage,
 ) {
     let offset = format!("{}", offset);
     let font = if let Some(size) = font_size {
@@ -48,7 +52,7 @@ fn take_silicon_snapshot(
         "--no-window-controls",
         // show_line_number.as_str(),
         "--language",
-        "Rust",
+        source_language.get_silicon_language(),
         "--line-offset",
         offset.as_str(),
         "--theme",
@@ -0,0 +1,107 @@
+use crate::batbelt::metadata::functions_source_code_metadata::FunctionSourceCodeMetadata;
+use crate::batbelt::metadata::structs_source_code_metadata::StructSourceCodeMetadata;
+use crate::batbelt::parser::context_accounts_parser::CAAccountParser;
+use crate::batbelt::sonar::BatSonarError;
+use crate::batbelt::BatEnumerator;
+use crate::config::BatConfig;
+use error_stack::{Result, ResultExt};
+use std::path::Path;
+
+pub mod rust_frontend;
+pub mod solidity_frontend;
+
+use rust_frontend::RustFrontend;
+use solidity_frontend::SolidityFrontend;
+
+/// Produces the source code metadata of a language, sonar dispatches to the front-end by the
+/// extension of every file of the program folder
+pub trait LanguageFrontend {
+    fn get_functions(
+        &self,
+        file_path: &str,
+        file_content: &str,
+    ) -> Result<Vec<FunctionSourceCodeMetadata>, BatSonarError>;
+
+    fn get_structs(
+        &self,
+        file_path: &str,
+        file_content: &str,
+    ) -> Result<Vec<StructSourceCodeMetadata>, BatSonarError>;
+
+    fn get_entrypoint_names(&self, file_content: &str) -> Result<Vec<String>, BatSonarError>;
+
+    /// Name of the entry point of an entry point function, as returned by get_entrypoint_names
+    fn get_entrypoint_name(
+        &self,
+        entrypoint_function: &FunctionSourceCodeMetadata,
+    ) -> Result<String, BatSonarError> {
+        Ok(entrypoint_function.name.clone())
+    }
+
+    /// Name of the struct holding the accounts (or parameters) of the entry point
+    fn get_context_accounts_name(&self, entrypoint_name: &str) -> Result<String, BatSonarError>;
+
+    /// The accounts (or parameters) of a context accounts struct, with their validations
+    fn get_context_accounts_info(
+        &self,
+        context_accounts: &StructSourceCodeMetadata,
+    ) -> Result<Vec<CAAccountParser>, BatSonarError>;
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, strum_macros::Display, strum_macros::EnumIter)]
+pub enum SourceLanguage {
+    Rust,
+    Solidity,
+}
+
+impl BatEnumerator for SourceLanguage {}
+
+impl SourceLanguage {
+    pub fn from_path(file_path: &str) -> Option<Self> {
+        match Path::new(file_path).extension()?.to_str()? {
+            "rs" => Some(SourceLanguage::Rust),
+            "sol" => Some(SourceLanguage::Solidity),
+            _ => None,
+        }
+    }
+
+    /// The language of the program lib file, Rust if the extension is not supported
+    pub fn get_program_language() -> Result<Self, BatSonarError> {
+        let bat_config = BatConfig::get_config().change_context(BatSonarError)?;
+        Ok(Self::from_path(&bat_config.program_lib_path).unwrap_or(SourceLanguage::Rust))
+    }
+
+    pub fn get_frontend(&self) -> Box<dyn LanguageFrontend> {
+        match self {
+            SourceLanguage::Rust => Box::new(RustFrontend),
+            SourceLanguage::Solidity => Box::new(SolidityFrontend),
+        }
+    }
+
+    /// Language of the markdown code blocks
+    pub fn get_markdown_language(&self) -> &'static str {
+        match self {
+            SourceLanguage::Rust => "rust",
+            SourceLanguage::Solidity => "solidity",
+        }
+    }
+
+    /// Markdown code block with the language of file_path, Rust if it is not supported
+    pub fn get_code_block(file_path: &str, content: &str) -> String {
+        format!(
+            "```{}\n{}\n```",
+            Self::from_path(file_path)
+                .unwrap_or(SourceLanguage::Rust)
+                .get_markdown_language(),
+            content
+        )
+    }
+
+    /// Silicon doesn't bundle a Solidity syntax, so the closest one is used
+    pub fn get_silicon_language(&self) -> &'static str {
+        match self {
+            SourceLanguage::Rust => "Rust",
+            SourceLanguage::Solidity => "JavaScript",
+        }
+    }
+}
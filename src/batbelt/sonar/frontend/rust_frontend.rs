@@ -0,0 +1,65 @@
+use crate::batbelt::metadata::functions_source_code_metadata::FunctionSourceCodeMetadata;
+use crate::batbelt::metadata::structs_source_code_metadata::StructSourceCodeMetadata;
+use crate::batbelt::metadata::BatMetadataParser;
+use crate::batbelt::parser::context_accounts_parser::CAAccountParser;
+use crate::batbelt::parser::entrypoint_parser::EntrypointParser;
+use crate::batbelt::parser::macro_parser::MacroParser;
+use crate::batbelt::sonar::frontend::LanguageFrontend;
+use crate::batbelt::sonar::{BatSonar, BatSonarError, SonarResultType};
+use crate::config::BatConfig;
+use error_stack::{Result, ResultExt};
+
+pub struct RustFrontend;
+
+impl LanguageFrontend for RustFrontend {
+    fn get_functions(
+        &self,
+        file_path: &str,
+        file_content: &str,
+    ) -> Result<Vec<FunctionSourceCodeMetadata>, BatSonarError> {
+        FunctionSourceCodeMetadata::get_rust_functions(file_path, file_content)
+            .change_context(BatSonarError)
+    }
+
+    fn get_structs(
+        &self,
+        file_path: &str,
+        file_content: &str,
+    ) -> Result<Vec<StructSourceCodeMetadata>, BatSonarError> {
+        StructSourceCodeMetadata::get_rust_structs(file_path, file_content)
+            .change_context(BatSonarError)
+    }
+
+    fn get_entrypoint_names(&self, file_content: &str) -> Result<Vec<String>, BatSonarError> {
+        let entry_point_macros = BatConfig::get_config()
+            .change_context(BatSonarError)?
+            .entry_point_macros;
+        Ok(
+            MacroParser::get_program_entrypoints(file_content, &entry_point_macros)
+                .into_iter()
+                .map(|entrypoint| entrypoint.name)
+                .collect(),
+        )
+    }
+
+    fn get_context_accounts_name(&self, entrypoint_name: &str) -> Result<String, BatSonarError> {
+        EntrypointParser::get_context_name(entrypoint_name).change_context(BatSonarError)
+    }
+
+    fn get_context_accounts_info(
+        &self,
+        context_accounts: &StructSourceCodeMetadata,
+    ) -> Result<Vec<CAAccountParser>, BatSonarError> {
+        let ca_content = context_accounts
+            .to_source_code_parser(None)
+            .get_source_code_content();
+        let bat_sonar = BatSonar::new_scanned(&ca_content, SonarResultType::ContextAccountsAll);
+        bat_sonar
+            .results
+            .into_iter()
+            .map(|result| {
+                CAAccountParser::new_from_sonar_result(result).change_context(BatSonarError)
+            })
+            .collect()
+    }
+}
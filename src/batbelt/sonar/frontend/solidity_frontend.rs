@@ -0,0 +1,470 @@
+use crate::batbelt::metadata::functions_source_code_metadata::{
+    FunctionMetadataType, FunctionSourceCodeMetadata,
+};
+use crate::batbelt::metadata::structs_source_code_metadata::{
+    StructMetadataType, StructSourceCodeMetadata,
+};
+use crate::batbelt::metadata::{BatMetadata, BatMetadataParser};
+use crate::batbelt::parser::context_accounts_parser::CAAccountParser;
+use crate::batbelt::parser::macro_parser::MacroParser;
+use crate::batbelt::parser::solana_account_parser::SolanaAccountType;
+use crate::batbelt::sonar::frontend::LanguageFrontend;
+use crate::batbelt::sonar::BatSonarError;
+use error_stack::{IntoReport, Result, ResultExt};
+use inflector::Inflector;
+use regex::Regex;
+use std::fs;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SolidityFunction {
+    pub name: String,
+    /// contract, library or interface declaring the function, empty for free functions
+    pub contract_name: String,
+    pub signature: String,
+    pub start_line_index: usize,
+    pub signature_end_line_index: usize,
+    pub end_line_index: usize,
+    pub is_entrypoint: bool,
+}
+
+pub struct SolidityFrontend;
+
+impl SolidityFunction {
+    /// Qualified with the contract name, contracts of the program can share function names
+    pub fn get_entrypoint_name(&self) -> String {
+        format!("{}.{}", self.contract_name, self.name)
+    }
+}
+
+impl SolidityFrontend {
+    /// Gets the functions with body, the external and public functions of contracts are the
+    /// entry points
+    pub fn get_solidity_functions(file_content: &str) -> Vec<SolidityFunction> {
+        let lines = file_content.lines().collect::<Vec<_>>();
+        let container_regex =
+            Regex::new(r"^\s*(?:abstract\s+)?(contract|library|interface)\s+([A-Za-z0-9_]+)")
+                .unwrap();
+        let function_regex = Regex::new(
+            r"^\s*(?:function\s+([A-Za-z_][A-Za-z0-9_]*)|(constructor|fallback|receive))\s*\(",
+        )
+        .unwrap();
+        let visibility_regex = Regex::new(r"\b(external|public)\b").unwrap();
+        let containers = lines
+            .iter()
+            .enumerate()
+            .filter_map(|(line_index, line)| {
+                let container_capture = container_regex.captures(line)?;
+                let end_line_index = MacroParser::get_closing_line_index(&lines, line_index, true)?;
+                Some((
+                    container_capture[1].to_string(),
+                    container_capture[2].to_string(),
+                    line_index,
+                    end_line_index,
+                ))
+            })
+            .collect::<Vec<_>>();
+        let mut functions = vec![];
+        for (line_index, line) in lines.iter().enumerate() {
+            let function_capture = match function_regex.captures(line) {
+                None => continue,
+                Some(capture) => capture,
+            };
+            let name = function_capture
+                .get(1)
+                .or_else(|| function_capture.get(2))
+                .unwrap()
+                .as_str()
+                .to_string();
+            // functions without body (interfaces and abstract functions) are skipped
+            let signature_end_line_index = match lines
+                .iter()
+                .enumerate()
+                .skip(line_index)
+                .find(|(_, signature_line)| signature_line.contains(['{', ';']))
+            {
+                Some((end_index, signature_line)) => {
+                    match (signature_line.find('{'), signature_line.find(';')) {
+                        (Some(brace_index), Some(semicolon_index))
+                            if brace_index < semicolon_index =>
+                        {
+                            end_index
+                        }
+                        (Some(_), None) => end_index,
+                        _ => continue,
+                    }
+                }
+                None => continue,
+            };
+            let end_line_index =
+                match MacroParser::get_closing_line_index(&lines, signature_end_line_index, true) {
+                    None => continue,
+                    Some(end_index) => end_index,
+                };
+            let signature = lines[line_index..=signature_end_line_index]
+                .iter()
+                .map(|signature_line| signature_line.trim())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let signature = signature.split('{').next().unwrap().trim().to_string();
+            let container = containers
+                .iter()
+                .find(|(_, _, start, end)| *start < line_index && *end > line_index);
+            let is_contract_function = container.is_some_and(|(kind, _, _, _)| kind == "contract");
+            let is_entrypoint = is_contract_function
+                && name != "constructor"
+                && visibility_regex.is_match(&signature);
+            functions.push(SolidityFunction {
+                name,
+                contract_name: container
+                    .map(|(_, contract_name, _, _)| contract_name.clone())
+                    .unwrap_or_default(),
+                signature,
+                start_line_index: line_index,
+                signature_end_line_index,
+                end_line_index,
+                is_entrypoint,
+            });
+        }
+        functions
+    }
+
+    /// Returns the (type, name) of the parameters of a function signature
+    pub fn get_signature_parameters(signature: &str) -> Vec<(String, String)> {
+        let parameters_content = Self::get_parameters_content(signature);
+        parameters_content
+            .split(',')
+            .map(|parameter| parameter.split_whitespace().collect::<Vec<_>>())
+            .filter(|parameter_tokens| parameter_tokens.len() > 1)
+            .map(|parameter_tokens| {
+                (
+                    parameter_tokens[0].to_string(),
+                    parameter_tokens.last().unwrap().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the modifiers applied to a function, like onlyOwner or whenNotPaused
+    pub fn get_signature_modifiers(signature: &str) -> Vec<String> {
+        let parameters_content = Self::get_parameters_content(signature);
+        let after_parameters = signature
+            .split_once(&format!("({})", parameters_content))
+            .map(|(_, after)| after)
+            .unwrap_or_default();
+        let returns_regex = Regex::new(r"returns\s*\([^)]*\)").unwrap();
+        let modifier_regex = Regex::new(r"([A-Za-z_][A-Za-z0-9_]*)(\s*\([^)]*\))?").unwrap();
+        let keywords = [
+            "public", "external", "internal", "private", "view", "pure", "payable", "virtual",
+            "override",
+        ];
+        let after_parameters = returns_regex.replace_all(after_parameters, "");
+        modifier_regex
+            .captures_iter(&after_parameters)
+            .filter(|modifier_capture| !keywords.contains(&&modifier_capture[1]))
+            .map(|modifier_capture| modifier_capture[0].trim().to_string())
+            .collect()
+    }
+
+    /// Lines of the function body requiring or reverting over the value
+    pub fn get_validations(function_content: &str, value_name: &str) -> Vec<String> {
+        let value_regex = Regex::new(&format!(r"\b{}\b", regex::escape(value_name))).unwrap();
+        function_content
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| {
+                ["require(", "assert(", "revert", "if (", "if("]
+                    .iter()
+                    .any(|validation| line.contains(validation))
+                    && value_regex.is_match(line)
+            })
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    fn get_parameters_content(signature: &str) -> String {
+        let mut depth = 0;
+        let mut parameters_content = String::new();
+        for character in signature.chars() {
+            match character {
+                '(' => {
+                    depth += 1;
+                    if depth == 1 {
+                        continue;
+                    }
+                }
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            if depth > 0 {
+                parameters_content.push(character);
+            }
+        }
+        parameters_content
+    }
+
+    fn new_ca_account_parser(
+        content: &str,
+        account_type: &str,
+        account_name: &str,
+        solana_account_type: SolanaAccountType,
+        validations: Vec<String>,
+    ) -> CAAccountParser {
+        CAAccountParser {
+            content: content.to_string(),
+            solana_account_type,
+            account_struct_name: account_type.to_string(),
+            account_wrapper_name: account_type.to_string(),
+            lifetime_name: "".to_string(),
+            account_name: account_name.to_string(),
+            is_pda: false,
+            is_init: false,
+            is_mut: false,
+            is_close: false,
+            seeds: vec![],
+            rent_exemption_account: "".to_string(),
+            validations,
+        }
+    }
+}
+
+impl LanguageFrontend for SolidityFrontend {
+    fn get_functions(
+        &self,
+        file_path: &str,
+        file_content: &str,
+    ) -> Result<Vec<FunctionSourceCodeMetadata>, BatSonarError> {
+        Ok(Self::get_solidity_functions(file_content)
+            .into_iter()
+            .map(|function| {
+                FunctionSourceCodeMetadata::new(
+                    file_path.to_string(),
+                    function.name,
+                    if function.is_entrypoint {
+                        FunctionMetadataType::EntryPoint
+                    } else {
+                        FunctionMetadataType::Other
+                    },
+                    function.start_line_index + 1,
+                    function.end_line_index + 1,
+                    BatMetadata::create_metadata_id(),
+                )
+            })
+            .collect())
+    }
+
+    /// Solidity structs, plus a context accounts struct spanning the signature of every entry
+    /// point, so the parameters are handled as the accounts of the entry point
+    fn get_structs(
+        &self,
+        file_path: &str,
+        file_content: &str,
+    ) -> Result<Vec<StructSourceCodeMetadata>, BatSonarError> {
+        let lines = file_content.lines().collect::<Vec<_>>();
+        let struct_regex = Regex::new(r"^\s*struct\s+([A-Za-z_][A-Za-z0-9_]*)").unwrap();
+        let mut structs = lines
+            .iter()
+            .enumerate()
+            .filter_map(|(line_index, line)| {
+                let struct_capture = struct_regex.captures(line)?;
+                let end_line_index = MacroParser::get_closing_line_index(&lines, line_index, true)?;
+                Some(StructSourceCodeMetadata::new(
+                    file_path.to_string(),
+                    struct_capture[1].to_string(),
+                    StructMetadataType::Other,
+                    line_index + 1,
+                    end_line_index + 1,
+                    BatMetadata::create_metadata_id(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        for function in Self::get_solidity_functions(file_content)
+            .into_iter()
+            .filter(|function| function.is_entrypoint)
+        {
+            structs.push(StructSourceCodeMetadata::new(
+                file_path.to_string(),
+                self.get_context_accounts_name(&function.get_entrypoint_name())?,
+                StructMetadataType::ContextAccounts,
+                function.start_line_index + 1,
+                function.signature_end_line_index + 1,
+                BatMetadata::create_metadata_id(),
+            ));
+        }
+        Ok(structs)
+    }
+
+    fn get_entrypoint_names(&self, file_content: &str) -> Result<Vec<String>, BatSonarError> {
+        Ok(Self::get_solidity_functions(file_content)
+            .into_iter()
+            .filter(|function| function.is_entrypoint)
+            .map(|function| function.get_entrypoint_name())
+            .collect())
+    }
+
+    fn get_entrypoint_name(
+        &self,
+        entrypoint_function: &FunctionSourceCodeMetadata,
+    ) -> Result<String, BatSonarError> {
+        let file_content = fs::read_to_string(&entrypoint_function.path)
+            .into_report()
+            .change_context(BatSonarError)
+            .attach_printable(format!(
+                "Error reading file in {}",
+                entrypoint_function.path
+            ))?;
+        Self::get_solidity_functions(&file_content)
+            .into_iter()
+            .find(|function| function.start_line_index + 1 == entrypoint_function.start_line_index)
+            .map(|function| function.get_entrypoint_name())
+            .ok_or(BatSonarError)
+            .into_report()
+            .attach_printable(format!(
+                "Entry point function not found for {}",
+                entrypoint_function.name
+            ))
+    }
+
+    fn get_context_accounts_name(&self, entrypoint_name: &str) -> Result<String, BatSonarError> {
+        Ok(format!(
+            "{}Context",
+            entrypoint_name
+                .trim_end_matches(".md")
+                .replace('.', "_")
+                .to_pascal_case()
+        ))
+    }
+
+    /// The parameters of the entry point, and msg.sender when the function checks it or
+    /// applies modifiers
+    fn get_context_accounts_info(
+        &self,
+        context_accounts: &StructSourceCodeMetadata,
+    ) -> Result<Vec<CAAccountParser>, BatSonarError> {
+        let file_content = fs::read_to_string(&context_accounts.path)
+            .into_report()
+            .change_context(BatSonarError)
+            .attach_printable(format!("Error reading file in {}", context_accounts.path))?;
+        let function = Self::get_solidity_functions(&file_content)
+            .into_iter()
+            .find(|function| function.start_line_index + 1 == context_accounts.start_line_index)
+            .ok_or(BatSonarError)
+            .into_report()
+            .attach_printable(format!(
+                "Entry point function not found for {}",
+                context_accounts.name
+            ))?;
+        let function_content = file_content.lines().collect::<Vec<_>>()
+            [function.start_line_index..=function.end_line_index]
+            .join("\n");
+        let mut accounts_info = Self::get_signature_parameters(&function.signature)
+            .into_iter()
+            .map(|(parameter_type, parameter_name)| {
+                Self::new_ca_account_parser(
+                    &format!("{} {}", parameter_type, parameter_name),
+                    &parameter_type,
+                    &parameter_name,
+                    SolanaAccountType::Other,
+                    Self::get_validations(&function_content, &parameter_name),
+                )
+            })
+            .collect::<Vec<_>>();
+        let modifiers = Self::get_signature_modifiers(&function.signature);
+        if !modifiers.is_empty() || function_content.contains("msg.sender") {
+            let mut sender_validations = modifiers;
+            sender_validations.append(&mut Self::get_validations(&function_content, "msg.sender"));
+            accounts_info.push(Self::new_ca_account_parser(
+                "msg.sender",
+                "address",
+                "msg.sender",
+                SolanaAccountType::Signer,
+                sender_validations,
+            ));
+        }
+        Ok(accounts_info)
+    }
+}
+
+#[cfg(test)]
+mod solidity_frontend_test {
+    use crate::batbelt::sonar::frontend::solidity_frontend::SolidityFrontend;
+    use crate::batbelt::sonar::frontend::LanguageFrontend;
+
+    #[test]
+    fn test_get_solidity_functions() {
+        let contract_content = r#"pragma solidity ^0.8.0;
+
+interface IVault {
+    function deposit(uint256 amount) external;
+}
+
+contract Vault is IVault {
+    struct Position {
+        uint256 amount;
+    }
+
+    constructor(address owner_) {
+        owner = owner_;
+    }
+
+    function deposit(uint256 amount) external override whenNotPaused {
+        require(amount > 0, "zero amount");
+        positions[msg.sender].amount += amount;
+    }
+
+    function _accrue(address account) internal {}
+}
+"#;
+        let functions = SolidityFrontend::get_solidity_functions(contract_content);
+        let names = functions
+            .iter()
+            .map(|function| (function.name.as_str(), function.is_entrypoint))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                ("constructor", false),
+                ("deposit", true),
+                ("_accrue", false)
+            ]
+        );
+        let deposit = &functions[1];
+        assert_eq!(deposit.get_entrypoint_name(), "Vault.deposit");
+        assert_eq!(
+            SolidityFrontend
+                .get_context_accounts_name(&deposit.get_entrypoint_name())
+                .unwrap(),
+            "VaultDepositContext"
+        );
+        // a second contract with the same function gets its own entry point
+        let other_content = contract_content.replace("contract Vault", "contract OtherVault");
+        assert_eq!(
+            SolidityFrontend
+                .get_entrypoint_names(&format!("{}\n{}", contract_content, other_content))
+                .unwrap(),
+            vec![
+                "Vault.deposit".to_string(),
+                "OtherVault.deposit".to_string()
+            ]
+        );
+        assert_eq!(
+            SolidityFrontend::get_signature_parameters(&deposit.signature),
+            vec![("uint256".to_string(), "amount".to_string())]
+        );
+        assert_eq!(
+            SolidityFrontend::get_signature_modifiers(&deposit.signature),
+            vec!["whenNotPaused".to_string()]
+        );
+        let deposit_content = contract_content.lines().collect::<Vec<_>>()
+            [deposit.start_line_index..=deposit.end_line_index]
+            .join("\n");
+        assert_eq!(
+            SolidityFrontend::get_validations(&deposit_content, "amount"),
+            vec![r#"require(amount > 0, "zero amount");"#.to_string()]
+        );
+    }
+}
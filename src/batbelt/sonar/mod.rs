@@ -6,6 +6,7 @@ use std::fmt::{self, Debug};
 
 use std::fs;
 
+pub mod frontend;
 pub mod functions;
 pub mod sonar_interactive;
 pub mod structs;
@@ -6,8 +6,9 @@ use crate::batbelt::metadata::traits_source_code_metadata::TraitSourceCodeMetada
 use crate::batbelt::metadata::{
     BatMetadata, BatMetadataParser, BatMetadataType, SourceCodeMetadata,
 };
-use crate::batbelt::path::BatFolder;
-use crate::batbelt::sonar::{BatSonar, BatSonarError, SonarResultType};
+use crate::batbelt::path::{BatFile, BatFolder};
+use crate::batbelt::sonar::frontend::SourceLanguage;
+use crate::batbelt::sonar::{BatSonarError, SonarResultType};
 use crate::batbelt::BatEnumerator;
 
 use colored::Colorize;
@@ -16,9 +17,10 @@ use error_stack::{Result, ResultExt};
 use indicatif::{HumanDuration, MultiProgress, ProgressBar, ProgressStyle};
 
 use crate::batbelt::metadata::context_accounts_metadata::ContextAccountsMetadata;
-use crate::batbelt::parser::context_accounts_parser::CAAccountParser;
 use crate::batbelt::parser::entrypoint_parser::EntrypointParser;
 use crate::batbelt::parser::function_parser::FunctionParser;
+use crate::batbelt::parser::macro_parser::MacroParser;
+use crate::batbelt::parser::native_parser::NativeParser;
 use crate::batbelt::parser::trait_parser::TraitParser;
 
 use crate::batbelt::metadata::enums_source_code_metadata::EnumSourceCodeMetadata;
@@ -216,6 +218,10 @@ impl BatSonarInteractive {
         }
         // m.clear().unwrap();
 
+        if NativeParser::is_native_program().change_context(BatSonarError)? {
+            NativeParser::update_context_accounts_structs().change_context(BatSonarError)?;
+        }
+
         println!("{} Done in {}", SPARKLE, HumanDuration(started.elapsed()));
 
         Ok(())
@@ -227,6 +233,14 @@ impl BatSonarInteractive {
             .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ ");
         let entrypoint_names =
             EntrypointParser::get_entrypoint_names(false).change_context(BatSonarError)?;
+        let lib_file_content = BatFile::ProgramLib
+            .read_content(false)
+            .change_context(BatSonarError)?;
+        if let Some(program_id) = MacroParser::get_declare_id(&lib_file_content) {
+            let mut bat_metadata = BatMetadata::read_metadata().change_context(BatSonarError)?;
+            bat_metadata.program_id = program_id;
+            bat_metadata.save_metadata().change_context(BatSonarError)?;
+        }
         println!(
             "Getting metadata for {}, analyzing {} entry points",
             "Entry points".green(),
@@ -344,6 +358,7 @@ impl BatSonarInteractive {
             Some(StructMetadataType::ContextAccounts),
         )
         .change_context(BatSonarError)?;
+        let is_native_program = NativeParser::is_native_program().change_context(BatSonarError)?;
         println!(
             "Getting metadata for {}, analyzing {} {}",
             StructMetadataType::ContextAccounts.get_colored_name(true),
@@ -361,15 +376,15 @@ impl BatSonarInteractive {
                         pb.set_prefix(format!("[{}/{}]", idx + 1, ca_sc_clone.len()));
                         pb.set_message(format!("Getting information for: {}", ca_sc.name));
                         pb.inc(1);
-                        let ca_content =
-                            ca_sc.to_source_code_parser(None).get_source_code_content();
-                        let bat_sonar =
-                            BatSonar::new_scanned(&ca_content, SonarResultType::ContextAccountsAll);
-                        let ca_info = bat_sonar
-                            .results
-                            .into_iter()
-                            .map(|result| CAAccountParser::new_from_sonar_result(result).unwrap())
-                            .collect::<Vec<_>>();
+                        let ca_info = if is_native_program {
+                            NativeParser::get_context_accounts_info(ca_sc).unwrap()
+                        } else {
+                            SourceLanguage::from_path(&ca_sc.path)
+                                .unwrap_or(SourceLanguage::Rust)
+                                .get_frontend()
+                                .get_context_accounts_info(ca_sc)
+                                .unwrap()
+                        };
                         let context_accounts_metadata = ContextAccountsMetadata::new(
                             ca_sc.name.clone(),
                             BatMetadata::create_metadata_id(),
@@ -12,10 +12,13 @@ use crate::batbelt::parser::function_parser::FunctionParser;
 use crate::batbelt::parser::solana_account_parser::{SolanaAccountParser, SolanaAccountType};
 use crate::batbelt::parser::ParserResult;
 use crate::batbelt::path::BatFile;
+use crate::batbelt::sonar::frontend::SourceLanguage;
 use crate::batbelt::sonar::{BatSonar, SonarResultType};
 use crate::batbelt::templates::code_overhaul_template::CoderOverhaulTemplatePlaceholders::{
     CompleteWithNotes, CompleteWithTheRestOfStateChanges,
 };
+use crate::batbelt::templates::open_questions_template::OpenQuestionsTemplate;
+use crate::batbelt::templates::sidecar_template::SidecarTemplate;
 use crate::batbelt::templates::{TemplateError, TemplateResult};
 use crate::batbelt::{BatEnumerator, ShareableData};
 use crate::config::BatConfig;
@@ -23,6 +26,7 @@ use crate::config::BatConfig;
 pub struct CodeOverhaulTemplate {
     pub entrypoint_name: String,
     pub entrypoint_parser: Option<EntrypointParser>,
+    pub definition: CodeOverhaulTemplateDefinition,
 }
 
 impl CodeOverhaulTemplate {
@@ -37,48 +41,156 @@ impl CodeOverhaulTemplate {
         Ok(Self {
             entrypoint_name: entrypoint_name.to_string(),
             entrypoint_parser,
+            definition: CodeOverhaulTemplateDefinition::read_definition()?,
         })
     }
 
     pub fn get_markdown_content(&self) -> TemplateResult<String> {
-        let state_changes_content = CodeOverhaulSection::StateChanges
-            .get_section_content_for_start_co_file(self.entrypoint_parser.clone())?;
-        let notes_content = CodeOverhaulSection::Notes
-            .get_section_content_for_start_co_file(self.entrypoint_parser.clone())?;
-        let signers_content = CodeOverhaulSection::Signers
-            .get_section_content_for_start_co_file(self.entrypoint_parser.clone())?;
-        let function_parameters_content = CodeOverhaulSection::HandlerFunctionParameters
-            .get_section_content_for_start_co_file(self.entrypoint_parser.clone())?;
-        let context_accounts_content = CodeOverhaulSection::ContextAccounts
-            .get_section_content_for_start_co_file(self.entrypoint_parser.clone())?;
-        let validations_content = CodeOverhaulSection::Validations
-            .get_section_content_for_start_co_file(self.entrypoint_parser.clone())?;
-        let miro_frame_url_content = CodeOverhaulSection::MiroFrameUrl
-            .get_section_content_for_start_co_file(self.entrypoint_parser.clone())?;
+        let mut sections_content = vec![];
+        for section in &self.definition.sections {
+            sections_content.push(section.get_content(self.entrypoint_parser.clone())?);
+        }
+        Ok(format!("{}\n", sections_content.join("\n\n")))
+    }
+}
 
-        Ok(format!(
-            "{state_changes_content}\
-            \n\
-            \n\
-            {notes_content}\
-            \n\
-            \n\
-            {signers_content}\
-            \n\
-            \n\
-            {function_parameters_content}\
-            \n\
-            \n\
-            {context_accounts_content}\
-            \n\
-            \n\
-            {validations_content}\
-            \n\
-            \n\
-            {miro_frame_url_content}
-            ",
-        ))
+/// Section of the code-overhaul template, a built-in one generated from the entry point or a
+/// custom one with static text and a checklist
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CodeOverhaulTemplateSection {
+    pub built_in: Option<CodeOverhaulSection>,
+    /// title of a custom section, the built-in ones keep their own
+    pub title: String,
+    pub content: String,
+    /// written as unchecked items, finishing the file asks before leaving them unchecked
+    pub checklist: Vec<String>,
+}
+
+impl CodeOverhaulTemplateSection {
+    pub fn new_built_in(code_overhaul_section: CodeOverhaulSection) -> Self {
+        Self {
+            built_in: Some(code_overhaul_section),
+            ..Default::default()
+        }
+    }
+
+    pub fn get_title(&self) -> String {
+        match &self.built_in {
+            Some(code_overhaul_section) => code_overhaul_section.to_string().to_sentence_case(),
+            None => self.title.clone(),
+        }
+    }
+
+    pub fn to_markdown_header(&self) -> String {
+        format!("# {}:", self.get_title())
     }
+
+    pub fn get_content(&self, ep_parser: Option<EntrypointParser>) -> TemplateResult<String> {
+        if let Some(code_overhaul_section) = &self.built_in {
+            return code_overhaul_section.get_section_content_for_start_co_file(ep_parser);
+        }
+        let mut blocks = vec![self.to_markdown_header()];
+        if !self.content.trim().is_empty() {
+            blocks.push(self.content.trim().to_string());
+        }
+        if !self.checklist.is_empty() {
+            blocks.push(
+                self.checklist
+                    .iter()
+                    .map(|item| format!("- [ ] {}", item))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+        }
+        Ok(blocks.join("\n\n"))
+    }
+}
+
+/// Code-overhaul template of the project, read from notes/code_overhaul_template.toml so the
+/// sections can be reordered and extended with the checks of each audit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CodeOverhaulTemplateDefinition {
+    pub sections: Vec<CodeOverhaulTemplateSection>,
+}
+
+impl Default for CodeOverhaulTemplateDefinition {
+    fn default() -> Self {
+        Self {
+            sections: CodeOverhaulSection::get_type_vec()
+                .into_iter()
+                .map(CodeOverhaulTemplateSection::new_built_in)
+                .collect(),
+        }
+    }
+}
+
+impl CodeOverhaulTemplateDefinition {
+    /// The default definition if the project does not have a code_overhaul_template.toml
+    pub fn read_definition() -> TemplateResult<Self> {
+        let definition_file = BatFile::CodeOverhaulTemplateToml;
+        if !definition_file
+            .file_exists()
+            .change_context(TemplateError)?
+        {
+            return Ok(Self::default());
+        }
+        let definition_content = definition_file
+            .read_content(false)
+            .change_context(TemplateError)?;
+        toml::from_str(&definition_content)
+            .into_report()
+            .change_context(TemplateError)
+            .attach_printable("Error parsing code_overhaul_template.toml")
+    }
+
+    pub fn save(&self) -> TemplateResult<()> {
+        let definition_content = toml::to_string_pretty(self)
+            .into_report()
+            .change_context(TemplateError)?;
+        BatFile::CodeOverhaulTemplateToml
+            .write_content(false, &definition_content)
+            .change_context(TemplateError)
+    }
+
+    /// Headers of the declared sections and of all the built-in ones, so the files created
+    /// before a template change are split the same way
+    pub fn get_markdown_headers(&self) -> Vec<String> {
+        let mut markdown_headers = self
+            .sections
+            .iter()
+            .map(|section| section.to_markdown_header())
+            .collect::<Vec<_>>();
+        for code_overhaul_section in CodeOverhaulSection::get_type_vec() {
+            let markdown_header = code_overhaul_section.to_markdown_header();
+            if !markdown_headers.contains(&markdown_header) {
+                markdown_headers.push(markdown_header);
+            }
+        }
+        markdown_headers
+    }
+}
+
+/// Content of a section of a code-overhaul file, including its header. Empty if the section is
+/// missing
+pub fn get_code_overhaul_section_content(
+    co_content: &str,
+    section_header: &str,
+    markdown_headers: &[String],
+) -> String {
+    let lines = co_content.lines().collect::<Vec<_>>();
+    let start = match lines.iter().position(|line| line.trim() == section_header) {
+        Some(start) => start,
+        None => return "".to_string(),
+    };
+    let end = lines
+        .iter()
+        .skip(start + 1)
+        .position(|line| markdown_headers.iter().any(|header| line.trim() == header))
+        .map(|position| position + start + 1)
+        .unwrap_or(lines.len());
+    lines[start..end].join("\n").trim().to_string()
 }
 
 #[derive(
@@ -180,8 +292,12 @@ impl CodeOverhaulSection {
                     None
                 }
             });
+        let answered_questions_notes =
+            OpenQuestionsTemplate::read()?.get_code_overhaul_notes(&entry_point_parser.name);
         if ca_info_with_validation.clone().count() == 0 {
-            return Ok(format!("- {}", CompleteWithNotes.to_placeholder()));
+            let mut result = vec![format!("- {}", CompleteWithNotes.to_placeholder())];
+            result.extend(answered_questions_notes);
+            return Ok(result.join("\n"));
         }
 
         let mut result = vec![];
@@ -197,6 +313,7 @@ impl CodeOverhaulSection {
                 GitAction::GetRepositoryPermalink {
                     file_path: context_accounts_sc_metadata.path.clone(),
                     start_line_index: validation_line + 1,
+                    end_line_index: None,
                     permalink: shared_permalink.original,
                 }
                 .execute_action()
@@ -210,6 +327,7 @@ impl CodeOverhaulSection {
             }
         }
         result.push(format!("- {}", CompleteWithNotes.to_placeholder()));
+        result.extend(answered_questions_notes);
         Ok(result.join("\n"))
     }
 
@@ -570,7 +688,12 @@ impl CodeOverhaulSection {
         } else {
             validations_vec
                 .iter()
-                .map(|validation| format!("```rust\n{}\n```", validation))
+                .map(|validation| {
+                    SourceLanguage::get_code_block(
+                        &entrypoint_parser.entry_point_function.path,
+                        validation,
+                    )
+                })
                 .collect::<Vec<_>>()
                 .join("\n")
         };
@@ -681,7 +804,7 @@ impl CodeOverhaulSection {
             .collect::<Vec<_>>()
             .join("\n");
 
-        format!("{}\n{}\n{}", "```rust", formatted, "```")
+        SourceLanguage::get_code_block(&entrypoint_parser.context_accounts.path, &formatted)
     }
 
     fn get_handler_function_parameters_section_content(
@@ -721,15 +844,15 @@ impl CodeOverhaulSection {
                     parameter.parameter_type.trim_start_matches("&").to_string(),
                     StructMetadataType::Other,
                 ) {
-                    parameters.push(format!(
-                        "```rust\n{}\n```",
-                        struct_metadata
+                    parameters.push(SourceLanguage::get_code_block(
+                        &struct_metadata.path,
+                        &struct_metadata
                             .to_source_code_parser(None)
                             .get_source_code_content()
                             .lines()
                             .map(|line| format!("  {line}"))
                             .collect::<Vec<_>>()
-                            .join("\n")
+                            .join("\n"),
                     ));
                 }
             }
@@ -784,3 +907,62 @@ fn test_to_title() {
     println!("title {:#?}", title);
     assert_eq!(expected, title, "Incorrect title");
 }
+
+#[test]
+fn test_code_overhaul_template_definition() {
+    let co_template_definition: CodeOverhaulTemplateDefinition = toml::from_str(
+        r#"
+[[sections]]
+built_in = "StateChanges"
+
+[[sections]]
+title = "Reentrancy via CPI"
+content = "Programs invoked by the entry point"
+checklist = ["state is updated before the CPI", "invoked program ids are checked"]
+
+[[sections]]
+built_in = "Notes"
+
+[[sections]]
+title = "Rent/close handling"
+checklist = ["closed accounts send the lamports to the expected account"]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        toml::from_str::<CodeOverhaulTemplateDefinition>(
+            &toml::to_string_pretty(&co_template_definition).unwrap()
+        )
+        .unwrap(),
+        co_template_definition
+    );
+    let reentrancy_section = &co_template_definition.sections[1];
+    assert_eq!(
+        reentrancy_section.get_content(None).unwrap(),
+        "# Reentrancy via CPI:\n\nPrograms invoked by the entry point\n\n- [ ] state is updated before the CPI\n- [ ] invoked program ids are checked"
+    );
+    let co_template = CodeOverhaulTemplate {
+        entrypoint_name: "withdraw".to_string(),
+        entrypoint_parser: None,
+        definition: co_template_definition.clone(),
+    };
+    let co_content = co_template.get_markdown_content().unwrap();
+    let markdown_headers = co_template_definition.get_markdown_headers();
+    assert_eq!(
+        get_code_overhaul_section_content(&co_content, "# Rent/close handling:", &markdown_headers),
+        "# Rent/close handling:\n\n- [ ] closed accounts send the lamports to the expected account"
+    );
+    assert_eq!(
+        get_code_overhaul_section_content(&co_content, "# Notes:", &markdown_headers),
+        "# Notes:"
+    );
+    // the built-in sections removed from the template are empty
+    assert_eq!(
+        get_code_overhaul_section_content(&co_content, "# Signers:", &markdown_headers),
+        ""
+    );
+    assert_eq!(
+        CodeOverhaulTemplateDefinition::default().sections[0].to_markdown_header(),
+        CodeOverhaulSection::StateChanges.to_markdown_header()
+    );
+}
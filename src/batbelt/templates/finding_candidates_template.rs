@@ -0,0 +1,277 @@
+use crate::batbelt::path::BatFile;
+use crate::batbelt::templates::sidecar_template::SidecarTemplate;
+use crate::batbelt::templates::{TemplateError, TemplateResult};
+use error_stack::Report;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+const FINDING_CANDIDATE_PLACEHOLDER: &str = "finding candidate description";
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Default,
+    Serialize,
+    Deserialize,
+    strum_macros::Display,
+    strum_macros::EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingCandidateStatus {
+    #[default]
+    ToReview,
+    Accepted,
+    Rejected,
+}
+
+impl FindingCandidateStatus {
+    fn get_section_title(&self) -> &'static str {
+        match self {
+            FindingCandidateStatus::ToReview => "to review",
+            FindingCandidateStatus::Accepted => "accepted",
+            FindingCandidateStatus::Rejected => "rejected",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FindingCandidate {
+    pub id: usize,
+    pub description: String,
+    pub status: FindingCandidateStatus,
+    /// metadata id of the source code item where the candidate was spotted
+    pub metadata_id: String,
+    /// name of the source code item
+    pub location: String,
+    pub permalink: String,
+    pub author: String,
+    pub notes: String,
+    /// finding created from the candidate by bat-cli candidate promote
+    pub finding_name: String,
+}
+
+impl FindingCandidate {
+    fn get_markdown_item(&self) -> String {
+        let check = if self.status == FindingCandidateStatus::ToReview {
+            " "
+        } else {
+            "x"
+        };
+        let mut item = if self.permalink.is_empty() {
+            format!("- [{}] {}", check, self.description)
+        } else {
+            format!("- [{}] [{}]({})", check, self.description, self.permalink)
+        };
+        if !self.notes.is_empty() {
+            item = format!("{}, {}", item, self.notes);
+        }
+        let mut details = vec![format!("#{}", self.id)];
+        if !self.location.is_empty() {
+            details.push(format!("`{}`", self.location));
+        }
+        if !self.author.is_empty() {
+            details.push(format!("by {}", self.author));
+        }
+        if !self.finding_name.is_empty() {
+            details.push(format!("promoted to `{}`", self.finding_name));
+        }
+        format!("{}\n  - {}", item, details.join(" "))
+    }
+}
+
+/// Finding candidates kept in finding_candidates.toml, the finding_candidates.md is generated
+/// from it
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FindingCandidatesTemplate {
+    pub candidates: Vec<FindingCandidate>,
+}
+
+impl FindingCandidatesTemplate {
+    /// Returns the id assigned to the candidate
+    pub fn add_candidate(&mut self, mut candidate: FindingCandidate) -> usize {
+        candidate.id = self
+            .candidates
+            .iter()
+            .map(|candidate| candidate.id)
+            .max()
+            .unwrap_or_default()
+            + 1;
+        self.candidates.push(candidate);
+        self.candidates.last().unwrap().id
+    }
+
+    pub fn get_candidates_by_status(
+        &self,
+        status: FindingCandidateStatus,
+    ) -> Vec<FindingCandidate> {
+        self.candidates
+            .iter()
+            .filter(|candidate| candidate.status == status)
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_candidate_mut(&mut self, id: usize) -> TemplateResult<&mut FindingCandidate> {
+        self.candidates
+            .iter_mut()
+            .find(|candidate| candidate.id == id)
+            .ok_or(Report::new(TemplateError).attach_printable(format!(
+                "Finding candidate #{} not found in finding_candidates.toml",
+                id
+            )))
+    }
+}
+
+impl SidecarTemplate for FindingCandidatesTemplate {
+    const SIDECAR_FILE_NAME: &'static str = "finding_candidates.toml";
+    const GENERATOR_COMMAND: &'static str = "bat-cli candidate";
+
+    fn sidecar_file() -> BatFile {
+        BatFile::FindingCandidatesToml
+    }
+
+    fn markdown_file() -> BatFile {
+        BatFile::FindingCandidates
+    }
+
+    fn to_markdown(&self) -> String {
+        let sections = [
+            FindingCandidateStatus::Accepted,
+            FindingCandidateStatus::Rejected,
+            FindingCandidateStatus::ToReview,
+        ]
+        .iter()
+        .map(|status| {
+            let items = self
+                .get_candidates_by_status(*status)
+                .iter()
+                .map(|candidate| candidate.get_markdown_item())
+                .collect::<Vec<_>>();
+            let items = if items.is_empty() {
+                "-".to_string()
+            } else {
+                items.join("\n")
+            };
+            format!("## {}\n\n{}", status.get_section_title(), items)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+        let generated_comment = Self::get_generated_comment();
+        format!(
+            r#"# Finding candidates (a.k.a Smellies)
+
+![Alt Text](https://media3.giphy.com/media/J2gHlRQQvFamqOWlJF/giphy.gif)
+
+{generated_comment}
+
+{sections}
+"#
+        )
+    }
+
+    fn new_from_markdown(markdown_content: &str) -> Self {
+        let item_regex = Regex::new(r#"^- \[[ xX]\] \[(.*)\]\((.*?)\)(?:,\s*(.*))?$"#).unwrap();
+        // checklist items written by hand, without the permalink
+        let plain_item_regex = Regex::new(r#"^- \[[ xX]\] (.+)$"#).unwrap();
+        let mut finding_candidates = Self::default();
+        let mut status = FindingCandidateStatus::ToReview;
+        for line in markdown_content.lines() {
+            if let Some(section_title) = line.strip_prefix("## ") {
+                status = match section_title.trim() {
+                    "accepted" => FindingCandidateStatus::Accepted,
+                    "rejected" => FindingCandidateStatus::Rejected,
+                    _ => FindingCandidateStatus::ToReview,
+                };
+                continue;
+            }
+            let candidate = if let Some(captures) = item_regex.captures(line.trim_end()) {
+                FindingCandidate {
+                    description: captures[1].to_string(),
+                    status,
+                    permalink: captures[2].to_string(),
+                    notes: captures
+                        .get(3)
+                        .map(|notes| notes.as_str().to_string())
+                        .unwrap_or_default(),
+                    ..Default::default()
+                }
+            } else if let Some(captures) = plain_item_regex.captures(line.trim_end()) {
+                FindingCandidate {
+                    description: captures[1].trim().to_string(),
+                    status,
+                    ..Default::default()
+                }
+            } else {
+                continue;
+            };
+            if candidate.description == FINDING_CANDIDATE_PLACEHOLDER {
+                continue;
+            }
+            finding_candidates.add_candidate(candidate);
+        }
+        finding_candidates
+    }
+}
+
+#[test]
+fn test_finding_candidates_from_markdown() {
+    let markdown_content = r#"# Finding candidates (a.k.a Smellies)
+
+## accepted
+
+- [ ] [finding candidate description](http://url-to-client-repository-with-corresponding-branch), little note to describe what to do with it
+- [x] [vault owner not checked](https://github.com/org/vault/blob/641bdb7/src/lib.rs#L10), check withdraw too
+
+## rejected
+
+## to review
+
+- [ ] [overflow on deposit](https://github.com/org/vault/blob/641bdb7/src/lib.rs#L20)
+- [ ] check rounding in fee
+"#;
+    let mut finding_candidates = FindingCandidatesTemplate::new_from_markdown(markdown_content);
+    assert_eq!(finding_candidates.candidates.len(), 3);
+    assert_eq!(
+        finding_candidates.candidates[2].description,
+        "check rounding in fee"
+    );
+    assert!(finding_candidates.candidates[2].permalink.is_empty());
+    assert_eq!(
+        finding_candidates.candidates[0].status,
+        FindingCandidateStatus::Accepted
+    );
+    assert_eq!(finding_candidates.candidates[0].notes, "check withdraw too");
+    assert_eq!(finding_candidates.candidates[1].id, 2);
+
+    let new_id = finding_candidates.add_candidate(FindingCandidate {
+        description: "missing signer".to_string(),
+        location: "withdraw".to_string(),
+        author: "alice".to_string(),
+        ..Default::default()
+    });
+    assert_eq!(new_id, 4);
+    finding_candidates.get_candidate_mut(2).unwrap().status = FindingCandidateStatus::Rejected;
+
+    let sidecar_content = toml::to_string_pretty(&finding_candidates).unwrap();
+    assert_eq!(
+        toml::from_str::<FindingCandidatesTemplate>(&sidecar_content).unwrap(),
+        finding_candidates
+    );
+    let generated_markdown = finding_candidates.to_markdown();
+    assert!(generated_markdown.contains(
+        "## rejected\n\n- [x] [overflow on deposit](https://github.com/org/vault/blob/641bdb7/src/lib.rs#L20)\n  - #2"
+    ));
+    assert!(generated_markdown.contains("- [ ] check rounding in fee\n  - #3\n"));
+    assert!(generated_markdown.contains("  - #4 `withdraw` by alice"));
+    // the generated markdown imports back to the same checklist
+    assert_eq!(
+        FindingCandidatesTemplate::new_from_markdown(&generated_markdown)
+            .candidates
+            .len(),
+        4
+    );
+}
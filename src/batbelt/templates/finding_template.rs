@@ -1,114 +1,256 @@
+use crate::batbelt::bat_dialoguer::BatDialoguer;
+use crate::batbelt::parser::finding_parser::{
+    get_finding_id_prefix, get_finding_section, get_next_finding_id, replace_finding_section,
+    set_finding_id, FindingParser, FINDING_SEVERITY_TITLE,
+};
 use crate::batbelt::path::BatFile;
-use crate::batbelt::templates::TemplateError;
-use error_stack::{Result, ResultExt};
+use crate::batbelt::templates::{TemplateError, TemplateResult};
+use crate::config::BatConfig;
+use error_stack::{IntoReport, Report, Result, ResultExt};
 use inflector::Inflector;
+use serde::{Deserialize, Serialize};
 
 pub struct FindingTemplate;
 
 impl FindingTemplate {
-    pub fn new_finding_file(
+    pub fn new_finding_file(finding_name: &str, severity: &str) -> Result<(), TemplateError> {
+        let content = FindingTemplateDefinition::read_definition()?
+            .get_finding_content(&finding_name.to_sentence_case(), severity);
+        Self::write_finding_file(finding_name, &content)
+    }
+
+    /// Replaces the description and affected resources placeholders, like when promoting a
+    /// finding candidate
+    pub fn new_prefilled_finding_file(
         finding_name: &str,
-        is_informational: bool,
+        severity: &str,
+        description: &str,
+        affected_resource: &str,
     ) -> Result<(), TemplateError> {
-        let finding_title = finding_name.to_sentence_case();
-        let content = if is_informational {
-            Self::get_informational_content(&finding_title)
-        } else {
-            Self::get_finding_content(&finding_title)
-        };
+        let content = FindingTemplateDefinition::read_definition()?
+            .get_finding_content(&finding_name.to_sentence_case(), severity);
+        let content = replace_finding_section(&content, "Description", description);
+        let content = replace_finding_section(
+            &content,
+            "Affected resources",
+            &format!("- {}", affected_resource),
+        );
+        Self::write_finding_file(finding_name, &content)
+    }
+
+    // the permanent id is assigned on creation, so it survives the severity renames. It follows
+    // the ids on the branches of every auditor, update-ids fixes the ones not pushed yet
+    fn write_finding_file(finding_name: &str, content: &str) -> Result<(), TemplateError> {
+        let bat_config = BatConfig::get_config().change_context(TemplateError)?;
+        let findings = FindingParser::get_all_auditors_findings().change_context(TemplateError)?;
+        let finding_id =
+            get_next_finding_id(&findings, &get_finding_id_prefix(&bat_config.project_name));
         BatFile::FindingToReview {
             file_name: finding_name.to_string(),
         }
-        .write_content(false, &content)
+        .write_content(false, &set_finding_id(content, &finding_id))
         .change_context(TemplateError)?;
         Ok(())
     }
+}
 
-    pub fn get_finding_content(finding_title: &str) -> String {
-        let content = format!(
-            r#"## {}
-
-**Severity:** High
-
-**Status:** Open
-
-| Impact | Likelihood | Difficulty |
-| :----: | :--------: | :--------: |
-|  High  |   Medium   |    Low     |
-
-### Description {{-}}
-
-Fill the description
-
-### Impact {{-}}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FindingTemplateSection {
+    pub title: String,
+    /// initial content, a required section can't keep it when finishing the finding
+    pub placeholder: String,
+    #[serde(default)]
+    pub required: bool,
+}
 
-Fill the impact
+impl FindingTemplateSection {
+    fn new(title: &str, placeholder: &str, required: bool) -> Self {
+        Self {
+            title: title.to_string(),
+            placeholder: placeholder.to_string(),
+            required,
+        }
+    }
+}
 
-### Evidence {{-}}
+/// Finding template of the project, read from notes/finding_template.toml so each client can
+/// have its own severity scale and report sections
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FindingTemplateDefinition {
+    /// from the most to the least severe, the to-review files are prefixed with the position
+    pub severities: Vec<String>,
+    /// severities written with the informational header and sections
+    pub informational_severities: Vec<String>,
+    /// content between the Status line and the first section, like the impact table
+    pub header: String,
+    pub informational_header: String,
+    pub sections: Vec<FindingTemplateSection>,
+    pub informational_sections: Vec<FindingTemplateSection>,
+}
 
-<figure style="display:block">
-    <img style="max-width:100%" src="../../figures/finding-name-1.png"/>
+impl Default for FindingTemplateDefinition {
+    fn default() -> Self {
+        let evidence_placeholder = |figure_name: &str, second_figure_number: usize| {
+            format!(
+                r#"<figure style="display:block">
+    <img style="max-width:100%" src="../../figures/{figure_name}-1.png"/>
 </figure>
 <figure style="display:block">
-    <img style="max-width:100%" src="../../figures/finding-name-2.png"/>
+    <img style="max-width:100%" src="../../figures/{figure_name}-{second_figure_number}.png"/>
 </figure>
 
-Add a description of the evidence here
-
-### Recommendation {{-}}
-
-Add recommendations
-
-### Affected resources {{-}}
-
-- N/A
-
-### Reference {{-}}
-
-- N/A
-"#,
-            finding_title
-        );
-        content
+Add a description of the evidence here"#
+            )
+        };
+        Self {
+            severities: ["High", "Medium", "Low", "Informational"]
+                .map(|severity| severity.to_string())
+                .to_vec(),
+            informational_severities: vec!["Informational".to_string()],
+            header: "| Impact | Likelihood | Difficulty |\n\
+                    | :----: | :--------: | :--------: |\n\
+                    |  High  |   Medium   |    Low     |"
+                .to_string(),
+            informational_header: "".to_string(),
+            sections: vec![
+                FindingTemplateSection::new("Description", "Fill the description", true),
+                FindingTemplateSection::new("Impact", "Fill the impact", true),
+                FindingTemplateSection::new(
+                    "Evidence",
+                    &evidence_placeholder("finding-name", 2),
+                    false,
+                ),
+                FindingTemplateSection::new("Recommendation", "Add recommendations", true),
+                FindingTemplateSection::new("Affected resources", "- N/A", false),
+                FindingTemplateSection::new("Reference", "- N/A", false),
+            ],
+            informational_sections: vec![
+                FindingTemplateSection::new("Description", "Add a description", true),
+                FindingTemplateSection::new(
+                    "Evidence",
+                    &evidence_placeholder("observation", 1),
+                    false,
+                ),
+                FindingTemplateSection::new("Recommendation", "Add some recomendations", true),
+                FindingTemplateSection::new(
+                    "Affected resources",
+                    "- Add affected resources",
+                    false,
+                ),
+                FindingTemplateSection::new("Reference", "- N/A", false),
+            ],
+        }
     }
-    pub fn get_informational_content(finding_title: &str) -> String {
-        let content = format!(
-            r#"## {}
-
-**Severity:** Informational
-
-**Status:** Open
-
-### Description {{-}}
-
-Add a description
-
-### Evidence {{-}}
+}
 
-<figure style="display:block">
-    <img style="max-width:100%" src="../../figures/observation-1.png"/>
-</figure>
-<figure style="display:block">
-    <img style="max-width:100%" src="../../figures/observation-1.png"/>
-</figure>
+impl FindingTemplateDefinition {
+    /// The default definition if the project does not have a finding_template.toml
+    pub fn read_definition() -> TemplateResult<Self> {
+        let definition_file = BatFile::FindingTemplateToml;
+        if !definition_file
+            .file_exists()
+            .change_context(TemplateError)?
+        {
+            return Ok(Self::default());
+        }
+        let definition_content = definition_file
+            .read_content(false)
+            .change_context(TemplateError)?;
+        let definition: Self = toml::from_str(&definition_content)
+            .into_report()
+            .change_context(TemplateError)
+            .attach_printable("Error parsing finding_template.toml")?;
+        if definition.severities.is_empty() {
+            return Err(Report::new(TemplateError)
+                .attach_printable("finding_template.toml should declare at least one severity"));
+        }
+        Ok(definition)
+    }
 
-Add a description of the evidence here
+    pub fn save(&self) -> TemplateResult<()> {
+        let definition_content = toml::to_string_pretty(self)
+            .into_report()
+            .change_context(TemplateError)?;
+        BatFile::FindingTemplateToml
+            .write_content(false, &definition_content)
+            .change_context(TemplateError)
+    }
 
-### Recommendation {{-}}
+    pub fn prompt_severity(&self) -> TemplateResult<String> {
+        let selection = BatDialoguer::select(
+            "Select the severity of the finding:".to_string(),
+            self.severities.clone(),
+            None,
+        )
+        .change_context(TemplateError)?;
+        Ok(self.severities[selection].clone())
+    }
 
-Add some recomendations
+    pub fn is_informational(&self, severity: &str) -> bool {
+        self.informational_severities
+            .iter()
+            .any(|informational_severity| informational_severity.eq_ignore_ascii_case(severity))
+    }
 
-### Affected resources {{-}}
+    /// Position of the severity starting from 1, None if the severity is not declared
+    pub fn get_severity_flag(&self, severity: &str) -> Option<usize> {
+        self.severities
+            .iter()
+            .position(|declared_severity| declared_severity.eq_ignore_ascii_case(severity))
+            .map(|position| position + 1)
+    }
 
-- Add affected resources
+    pub fn get_sections(&self, severity: &str) -> &[FindingTemplateSection] {
+        if self.is_informational(severity) {
+            &self.informational_sections
+        } else {
+            &self.sections
+        }
+    }
 
-### Reference {{-}}
+    pub fn get_finding_content(&self, finding_title: &str, severity: &str) -> String {
+        let header = if self.is_informational(severity) {
+            &self.informational_header
+        } else {
+            &self.header
+        };
+        let mut blocks = vec![
+            format!("## {}", finding_title),
+            format!("{} {}", FINDING_SEVERITY_TITLE, severity),
+            "**Status:** Open".to_string(),
+        ];
+        if !header.trim().is_empty() {
+            blocks.push(header.trim().to_string());
+        }
+        for section in self.get_sections(severity) {
+            blocks.push(format!(
+                "### {} {{-}}\n\n{}",
+                section.title,
+                section.placeholder.trim()
+            ));
+        }
+        format!("{}\n", blocks.join("\n\n"))
+    }
 
-- N/A
-"#,
-            finding_title
-        );
-        content
+    /// Titles of the required sections that are missing, empty or still have the placeholder
+    pub fn get_incomplete_sections(&self, content: &str) -> Vec<String> {
+        let severity = content
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(FINDING_SEVERITY_TITLE))
+            .unwrap_or_default()
+            .trim();
+        self.get_sections(severity)
+            .iter()
+            .filter(|section| {
+                let section_content = get_finding_section(content, &section.title);
+                section.required
+                    && (section_content.is_empty()
+                        || (!section.placeholder.trim().is_empty()
+                            && section_content.contains(section.placeholder.trim())))
+            })
+            .map(|section| section.title.clone())
+            .collect()
     }
 }
 
@@ -124,8 +266,59 @@ fn test_title_parser() {
 #[test]
 fn test_content() {
     let test_text = "hello_how Are-you".to_sentence_case();
-    let finding_content = FindingTemplate::get_finding_content(&test_text);
-    let info_content = FindingTemplate::get_informational_content(&test_text);
+    let definition = FindingTemplateDefinition::default();
+    let finding_content = definition.get_finding_content(&test_text, "High");
+    let info_content = definition.get_finding_content(&test_text, "Informational");
     println!("finding: {}", finding_content);
     println!("info: {}", info_content);
+    assert!(finding_content.contains("**Status:** Open\n\n| Impact | Likelihood | Difficulty |"));
+    assert!(info_content.contains("**Status:** Open\n\n### Description {-}\n\nAdd a description"));
+    assert_eq!(
+        definition.get_incomplete_sections(&finding_content),
+        vec!["Description", "Impact", "Recommendation"]
+    );
+    let finished_content =
+        replace_finding_section(&finding_content, "Description", "The owner is not checked");
+    let finished_content = replace_finding_section(&finished_content, "Impact", "Drained vault");
+    let finished_content =
+        replace_finding_section(&finished_content, "Recommendation", "Check the owner");
+    assert!(definition
+        .get_incomplete_sections(&finished_content)
+        .is_empty());
+
+    // a client scale with Critical and an extra section
+    let mut client_definition: FindingTemplateDefinition = toml::from_str(
+        r#"
+severities = ["Critical", "High", "Medium", "Low", "Informational"]
+informational_severities = ["Informational"]
+header = "**CVSS:** Fill the CVSS vector"
+
+[[sections]]
+title = "Description"
+placeholder = "Fill the description"
+required = true
+
+[[sections]]
+title = "Proof of concept"
+placeholder = ""
+required = true
+"#,
+    )
+    .unwrap();
+    assert_eq!(client_definition.get_severity_flag("critical"), Some(1));
+    assert_eq!(client_definition.get_severity_flag("Unknown"), None);
+    let critical_content = client_definition.get_finding_content("Drained vault", "Critical");
+    assert!(critical_content.contains("**Severity:** Critical\n\n**Status:** Open\n\n**CVSS:**"));
+    assert_eq!(
+        client_definition.get_incomplete_sections(&critical_content),
+        vec!["Description", "Proof of concept"]
+    );
+    client_definition.informational_sections = vec![];
+    assert_eq!(
+        toml::from_str::<FindingTemplateDefinition>(
+            &toml::to_string_pretty(&client_definition).unwrap()
+        )
+        .unwrap(),
+        client_definition
+    );
 }
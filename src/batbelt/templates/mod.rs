@@ -1,13 +1,20 @@
 pub mod code_overhaul_template;
+pub mod finding_candidates_template;
 pub mod finding_template;
 pub mod notes_template;
+pub mod open_questions_template;
 pub mod package_json_template;
+pub mod poc_template;
+pub mod sidecar_template;
+pub mod threat_model_template;
 
 use super::*;
 use crate::batbelt;
 use crate::batbelt::command_line::{execute_command, execute_command_with_child_process};
 use crate::batbelt::metadata::BatMetadata;
 use crate::batbelt::path::{BatFile, BatFolder};
+use crate::batbelt::templates::code_overhaul_template::CodeOverhaulTemplateDefinition;
+use crate::batbelt::templates::finding_template::FindingTemplateDefinition;
 use crate::batbelt::templates::notes_template::NoteTemplate;
 use crate::batbelt::templates::package_json_template::PackageJsonTemplate;
 use crate::batbelt::BatEnumerator;
@@ -97,6 +104,9 @@ impl TemplateGenerator {
         fs::create_dir("./notes")
             .into_report()
             .change_context(TemplateError)?;
+        // written so the finding and code-overhaul templates can be adapted to the client
+        FindingTemplateDefinition::default().save()?;
+        CodeOverhaulTemplateDefinition::default().save()?;
         Ok(())
     }
 
@@ -150,6 +160,12 @@ impl TemplateGenerator {
             .change_context(TemplateError)?;
         self.create_dir(&auditor_figures, true)?;
 
+        // PoC
+        let auditor_poc = BatFolder::AuditorPoc
+            .get_path(false)
+            .change_context(TemplateError)?;
+        self.create_dir(&auditor_poc, true)?;
+
         // findings
         self.create_findings_folders()?;
 
@@ -0,0 +1,470 @@
+use crate::batbelt::path::BatFile;
+use crate::batbelt::templates::code_overhaul_template::CodeOverhaulSection;
+use crate::batbelt::templates::sidecar_template::SidecarTemplate;
+use crate::batbelt::templates::{TemplateError, TemplateResult};
+use error_stack::{IntoReport, Report, ResultExt};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+const OPEN_QUESTION_PLACEHOLDER: &str = "open question here";
+const OPEN_QUESTIONS_GENERAL_GROUP: &str = "General";
+const OPEN_QUESTIONS_ANSWER_TITLE: &str = "**Answer:**";
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Default,
+    Serialize,
+    Deserialize,
+    strum_macros::Display,
+    strum_macros::EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum OpenQuestionStatus {
+    #[default]
+    Open,
+    /// sent to the client with bat-cli questions export
+    Asked,
+    Answered,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenQuestion {
+    pub id: usize,
+    pub question: String,
+    pub status: OpenQuestionStatus,
+    /// code-overhaul entry point the question is about, empty for general questions
+    pub entry_point: String,
+    pub metadata_id: String,
+    /// name of the source code item
+    pub location: String,
+    pub permalink: String,
+    pub author: String,
+    pub answer: String,
+}
+
+impl OpenQuestion {
+    fn get_group_name(&self) -> &str {
+        if self.entry_point.is_empty() {
+            OPEN_QUESTIONS_GENERAL_GROUP
+        } else {
+            &self.entry_point
+        }
+    }
+
+    fn get_linked_question(&self) -> String {
+        if self.permalink.is_empty() {
+            self.question.clone()
+        } else {
+            format!("[{}]({})", self.question, self.permalink)
+        }
+    }
+
+    /// Line for the Notes section of the code-overhaul file of the entry point
+    pub fn get_code_overhaul_note(&self) -> String {
+        format!(
+            "- Q{}. {}: {}",
+            self.id,
+            self.get_linked_question(),
+            self.answer.lines().collect::<Vec<_>>().join(" ")
+        )
+    }
+}
+
+/// Open questions kept in open_questions.toml, the open_questions.md is generated from it
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenQuestionsTemplate {
+    pub questions: Vec<OpenQuestion>,
+}
+
+impl OpenQuestionsTemplate {
+    /// Returns the id assigned to the question
+    pub fn add_question(&mut self, mut question: OpenQuestion) -> usize {
+        question.id = self
+            .questions
+            .iter()
+            .map(|question| question.id)
+            .max()
+            .unwrap_or_default()
+            + 1;
+        self.questions.push(question);
+        self.questions.last().unwrap().id
+    }
+
+    /// Answered questions of the entry point, as lines of its code-overhaul Notes section
+    pub fn get_code_overhaul_notes(&self, entry_point_name: &str) -> Vec<String> {
+        self.questions
+            .iter()
+            .filter(|question| {
+                question.status == OpenQuestionStatus::Answered
+                    && question.entry_point == entry_point_name
+            })
+            .map(|question| question.get_code_overhaul_note())
+            .collect()
+    }
+
+    /// Client document grouped by entry point, marks the open questions as asked
+    pub fn get_export_content(&mut self, project_name: &str) -> String {
+        let mut group_names: Vec<String> = vec![];
+        for question in &mut self.questions {
+            if question.status == OpenQuestionStatus::Open {
+                question.status = OpenQuestionStatus::Asked;
+            }
+            if !group_names.contains(&question.get_group_name().to_string()) {
+                group_names.push(question.get_group_name().to_string());
+            }
+        }
+        let groups = group_names
+            .iter()
+            .map(|group_name| {
+                let questions = self
+                    .questions
+                    .iter()
+                    .filter(|question| question.get_group_name() == group_name)
+                    .map(|question| {
+                        let location = if question.location.is_empty() {
+                            "".to_string()
+                        } else if question.permalink.is_empty() {
+                            format!("Location: `{}`\n\n", question.location)
+                        } else {
+                            format!(
+                                "Location: [`{}`]({})\n\n",
+                                question.location, question.permalink
+                            )
+                        };
+                        format!(
+                            "### Q{}. {}\n\n{}{}\n\n{}",
+                            question.id,
+                            question.question,
+                            location,
+                            OPEN_QUESTIONS_ANSWER_TITLE,
+                            question.answer
+                        )
+                        .trim_end()
+                        .to_string()
+                    })
+                    .collect::<Vec<_>>();
+                format!("## {}\n\n{}", group_name, questions.join("\n\n"))
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "# {} open questions\n\n\
+            Please write each answer below its {} line, keeping the rest of the document as is.\n\n\
+            {}\n",
+            project_name,
+            OPEN_QUESTIONS_ANSWER_TITLE,
+            groups.join("\n\n")
+        )
+    }
+
+    /// Merges the answers of a document with the export format, returns the ids of the
+    /// questions with a new answer
+    pub fn merge_export_content(&mut self, export_content: &str) -> TemplateResult<Vec<usize>> {
+        let question_title_regex = Regex::new(r#"^### Q(\d+)\. "#).unwrap();
+        let mut answers: Vec<(usize, Vec<&str>)> = vec![];
+        let mut is_answer = false;
+        for line in export_content.lines() {
+            if let Some(captures) = question_title_regex.captures(line) {
+                let id = captures[1]
+                    .parse::<usize>()
+                    .into_report()
+                    .change_context(TemplateError)?;
+                answers.push((id, vec![]));
+                is_answer = false;
+            } else if line.starts_with("## ") || line.starts_with("# ") {
+                is_answer = false;
+            } else if line.trim() == OPEN_QUESTIONS_ANSWER_TITLE {
+                is_answer = true;
+            } else if is_answer {
+                if let Some((_, answer_lines)) = answers.last_mut() {
+                    answer_lines.push(line);
+                }
+            }
+        }
+        let mut answered_ids = vec![];
+        for (id, answer_lines) in answers {
+            let answer = answer_lines.join("\n").trim().to_string();
+            if answer.is_empty() {
+                continue;
+            }
+            let question = self
+                .questions
+                .iter_mut()
+                .find(|question| question.id == id)
+                .ok_or(Report::new(TemplateError).attach_printable(format!(
+                    "Question Q{} not found in open_questions.toml",
+                    id
+                )))?;
+            if question.answer != answer {
+                question.answer = answer;
+                question.status = OpenQuestionStatus::Answered;
+                answered_ids.push(id);
+            }
+        }
+        Ok(answered_ids)
+    }
+}
+
+impl SidecarTemplate for OpenQuestionsTemplate {
+    const SIDECAR_FILE_NAME: &'static str = "open_questions.toml";
+    const GENERATOR_COMMAND: &'static str = "bat-cli questions";
+
+    fn sidecar_file() -> BatFile {
+        BatFile::OpenQuestionsToml
+    }
+
+    fn markdown_file() -> BatFile {
+        BatFile::OpenQuestions
+    }
+
+    fn to_markdown(&self) -> String {
+        let questions = if self.questions.is_empty() {
+            "-".to_string()
+        } else {
+            self.questions
+                .iter()
+                .map(|question| {
+                    let check = if question.status == OpenQuestionStatus::Answered {
+                        "x"
+                    } else {
+                        " "
+                    };
+                    let mut item = format!(
+                        "- [{}] Q{}. {} ({}, {})",
+                        check,
+                        question.id,
+                        question.get_linked_question(),
+                        question.get_group_name(),
+                        question.status
+                    );
+                    if !question.answer.is_empty() {
+                        item = format!(
+                            "{}\n  - {}",
+                            item,
+                            question.answer.lines().collect::<Vec<_>>().join(" ")
+                        );
+                    }
+                    item
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let generated_comment = Self::get_generated_comment();
+        format!(
+            r#"# Open questions
+
+![Alt Text](http://38.media.tumblr.com/1e3486ff57a997da3ffeea759b8eccde/tumblr_nl4tn2cY1m1spm17no1_400.gif)
+
+{generated_comment}
+
+{questions}
+"#
+        )
+    }
+
+    fn new_from_markdown(markdown_content: &str) -> Self {
+        // items generated by get_markdown_content, followed by the answer
+        let generated_item_regex =
+            Regex::new(r#"^- \[[ xX]\] Q\d+\. (.*) \(([^()]*), (open|asked|answered)\)$"#).unwrap();
+        let linked_question_regex = Regex::new(r#"^\[(.*)\]\((.*?)\)$"#).unwrap();
+        let item_regex = Regex::new(r#"^- \[[ xX]\] \[(.*)\]\((.*?)\)(?:,\s*(.*))?$"#).unwrap();
+        // checklist items written by hand, without the permalink
+        let plain_item_regex = Regex::new(r#"^- \[[ xX]\] (.+)$"#).unwrap();
+        let mut open_questions = Self::default();
+        let mut is_generated_item = false;
+        for line in markdown_content.lines() {
+            let line = line.trim_end();
+            if is_generated_item {
+                if let Some(answer) = line.strip_prefix("  - ") {
+                    open_questions.questions.last_mut().unwrap().answer = answer.to_string();
+                    continue;
+                }
+            }
+            is_generated_item = false;
+            let open_question = if let Some(captures) = generated_item_regex.captures(line) {
+                is_generated_item = true;
+                let (question, permalink) = match linked_question_regex.captures(&captures[1]) {
+                    Some(linked_captures) => (
+                        linked_captures[1].to_string(),
+                        linked_captures[2].to_string(),
+                    ),
+                    None => (captures[1].to_string(), "".to_string()),
+                };
+                OpenQuestion {
+                    question,
+                    permalink,
+                    entry_point: if &captures[2] == OPEN_QUESTIONS_GENERAL_GROUP {
+                        "".to_string()
+                    } else {
+                        captures[2].to_string()
+                    },
+                    status: match &captures[3] {
+                        "asked" => OpenQuestionStatus::Asked,
+                        "answered" => OpenQuestionStatus::Answered,
+                        _ => OpenQuestionStatus::Open,
+                    },
+                    ..Default::default()
+                }
+            } else if let Some(captures) = item_regex.captures(line) {
+                if &captures[1] == OPEN_QUESTION_PLACEHOLDER {
+                    continue;
+                }
+                OpenQuestion {
+                    question: match captures.get(3) {
+                        Some(notes) => format!("{} ({})", &captures[1], notes.as_str()),
+                        None => captures[1].to_string(),
+                    },
+                    permalink: captures[2].to_string(),
+                    ..Default::default()
+                }
+            } else if let Some(captures) = plain_item_regex.captures(line) {
+                OpenQuestion {
+                    question: captures[1].trim().to_string(),
+                    ..Default::default()
+                }
+            } else {
+                continue;
+            };
+            if open_question.question == OPEN_QUESTION_PLACEHOLDER {
+                is_generated_item = false;
+                continue;
+            }
+            open_questions.add_question(open_question);
+        }
+        open_questions
+    }
+}
+
+/// Appends the notes missing on the Notes section of a code-overhaul file
+pub fn insert_code_overhaul_notes(co_content: &str, notes: &[String]) -> String {
+    let notes_header = CodeOverhaulSection::Notes.to_markdown_header();
+    let mut lines = co_content.lines().collect::<Vec<_>>();
+    let notes_header_index = match lines.iter().position(|line| line.trim() == notes_header) {
+        Some(index) => index,
+        None => return co_content.to_string(),
+    };
+    let mut section_end_index = lines
+        .iter()
+        .skip(notes_header_index + 1)
+        .position(|line| line.starts_with("# "))
+        .map(|index| index + notes_header_index + 1)
+        .unwrap_or(lines.len());
+    while section_end_index > notes_header_index + 1
+        && lines[section_end_index - 1].trim().is_empty()
+    {
+        section_end_index -= 1;
+    }
+    let missing_notes = notes
+        .iter()
+        .filter(|note| !lines.contains(&note.as_str()))
+        .map(|note| note.as_str())
+        .collect::<Vec<_>>();
+    if missing_notes.is_empty() {
+        return co_content.to_string();
+    }
+    let trailing_new_line = if co_content.ends_with('\n') { "\n" } else { "" };
+    lines.splice(section_end_index..section_end_index, missing_notes);
+    format!("{}{}", lines.join("\n"), trailing_new_line)
+}
+
+#[test]
+fn test_open_questions_export_and_import() {
+    let mut open_questions = OpenQuestionsTemplate::new_from_markdown(
+        "# Open questions\n\n- [ ] [open question here](http://url-to-client-repository-with-corresponding-branch), little note\n- [ ] [is the fee capped?](https://github.com/org/vault/blob/641bdb7/src/fee.rs#L3)\n",
+    );
+    open_questions.add_question(OpenQuestion {
+        question: "can the authority be rotated?".to_string(),
+        entry_point: "deposit".to_string(),
+        location: "Vault".to_string(),
+        ..Default::default()
+    });
+    assert_eq!(open_questions.questions.len(), 2);
+
+    let export_content = open_questions.get_export_content("vault-audit");
+    assert!(open_questions
+        .questions
+        .iter()
+        .all(|question| question.status == OpenQuestionStatus::Asked));
+    assert!(export_content.contains(
+        "## deposit\n\n### Q2. can the authority be rotated?\n\nLocation: `Vault`\n\n**Answer:**"
+    ));
+
+    let answered_content = export_content.replace(
+        "Location: `Vault`\n\n**Answer:**",
+        "Location: `Vault`\n\n**Answer:**\n\nYes, by the admin\nthrough set_authority\n",
+    );
+    assert_eq!(
+        open_questions
+            .merge_export_content(&answered_content)
+            .unwrap(),
+        vec![2]
+    );
+    assert_eq!(
+        open_questions.questions[1].answer,
+        "Yes, by the admin\nthrough set_authority"
+    );
+    assert_eq!(
+        open_questions.questions[0].status,
+        OpenQuestionStatus::Asked
+    );
+    // importing the same answers again changes nothing
+    assert!(open_questions
+        .merge_export_content(&answered_content)
+        .unwrap()
+        .is_empty());
+
+    let notes = open_questions.get_code_overhaul_notes("deposit");
+    assert_eq!(
+        notes,
+        vec!["- Q2. can the authority be rotated?: Yes, by the admin through set_authority"]
+    );
+    let co_content =
+        "# State changes:\n\n- x\n\n# Notes:\n\n- COMPLETE_WITH_NOTES\n\n# Signers:\n\n- y\n";
+    let updated_co_content = insert_code_overhaul_notes(co_content, &notes);
+    assert_eq!(
+        updated_co_content,
+        format!(
+            "# State changes:\n\n- x\n\n# Notes:\n\n- COMPLETE_WITH_NOTES\n{}\n\n# Signers:\n\n- y\n",
+            notes[0]
+        )
+    );
+    assert_eq!(
+        insert_code_overhaul_notes(&updated_co_content, &notes),
+        updated_co_content
+    );
+}
+
+#[test]
+fn test_open_questions_plain_item_round_trip() {
+    let mut open_questions = OpenQuestionsTemplate::new_from_markdown(
+        "# Open questions\n\n- [ ] [open question here](http://url-to-client-repository-with-corresponding-branch), little note\n- [ ] is the oracle price checked for staleness?\n",
+    );
+    assert_eq!(open_questions.questions.len(), 1);
+    assert_eq!(
+        open_questions.questions[0].question,
+        "is the oracle price checked for staleness?"
+    );
+    assert!(open_questions.questions[0].permalink.is_empty());
+
+    open_questions.add_question(OpenQuestion {
+        question: "is the fee capped?".to_string(),
+        entry_point: "deposit".to_string(),
+        permalink: "https://github.com/org/vault/blob/641bdb7/src/fee.rs#L3".to_string(),
+        status: OpenQuestionStatus::Answered,
+        answer: "Yes, at 10%".to_string(),
+        ..Default::default()
+    });
+    // the generated markdown imports back to the same questions
+    let generated_markdown = open_questions.to_markdown();
+    assert!(generated_markdown
+        .contains("- [ ] Q1. is the oracle price checked for staleness? (General, open)"));
+    assert_eq!(
+        OpenQuestionsTemplate::new_from_markdown(&generated_markdown),
+        open_questions
+    );
+}
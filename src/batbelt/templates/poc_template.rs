@@ -0,0 +1,492 @@
+use crate::batbelt::metadata::context_accounts_metadata::ContextAccountsMetadata;
+use crate::batbelt::parser::context_accounts_parser::CAAccountParser;
+use crate::batbelt::parser::entrypoint_parser::EntrypointParser;
+use crate::batbelt::parser::function_parser::FunctionParser;
+use crate::batbelt::parser::solana_account_parser::SolanaAccountType;
+use crate::batbelt::templates::{TemplateError, TemplateResult};
+use crate::batbelt::BatEnumerator;
+use error_stack::ResultExt;
+use inflector::Inflector;
+
+/// Variables declared by the Rust template, a signer with one of these names is renamed
+const POC_RUST_BINDINGS: [&str; 8] = [
+    "program_test",
+    "banks_client",
+    "payer",
+    "recent_blockhash",
+    "accounts",
+    "instruction_data",
+    "instruction",
+    "transaction",
+];
+const POC_TYPESCRIPT_BINDINGS: [&str; 2] = ["provider", "program"];
+
+#[derive(Debug, Clone, Copy, PartialEq, strum_macros::Display, strum_macros::EnumIter, Default)]
+pub enum PocLanguage {
+    /// solana-program-test with the Anchor generated accounts and instruction structs
+    #[default]
+    Rust,
+    /// Anchor mocha test with the generated program client
+    TypeScript,
+}
+
+impl BatEnumerator for PocLanguage {}
+
+impl PocLanguage {
+    pub fn get_file_extension(&self) -> &'static str {
+        match self {
+            PocLanguage::Rust => "rs",
+            PocLanguage::TypeScript => "ts",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PocAccount {
+    pub name: String,
+    pub is_signer: bool,
+    /// type and constraints, left as a comment for the auditor
+    pub description: String,
+}
+
+impl PocAccount {
+    pub fn new_from_context_account(ca_account: &CAAccountParser) -> Self {
+        let account_type = if ca_account.account_struct_name == ca_account.account_wrapper_name {
+            format!(
+                "{}<{}>",
+                ca_account.account_wrapper_name, ca_account.lifetime_name
+            )
+        } else {
+            format!(
+                "{}<{}, {}>",
+                ca_account.account_wrapper_name,
+                ca_account.lifetime_name,
+                ca_account.account_struct_name
+            )
+        };
+        let mut details = vec![account_type];
+        for (flag, flag_name) in [
+            (ca_account.is_init, "init"),
+            (ca_account.is_mut, "mut"),
+            (ca_account.is_close, "close"),
+        ] {
+            if flag {
+                details.push(flag_name.to_string());
+            }
+        }
+        if !ca_account.seeds.is_empty() {
+            details.push(format!("seeds = [{}]", ca_account.seeds.join(", ")));
+        }
+        Self {
+            name: ca_account.account_name.clone(),
+            is_signer: ca_account.solana_account_type == SolanaAccountType::Signer,
+            description: details.join(", "),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PocParameter {
+    pub name: String,
+    pub parameter_type: String,
+}
+
+/// Test stub to start the proof of concept of a finding, with the accounts, signers and
+/// parameters of the affected entry point
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PocTemplate {
+    pub finding_name: String,
+    pub program_name: String,
+    pub entry_point_name: String,
+    pub context_accounts_name: String,
+    pub accounts: Vec<PocAccount>,
+    pub parameters: Vec<PocParameter>,
+}
+
+impl PocTemplate {
+    /// snake_case identifier of the finding, without the severity prefix of the to-review file
+    /// name, like missing_owner_check for 1-missing-owner-check
+    pub fn get_poc_name(finding_name: &str) -> String {
+        let finding_name = match finding_name.split_once('-') {
+            Some((severity_flag, name))
+                if !severity_flag.is_empty()
+                    && severity_flag
+                        .chars()
+                        .all(|character| character.is_ascii_digit()) =>
+            {
+                name
+            }
+            _ => finding_name,
+        };
+        finding_name
+            .chars()
+            .map(|character| {
+                if character.is_ascii_alphanumeric() {
+                    character
+                } else {
+                    ' '
+                }
+            })
+            .collect::<String>()
+            .to_snake_case()
+    }
+
+    pub fn new_from_entry_point(
+        finding_name: &str,
+        program_name: &str,
+        entry_point_name: &str,
+    ) -> TemplateResult<Self> {
+        let entrypoint_parser =
+            EntrypointParser::new_from_name(entry_point_name).change_context(TemplateError)?;
+        let context_accounts_metadata =
+            ContextAccountsMetadata::find_context_accounts_metadata_by_struct_metadata_id(
+                entrypoint_parser.context_accounts.metadata_id.clone(),
+            )
+            .change_context(TemplateError)?;
+        // same parameters as the code overhaul file, the entry point ones if there is no handler
+        let parameters_function = entrypoint_parser
+            .handler
+            .clone()
+            .unwrap_or(entrypoint_parser.entry_point_function.clone());
+        let parameters = FunctionParser::new_from_metadata(parameters_function)
+            .change_context(TemplateError)?
+            .parameters
+            .into_iter()
+            .filter(|parameter| !parameter.parameter_type.contains("Context<"))
+            .map(|parameter| PocParameter {
+                name: parameter.parameter_name,
+                parameter_type: parameter.parameter_type.trim_end_matches(',').to_string(),
+            })
+            .collect();
+        Ok(Self {
+            finding_name: Self::get_poc_name(finding_name),
+            program_name: program_name.to_string(),
+            entry_point_name: entrypoint_parser.name,
+            context_accounts_name: entrypoint_parser.context_accounts.name,
+            accounts: context_accounts_metadata
+                .context_accounts_info
+                .iter()
+                .map(PocAccount::new_from_context_account)
+                .collect(),
+            parameters,
+        })
+    }
+
+    pub fn get_content(&self, poc_language: PocLanguage) -> String {
+        match poc_language {
+            PocLanguage::Rust => self.get_rust_content(),
+            PocLanguage::TypeScript => self.get_typescript_content(),
+        }
+    }
+
+    /// Name of the keypair variable of a signer, renamed if the template already uses it
+    fn get_signer_binding(signer_name: &str, poc_language: PocLanguage) -> String {
+        match poc_language {
+            PocLanguage::Rust if POC_RUST_BINDINGS.contains(&signer_name) => {
+                format!("{}_signer", signer_name)
+            }
+            PocLanguage::Rust => signer_name.to_string(),
+            PocLanguage::TypeScript => {
+                let signer_name = signer_name.to_camel_case();
+                if POC_TYPESCRIPT_BINDINGS.contains(&signer_name.as_str()) {
+                    format!("{}Signer", signer_name)
+                } else {
+                    signer_name
+                }
+            }
+        }
+    }
+
+    fn get_signers(&self) -> Vec<&PocAccount> {
+        self.accounts
+            .iter()
+            .filter(|account| account.is_signer)
+            .collect()
+    }
+
+    fn get_rust_content(&self) -> String {
+        let program_name = self.program_name.to_snake_case();
+        let signers = self.get_signers();
+        let signer_keypairs = signers
+            .iter()
+            .map(|signer| {
+                format!(
+                    "    let {} = Keypair::new();",
+                    Self::get_signer_binding(&signer.name, PocLanguage::Rust)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|account| {
+                if account.is_signer {
+                    format!(
+                        "        {}: {}.pubkey(),",
+                        account.name,
+                        Self::get_signer_binding(&account.name, PocLanguage::Rust)
+                    )
+                } else {
+                    format!(
+                        "        {}: todo!(), // {}",
+                        account.name, account.description
+                    )
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|parameter| {
+                format!(
+                    "        {}: todo!(), // {}",
+                    parameter.name, parameter.parameter_type
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let transaction_signers = ["&payer".to_string()]
+            .into_iter()
+            .chain(signers.iter().map(|signer| {
+                format!(
+                    "&{}",
+                    Self::get_signer_binding(&signer.name, PocLanguage::Rust)
+                )
+            }))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            r#"//! Proof of concept of {finding_name}, generated by bat-cli finding poc
+use anchor_lang::{{InstructionData, ToAccountMetas}};
+use solana_program_test::{{tokio, ProgramTest}};
+use solana_sdk::{{
+    instruction::Instruction, signature::Keypair, signer::Signer, transaction::Transaction,
+}};
+
+#[tokio::test]
+async fn poc_{finding_name}() {{
+    let mut program_test = ProgramTest::new("{program_name}", {program_name}::id(), None);
+    // signers
+{signer_keypairs}
+    // TODO: add the accounts state needed by the finding with program_test.add_account
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let accounts = {program_name}::accounts::{context_accounts_name} {{
+{accounts}
+    }};
+    let instruction_data = {program_name}::instruction::{instruction_name} {{
+{parameters}
+    }};
+    let instruction = Instruction {{
+        program_id: {program_name}::id(),
+        accounts: accounts.to_account_metas(None),
+        data: instruction_data.data(),
+    }};
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[{transaction_signers}], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // TODO: assert the state that proves the finding
+}}
+"#,
+            finding_name = self.finding_name,
+            context_accounts_name = self.context_accounts_name,
+            instruction_name = self.entry_point_name.to_pascal_case(),
+        )
+    }
+
+    fn get_typescript_content(&self) -> String {
+        let program_type_name = self.program_name.to_pascal_case();
+        let signers = self.get_signers();
+        let signer_keypairs = signers
+            .iter()
+            .map(|signer| {
+                format!(
+                    "  const {} = anchor.web3.Keypair.generate();",
+                    Self::get_signer_binding(&signer.name, PocLanguage::TypeScript)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|parameter| {
+                format!(
+                    "        undefined, // {}: {}",
+                    parameter.name, parameter.parameter_type
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|account| {
+                let account_name = account.name.to_camel_case();
+                if account.is_signer {
+                    format!(
+                        "        {}: {}.publicKey,",
+                        account_name,
+                        Self::get_signer_binding(&account.name, PocLanguage::TypeScript)
+                    )
+                } else {
+                    format!(
+                        "        {}: undefined, // {}",
+                        account_name, account.description
+                    )
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let transaction_signers = signers
+            .iter()
+            .map(|signer| Self::get_signer_binding(&signer.name, PocLanguage::TypeScript))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            r#"// Proof of concept of {finding_name}, generated by bat-cli finding poc
+import * as anchor from "@coral-xyz/anchor";
+import {{ Program }} from "@coral-xyz/anchor";
+import {{ {program_type_name} }} from "../target/types/{program_name}";
+
+describe("{finding_title}", () => {{
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.{program_type_name} as Program<{program_type_name}>;
+  // signers
+{signer_keypairs}
+
+  it("poc {finding_name}", async () => {{
+    // TODO: create the accounts state needed by the finding
+    await program.methods
+      .{method_name}(
+{parameters}
+      )
+      .accounts({{
+{accounts}
+      }})
+      .signers([{transaction_signers}])
+      .rpc();
+
+    // TODO: assert the state that proves the finding
+  }});
+}});
+"#,
+            finding_name = self.finding_name,
+            finding_title = self.finding_name.to_sentence_case(),
+            program_name = self.program_name.to_snake_case(),
+            method_name = self.entry_point_name.to_camel_case(),
+        )
+    }
+}
+
+#[test]
+fn test_poc_content() {
+    let poc_template = PocTemplate {
+        finding_name: "missing_owner_check".to_string(),
+        program_name: "vault".to_string(),
+        entry_point_name: "withdraw".to_string(),
+        context_accounts_name: "Withdraw".to_string(),
+        accounts: vec![
+            PocAccount {
+                name: "vault_owner".to_string(),
+                is_signer: true,
+                description: "Signer<'info>".to_string(),
+            },
+            PocAccount {
+                name: "vault".to_string(),
+                is_signer: false,
+                description: "Account<'info, Vault>, mut".to_string(),
+            },
+        ],
+        parameters: vec![PocParameter {
+            name: "amount".to_string(),
+            parameter_type: "u64".to_string(),
+        }],
+    };
+    let rust_content = poc_template.get_content(PocLanguage::Rust);
+    assert!(rust_content.contains("    let vault_owner = Keypair::new();\n"));
+    assert!(rust_content.contains(
+        "    let accounts = vault::accounts::Withdraw {\n        vault_owner: vault_owner.pubkey(),\n        vault: todo!(), // Account<'info, Vault>, mut\n    };"
+    ));
+    assert!(
+        rust_content.contains("vault::instruction::Withdraw {\n        amount: todo!(), // u64\n")
+    );
+    assert!(rust_content.contains("transaction.sign(&[&payer, &vault_owner], recent_blockhash);"));
+    let typescript_content = poc_template.get_content(PocLanguage::TypeScript);
+    assert!(typescript_content.contains("      .withdraw(\n        undefined, // amount: u64\n"));
+    assert!(typescript_content.contains("        vaultOwner: vaultOwner.publicKey,\n"));
+    assert!(typescript_content.contains("      .signers([vaultOwner])"));
+}
+
+#[test]
+fn test_poc_signer_named_like_the_template_variables() {
+    let poc_template = PocTemplate {
+        finding_name: "payer_not_checked".to_string(),
+        program_name: "vault".to_string(),
+        entry_point_name: "initialize".to_string(),
+        context_accounts_name: "Initialize".to_string(),
+        accounts: vec![
+            PocAccount {
+                name: "payer".to_string(),
+                is_signer: true,
+                description: "Signer<'info>, mut".to_string(),
+            },
+            PocAccount {
+                name: "program".to_string(),
+                is_signer: true,
+                description: "Signer<'info>".to_string(),
+            },
+        ],
+        parameters: vec![],
+    };
+    let rust_content = poc_template.get_content(PocLanguage::Rust);
+    // the payer of the banks client is not shadowed, nor passed twice
+    assert!(rust_content.contains(
+        "    let payer_signer = Keypair::new();
+"
+    ));
+    assert!(!rust_content.contains("let payer = Keypair::new();"));
+    assert!(rust_content.contains(
+        "        payer: payer_signer.pubkey(),
+"
+    ));
+    assert!(rust_content
+        .contains("transaction.sign(&[&payer, &payer_signer, &program], recent_blockhash);"));
+    let typescript_content = poc_template.get_content(PocLanguage::TypeScript);
+    assert!(typescript_content.contains("  const programSigner = anchor.web3.Keypair.generate();"));
+    assert!(typescript_content.contains(
+        "        program: programSigner.publicKey,
+"
+    ));
+    assert!(typescript_content.contains("      .signers([payer, programSigner])"));
+}
+
+#[test]
+fn test_poc_name() {
+    assert_eq!(
+        PocTemplate::get_poc_name("1-missing_owner_check"),
+        "missing_owner_check"
+    );
+    assert_eq!(
+        PocTemplate::get_poc_name("12-Missing owner-check (vault)"),
+        "missing_owner_check_vault"
+    );
+    assert_eq!(
+        PocTemplate::get_poc_name("missing_owner_check"),
+        "missing_owner_check"
+    );
+    let poc_template = PocTemplate {
+        finding_name: PocTemplate::get_poc_name("2-overflow-on-deposit"),
+        program_name: "vault".to_string(),
+        entry_point_name: "deposit".to_string(),
+        context_accounts_name: "Deposit".to_string(),
+        ..Default::default()
+    };
+    assert!(poc_template
+        .get_content(PocLanguage::Rust)
+        .contains("async fn poc_overflow_on_deposit() {"));
+}
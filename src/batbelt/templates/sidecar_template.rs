@@ -0,0 +1,81 @@
+use crate::batbelt::path::BatFile;
+use crate::batbelt::templates::{TemplateError, TemplateResult};
+use error_stack::{IntoReport, ResultExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Notes kept in a toml sidecar, the source of truth, with a markdown file generated from it.
+/// The markdown is imported the first time, so the notes written by hand are not lost
+pub trait SidecarTemplate: Serialize + DeserializeOwned + Default {
+    /// file name of the sidecar, for the generated comment
+    const SIDECAR_FILE_NAME: &'static str;
+    /// bat-cli command that manages the sidecar
+    const GENERATOR_COMMAND: &'static str;
+
+    fn sidecar_file() -> BatFile;
+
+    fn markdown_file() -> BatFile;
+
+    fn to_markdown(&self) -> String;
+
+    /// Parses the items of the markdown, the generated one or the notes template filled by hand
+    fn new_from_markdown(markdown_content: &str) -> Self;
+
+    fn get_generated_comment() -> String {
+        format!(
+            "<!-- generated from {} by {}, edit that file instead -->",
+            Self::SIDECAR_FILE_NAME,
+            Self::GENERATOR_COMMAND
+        )
+    }
+
+    /// None if the sidecar was never written
+    fn read_sidecar() -> TemplateResult<Option<Self>> {
+        let sidecar_file = Self::sidecar_file();
+        if !sidecar_file.file_exists().change_context(TemplateError)? {
+            return Ok(None);
+        }
+        let sidecar_content = sidecar_file
+            .read_content(false)
+            .change_context(TemplateError)?;
+        let template = toml::from_str(&sidecar_content)
+            .into_report()
+            .change_context(TemplateError)
+            .attach_printable(format!("Error parsing {}", Self::SIDECAR_FILE_NAME))?;
+        Ok(Some(template))
+    }
+
+    /// The markdown written by hand before the sidecar existed, None if it doesn't exist
+    fn read_markdown() -> TemplateResult<Option<Self>> {
+        let markdown_file = Self::markdown_file();
+        if !markdown_file.file_exists().change_context(TemplateError)? {
+            return Ok(None);
+        }
+        let markdown_content = markdown_file
+            .read_content(false)
+            .change_context(TemplateError)?;
+        Ok(Some(Self::new_from_markdown(&markdown_content)))
+    }
+
+    /// The sidecar, or the imported markdown if the sidecar doesn't exist yet
+    fn read() -> TemplateResult<Self> {
+        match Self::read_sidecar()? {
+            Some(template) => Ok(template),
+            None => Ok(Self::read_markdown()?.unwrap_or_default()),
+        }
+    }
+
+    /// Writes the sidecar and the markdown generated from it
+    fn save(&self) -> TemplateResult<()> {
+        let sidecar_content = toml::to_string_pretty(self)
+            .into_report()
+            .change_context(TemplateError)?;
+        Self::sidecar_file()
+            .write_content(false, &sidecar_content)
+            .change_context(TemplateError)?;
+        Self::markdown_file()
+            .write_content(false, &self.to_markdown())
+            .change_context(TemplateError)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,553 @@
+use crate::batbelt::metadata::structs_source_code_metadata::StructMetadataType;
+use crate::batbelt::metadata::BatMetadata;
+use crate::batbelt::parser::solana_account_parser::SolanaAccountType;
+use crate::batbelt::path::BatFile;
+use crate::batbelt::templates::sidecar_template::SidecarTemplate;
+use serde::{Deserialize, Serialize};
+
+/// Entry point of the scenarios written by hand outside of an entry point header
+pub const THREAT_MODEL_OTHER_SCENARIOS: &str = "Other scenarios";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreatModelAsset {
+    pub name: String,
+    #[serde(default)]
+    pub metadata_id: String,
+    #[serde(default)]
+    pub notes: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreatModelActor {
+    pub name: String,
+    /// entry points where the actor signs
+    #[serde(default)]
+    pub entry_points: Vec<String>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreatModelScenario {
+    pub entry_point: String,
+    pub asset: String,
+    /// initializes, modifies or closes
+    pub action: String,
+    pub description: String,
+    #[serde(default)]
+    pub reviewed: bool,
+    #[serde(default)]
+    pub notes: String,
+}
+
+impl ThreatModelScenario {
+    fn has_same_key(&self, other: &Self) -> bool {
+        self.entry_point == other.entry_point
+            && self.asset == other.asset
+            && self.action == other.action
+    }
+}
+
+/// Threat model kept in threat_modeling.toml, the threat_modeling.md is generated from it.
+/// Assets come from the Solana account structs, actors from the signers of the context
+/// accounts and scenarios from the entry points that mutate each asset
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThreatModelTemplate {
+    pub assets: Vec<ThreatModelAsset>,
+    /// assets that are not accounts, only added by the auditor
+    pub other_assets: Vec<ThreatModelAsset>,
+    pub actors: Vec<ThreatModelActor>,
+    pub scenarios: Vec<ThreatModelScenario>,
+}
+
+impl ThreatModelTemplate {
+    pub fn new_from_metadata(bat_metadata: &BatMetadata) -> Self {
+        let mut threat_model = Self::default();
+        for struct_metadata in &bat_metadata.source_code.structs_source_code {
+            if struct_metadata.struct_type == StructMetadataType::SolanaAccount
+                && !threat_model
+                    .assets
+                    .iter()
+                    .any(|asset| asset.name == struct_metadata.name)
+            {
+                threat_model.assets.push(ThreatModelAsset {
+                    name: struct_metadata.name.clone(),
+                    metadata_id: struct_metadata.metadata_id.clone(),
+                    notes: "".to_string(),
+                });
+            }
+        }
+        for entry_point in &bat_metadata.entry_points {
+            let context_accounts_info = match bat_metadata
+                .get_context_accounts_metadata_by_struct_source_code_metadata_id(
+                    entry_point.context_accounts_id.clone(),
+                ) {
+                Ok(ca_metadata) => ca_metadata.context_accounts_info,
+                Err(_) => continue,
+            };
+            let signer_names = context_accounts_info
+                .iter()
+                .filter(|ca_info| ca_info.solana_account_type == SolanaAccountType::Signer)
+                .map(|ca_info| ca_info.account_name.clone())
+                .collect::<Vec<_>>();
+            for signer_name in &signer_names {
+                match threat_model
+                    .actors
+                    .iter_mut()
+                    .find(|actor| &actor.name == signer_name)
+                {
+                    Some(actor) => actor.entry_points.push(entry_point.name.clone()),
+                    None => threat_model.actors.push(ThreatModelActor {
+                        name: signer_name.clone(),
+                        entry_points: vec![entry_point.name.clone()],
+                        notes: "".to_string(),
+                    }),
+                }
+            }
+            let signers = if signer_names.is_empty() {
+                "anyone".to_string()
+            } else {
+                signer_names.join(", ")
+            };
+            for ca_info in &context_accounts_info {
+                if !threat_model
+                    .assets
+                    .iter()
+                    .any(|asset| asset.name == ca_info.account_struct_name)
+                {
+                    continue;
+                }
+                let (action, question) = if ca_info.is_init {
+                    (
+                        "initializes",
+                        "can it be initialized twice, or with data controlled by an attacker?",
+                    )
+                } else if ca_info.is_close {
+                    (
+                        "closes",
+                        "can it be closed by someone else than its owner, or used after being closed?",
+                    )
+                } else if ca_info.is_mut {
+                    (
+                        "modifies",
+                        "can an account of someone else be passed, or be left in an inconsistent state?",
+                    )
+                } else {
+                    continue;
+                };
+                let scenario = ThreatModelScenario {
+                    entry_point: entry_point.name.clone(),
+                    asset: ca_info.account_struct_name.clone(),
+                    action: action.to_string(),
+                    description: format!(
+                        "{} {} {} ({}): {}",
+                        signers,
+                        action,
+                        ca_info.account_struct_name,
+                        ca_info.account_name,
+                        question
+                    ),
+                    reviewed: false,
+                    notes: "".to_string(),
+                };
+                if !threat_model
+                    .scenarios
+                    .iter()
+                    .any(|other| other.has_same_key(&scenario))
+                {
+                    threat_model.scenarios.push(scenario);
+                }
+            }
+        }
+        threat_model
+    }
+
+    /// Keeps the previous model with its notes, reviews and manual additions, refreshing the
+    /// metadata ids and adding what the metadata has new. Nothing is deleted
+    pub fn merge_previous(self, previous: Self) -> Self {
+        let mut merged = previous;
+        for asset in self.assets {
+            match merged
+                .assets
+                .iter_mut()
+                .find(|merged_asset| merged_asset.name == asset.name)
+            {
+                Some(merged_asset) => merged_asset.metadata_id = asset.metadata_id,
+                None => merged.assets.push(asset),
+            }
+        }
+        for actor in self.actors {
+            match merged
+                .actors
+                .iter_mut()
+                .find(|merged_actor| merged_actor.name == actor.name)
+            {
+                Some(merged_actor) => merged_actor.entry_points = actor.entry_points,
+                None => merged.actors.push(actor),
+            }
+        }
+        for scenario in self.scenarios {
+            if !merged
+                .scenarios
+                .iter()
+                .any(|merged_scenario| merged_scenario.has_same_key(&scenario))
+            {
+                merged.scenarios.push(scenario);
+            }
+        }
+        merged
+    }
+}
+
+impl SidecarTemplate for ThreatModelTemplate {
+    const SIDECAR_FILE_NAME: &'static str = "threat_modeling.toml";
+    const GENERATOR_COMMAND: &'static str = "bat-cli threat-model";
+
+    fn sidecar_file() -> BatFile {
+        BatFile::ThreatModelingToml
+    }
+
+    fn markdown_file() -> BatFile {
+        BatFile::ThreatModeling
+    }
+
+    fn to_markdown(&self) -> String {
+        let with_notes = |name: &str, notes: &str| {
+            if notes.is_empty() {
+                format!("- `{}`", name)
+            } else {
+                format!("- `{}`: {}", name, notes)
+            }
+        };
+        let or_empty = |lines: Vec<String>| {
+            if lines.is_empty() {
+                "-".to_string()
+            } else {
+                lines.join("\n")
+            }
+        };
+        let accounts = or_empty(
+            self.assets
+                .iter()
+                .map(|asset| with_notes(&asset.name, &asset.notes))
+                .collect(),
+        );
+        let others = or_empty(
+            self.other_assets
+                .iter()
+                .map(|asset| with_notes(&asset.name, &asset.notes))
+                .collect(),
+        );
+        let actors = or_empty(
+            self.actors
+                .iter()
+                .map(|actor| {
+                    with_notes(
+                        &actor.name,
+                        format!("signs {}. {}", actor.entry_points.join(", "), actor.notes)
+                            .trim_end_matches(". "),
+                    )
+                })
+                .collect(),
+        );
+        let mut entry_point_names: Vec<String> = vec![];
+        for scenario in &self.scenarios {
+            if !entry_point_names.contains(&scenario.entry_point) {
+                entry_point_names.push(scenario.entry_point.clone());
+            }
+        }
+        let scenarios = if entry_point_names.is_empty() {
+            "-".to_string()
+        } else {
+            entry_point_names
+                .iter()
+                .map(|entry_point_name| {
+                    let scenario_lines = self
+                        .scenarios
+                        .iter()
+                        .filter(|scenario| &scenario.entry_point == entry_point_name)
+                        .map(|scenario| {
+                            let check = if scenario.reviewed { "x" } else { " " };
+                            let notes = if scenario.notes.is_empty() {
+                                "".to_string()
+                            } else {
+                                format!("\n  - {}", scenario.notes)
+                            };
+                            format!("- [{}] {}{}", check, scenario.description, notes)
+                        })
+                        .collect::<Vec<_>>();
+                    format!("### {}\n\n{}", entry_point_name, scenario_lines.join("\n"))
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        };
+        let generated_comment = Self::get_generated_comment();
+        format!(
+            r#"# Threat modeling
+
+![Alt Text](https://media.tenor.com/26GU1Sq64AcAAAAC/hacker.gif)
+
+{generated_comment}
+
+## Assets
+
+### Accounts
+
+{accounts}
+
+### Others
+
+{others}
+
+## Actors
+
+{actors}
+
+## Scenarios
+
+{scenarios}
+"#
+        )
+    }
+
+    /// Scenarios outside of an entry point header go to THREAT_MODEL_OTHER_SCENARIOS
+    fn new_from_markdown(markdown_content: &str) -> Self {
+        let mut threat_model = Self::default();
+        let mut current_section = "";
+        let mut current_entry_point = THREAT_MODEL_OTHER_SCENARIOS.to_string();
+        let split_notes = |item: &str| match item.split_once(": ") {
+            Some((name, notes)) => (
+                name.trim().trim_matches('`').to_string(),
+                notes.trim().to_string(),
+            ),
+            None => (item.trim().trim_matches('`').to_string(), "".to_string()),
+        };
+        for line in markdown_content.lines() {
+            if let Some(header) = line.strip_prefix("## ") {
+                current_section = header.trim();
+                continue;
+            }
+            if let Some(header) = line.strip_prefix("### ") {
+                if current_section == "Scenarios" {
+                    current_entry_point = header.trim().to_string();
+                } else {
+                    current_section = header.trim();
+                }
+                continue;
+            }
+            if let Some(notes) = line.strip_prefix("  - ") {
+                if current_section == "Scenarios" {
+                    if let Some(scenario) = threat_model.scenarios.last_mut() {
+                        scenario.notes = [scenario.notes.as_str(), notes.trim()]
+                            .join(" ")
+                            .trim()
+                            .to_string();
+                    }
+                }
+                continue;
+            }
+            let item = match line.strip_prefix("- ") {
+                Some(item) if !item.trim().is_empty() => item.trim(),
+                _ => continue,
+            };
+            match current_section {
+                "Accounts" | "Others" => {
+                    let (name, notes) = split_notes(item);
+                    let asset = ThreatModelAsset {
+                        name,
+                        metadata_id: "".to_string(),
+                        notes,
+                    };
+                    if current_section == "Accounts" {
+                        threat_model.assets.push(asset);
+                    } else {
+                        threat_model.other_assets.push(asset);
+                    }
+                }
+                "Actors" => {
+                    let (name, notes) = split_notes(item);
+                    let (entry_points, notes) = match notes.strip_prefix("signs ") {
+                        Some(signed) => {
+                            let (signed, notes) = signed.split_once(". ").unwrap_or((signed, ""));
+                            (
+                                signed
+                                    .split(", ")
+                                    .map(|entry_point| entry_point.trim().to_string())
+                                    .collect(),
+                                notes.to_string(),
+                            )
+                        }
+                        None => (vec![], notes),
+                    };
+                    threat_model.actors.push(ThreatModelActor {
+                        name,
+                        entry_points,
+                        notes,
+                    });
+                }
+                "Scenarios" => {
+                    let (reviewed, description) = if let Some(description) =
+                        item.strip_prefix("[x] ").or(item.strip_prefix("[X] "))
+                    {
+                        (true, description)
+                    } else {
+                        (false, item.strip_prefix("[ ] ").unwrap_or(item))
+                    };
+                    // the generated descriptions read "signers action Asset (account): question"
+                    let words = description.split_whitespace().collect::<Vec<_>>();
+                    let (action, asset) = words
+                        .iter()
+                        .position(|word| ["initializes", "modifies", "closes"].contains(word))
+                        .and_then(|position| {
+                            words
+                                .get(position + 1)
+                                .map(|asset| (words[position].to_string(), asset.to_string()))
+                        })
+                        .unwrap_or_default();
+                    threat_model.scenarios.push(ThreatModelScenario {
+                        entry_point: current_entry_point.clone(),
+                        asset,
+                        action,
+                        description: description.trim().to_string(),
+                        reviewed,
+                        notes: "".to_string(),
+                    });
+                }
+                _ => {}
+            }
+        }
+        threat_model
+    }
+}
+
+#[test]
+fn test_threat_model_merge_keeps_auditor_edits() {
+    let new_scenario = |entry_point: &str, action: &str| ThreatModelScenario {
+        entry_point: entry_point.to_string(),
+        asset: "Vault".to_string(),
+        action: action.to_string(),
+        description: format!("authority {} Vault (vault): ?", action),
+        reviewed: false,
+        notes: "".to_string(),
+    };
+    let mut previous = ThreatModelTemplate {
+        assets: vec![ThreatModelAsset {
+            name: "Vault".to_string(),
+            metadata_id: "old".to_string(),
+            notes: "holds the deposits".to_string(),
+        }],
+        other_assets: vec![],
+        actors: vec![],
+        scenarios: vec![new_scenario("deposit", "modifies")],
+    };
+    previous.scenarios[0].reviewed = true;
+    let generated = ThreatModelTemplate {
+        assets: vec![ThreatModelAsset {
+            name: "Vault".to_string(),
+            metadata_id: "new".to_string(),
+            notes: "".to_string(),
+        }],
+        other_assets: vec![],
+        actors: vec![ThreatModelActor {
+            name: "authority".to_string(),
+            entry_points: vec!["deposit".to_string(), "withdraw".to_string()],
+            notes: "".to_string(),
+        }],
+        scenarios: vec![
+            new_scenario("deposit", "modifies"),
+            new_scenario("withdraw", "closes"),
+        ],
+    };
+    let merged = generated.merge_previous(previous);
+    assert_eq!(merged.assets[0].metadata_id, "new");
+    assert_eq!(merged.assets[0].notes, "holds the deposits");
+    assert_eq!(merged.scenarios.len(), 2);
+    assert!(merged.scenarios[0].reviewed);
+
+    let sidecar_content = toml::to_string_pretty(&merged).unwrap();
+    assert_eq!(
+        toml::from_str::<ThreatModelTemplate>(&sidecar_content).unwrap(),
+        merged
+    );
+    let markdown_content = merged.to_markdown();
+    assert!(markdown_content.contains("- `Vault`: holds the deposits"));
+    assert!(markdown_content.contains("- `authority`: signs deposit, withdraw"));
+    assert!(markdown_content.contains("### withdraw\n\n- [ ] authority closes Vault (vault): ?"));
+    assert!(markdown_content.contains("### Others\n\n-\n"));
+}
+
+#[test]
+fn test_threat_model_from_hand_written_markdown() {
+    let markdown_content = r#"# Threat modeling
+
+![Alt Text](https://media.tenor.com/26GU1Sq64AcAAAAC/hacker.gif)
+
+## Assets
+
+### Accounts
+
+- Vault: holds the deposits
+
+### Others
+
+- `oracle price`
+
+## Actors
+
+- `authority`: signs deposit, withdraw. can pause the pool
+
+## Scenarios
+
+- [ ] flash loan on the oracle price
+  - checked on the swap
+
+### withdraw
+
+- [x] authority closes Vault (vault): can it be closed by someone else than its owner?
+"#;
+    let threat_model = ThreatModelTemplate::new_from_markdown(markdown_content);
+    assert_eq!(threat_model.assets[0].name, "Vault");
+    assert_eq!(threat_model.assets[0].notes, "holds the deposits");
+    assert_eq!(threat_model.other_assets[0].name, "oracle price");
+    assert_eq!(
+        threat_model.actors[0].entry_points,
+        vec!["deposit".to_string(), "withdraw".to_string()]
+    );
+    assert_eq!(threat_model.actors[0].notes, "can pause the pool");
+    assert_eq!(
+        threat_model.scenarios[0].entry_point,
+        THREAT_MODEL_OTHER_SCENARIOS
+    );
+    assert_eq!(threat_model.scenarios[0].notes, "checked on the swap");
+    assert!(threat_model.scenarios[1].reviewed);
+    assert_eq!(threat_model.scenarios[1].action, "closes");
+    assert_eq!(threat_model.scenarios[1].asset, "Vault");
+
+    // the generated scenario with the same key doesn't duplicate the imported one
+    let generated = ThreatModelTemplate {
+        scenarios: vec![ThreatModelScenario {
+            entry_point: "withdraw".to_string(),
+            asset: "Vault".to_string(),
+            action: "closes".to_string(),
+            description: "authority closes Vault (vault): ?".to_string(),
+            reviewed: false,
+            notes: "".to_string(),
+        }],
+        ..Default::default()
+    };
+    let merged = generated.merge_previous(threat_model.clone());
+    assert_eq!(merged.scenarios, threat_model.scenarios);
+    assert_eq!(
+        ThreatModelTemplate::new_from_markdown(&merged.to_markdown()),
+        ThreatModelTemplate::new_from_markdown(markdown_content)
+    );
+
+    // the empty notes template has nothing to import
+    assert_eq!(
+        ThreatModelTemplate::new_from_markdown(
+            "## Assets\n\n### Accounts\n\n-\n\n### Others\n\n-\n\n## Actors\n\n-\n\n## Scenarios\n\n-\n"
+        ),
+        ThreatModelTemplate::default()
+    );
+}
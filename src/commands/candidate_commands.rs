@@ -0,0 +1,211 @@
+use crate::batbelt::bat_dialoguer::BatDialoguer;
+use crate::batbelt::git::GitCommit;
+use crate::batbelt::metadata::BatMetadataType;
+use crate::batbelt::path::BatFile;
+use crate::batbelt::templates::finding_candidates_template::{
+    FindingCandidate, FindingCandidateStatus, FindingCandidatesTemplate,
+};
+use crate::batbelt::templates::finding_template::{FindingTemplate, FindingTemplateDefinition};
+use crate::batbelt::templates::sidecar_template::SidecarTemplate;
+use crate::batbelt::BatEnumerator;
+use crate::commands::{BatCommandEnumerator, CommandError, CommandResult};
+use crate::config::BatAuditorConfig;
+use clap::Subcommand;
+use colored::Colorize;
+use error_stack::{Report, ResultExt};
+use inflector::Inflector;
+
+#[derive(
+    Subcommand, Debug, strum_macros::Display, PartialEq, Clone, strum_macros::EnumIter, Default,
+)]
+pub enum CandidateCommand {
+    /// Adds a finding candidate pointing to a function, struct, trait or enum
+    #[default]
+    Add,
+    /// Moves a to-review finding candidate to accepted
+    Accept,
+    /// Moves a to-review finding candidate to rejected
+    Reject,
+    /// Creates a finding file from a to-review finding candidate and moves it to accepted
+    Promote,
+}
+
+impl BatEnumerator for CandidateCommand {}
+
+impl BatCommandEnumerator for CandidateCommand {
+    fn execute_command(&self) -> CommandResult<()> {
+        match self {
+            CandidateCommand::Add => self.add_candidate(),
+            CandidateCommand::Accept => {
+                self.update_candidate_status(FindingCandidateStatus::Accepted, "accepted")
+            }
+            CandidateCommand::Reject => {
+                self.update_candidate_status(FindingCandidateStatus::Rejected, "rejected")
+            }
+            CandidateCommand::Promote => self.promote_candidate(),
+        }
+    }
+
+    fn check_metadata_is_initialized(&self) -> bool {
+        matches!(self, CandidateCommand::Add)
+    }
+
+    fn check_correct_branch(&self) -> bool {
+        true
+    }
+}
+
+impl CandidateCommand {
+    fn add_candidate(&self) -> CommandResult<()> {
+        let mut finding_candidates =
+            FindingCandidatesTemplate::read().change_context(CommandError)?;
+        let source_code_location =
+            BatMetadataType::prompt_source_code_location().change_context(CommandError)?;
+        let description = BatDialoguer::input("Finding candidate description:".to_string())?;
+        let notes = BatDialoguer::input("Notes:".to_string())?;
+        let author = BatAuditorConfig::get_config()
+            .change_context(CommandError)?
+            .auditor_name;
+        let candidate_id = finding_candidates.add_candidate(FindingCandidate {
+            description,
+            metadata_id: source_code_location.metadata_id,
+            location: source_code_location.name,
+            permalink: source_code_location.permalink,
+            author,
+            notes,
+            ..Default::default()
+        });
+        self.save_and_commit(&finding_candidates, candidate_id, "added", None)?;
+        println!(
+            "Finding candidate #{} added to {}",
+            candidate_id,
+            "to review".green()
+        );
+        Ok(())
+    }
+
+    fn update_candidate_status(
+        &self,
+        status: FindingCandidateStatus,
+        action: &str,
+    ) -> CommandResult<()> {
+        let mut finding_candidates =
+            FindingCandidatesTemplate::read().change_context(CommandError)?;
+        let candidate_id = Self::prompt_to_review_candidate(
+            &finding_candidates,
+            &format!("Select the finding candidate to move to {}:", action),
+        )?;
+        finding_candidates
+            .get_candidate_mut(candidate_id)
+            .change_context(CommandError)?
+            .status = status;
+        self.save_and_commit(&finding_candidates, candidate_id, action, None)?;
+        println!(
+            "Finding candidate #{} moved to {}",
+            candidate_id,
+            action.green()
+        );
+        Ok(())
+    }
+
+    fn promote_candidate(&self) -> CommandResult<()> {
+        let mut finding_candidates =
+            FindingCandidatesTemplate::read().change_context(CommandError)?;
+        let candidate_id = Self::prompt_to_review_candidate(
+            &finding_candidates,
+            "Select the finding candidate to promote:",
+        )?;
+        let candidate = finding_candidates
+            .get_candidate_mut(candidate_id)
+            .change_context(CommandError)?;
+        let finding_name = BatDialoguer::input("Finding name:".to_string())?.to_snake_case();
+        let finding_file = BatFile::FindingToReview {
+            file_name: finding_name.clone(),
+        };
+        if finding_file.file_exists().change_context(CommandError)? {
+            return Err(Report::new(CommandError).attach_printable(format!(
+                "Finding file already exists: {:#?}",
+                finding_file.get_path(false).change_context(CommandError)?
+            )));
+        }
+        let severity = FindingTemplateDefinition::read_definition()
+            .change_context(CommandError)?
+            .prompt_severity()
+            .change_context(CommandError)?;
+        let description = if candidate.notes.is_empty() {
+            candidate.description.clone()
+        } else {
+            format!("{}\n\n{}", candidate.description, candidate.notes)
+        };
+        // candidates imported from hand written items have no location
+        let affected_resource = if candidate.location.is_empty() {
+            "N/A".to_string()
+        } else if candidate.permalink.is_empty() {
+            format!("`{}`", candidate.location)
+        } else {
+            format!("[`{}`]({})", candidate.location, candidate.permalink)
+        };
+        FindingTemplate::new_prefilled_finding_file(
+            &finding_name,
+            &severity,
+            &description,
+            &affected_resource,
+        )
+        .change_context(CommandError)?;
+        candidate.status = FindingCandidateStatus::Accepted;
+        candidate.finding_name = finding_name.clone();
+        self.save_and_commit(
+            &finding_candidates,
+            candidate_id,
+            "promoted",
+            Some(finding_name.clone()),
+        )?;
+        println!(
+            "Finding candidate #{} promoted to {}",
+            candidate_id,
+            finding_file
+                .get_path(false)
+                .change_context(CommandError)?
+                .green()
+        );
+        finding_file
+            .open_in_editor(true, None)
+            .change_context(CommandError)?;
+        Ok(())
+    }
+
+    fn prompt_to_review_candidate(
+        finding_candidates: &FindingCandidatesTemplate,
+        prompt_text: &str,
+    ) -> CommandResult<usize> {
+        let to_review_candidates =
+            finding_candidates.get_candidates_by_status(FindingCandidateStatus::ToReview);
+        if to_review_candidates.is_empty() {
+            return Err(Report::new(CommandError)
+                .attach_printable("There are no finding candidates to review"));
+        }
+        let candidate_names = to_review_candidates
+            .iter()
+            .map(|candidate| format!("#{} {}", candidate.id, candidate.description))
+            .collect::<Vec<_>>();
+        let selection = BatDialoguer::select(prompt_text.to_string(), candidate_names, None)?;
+        Ok(to_review_candidates[selection].id)
+    }
+
+    fn save_and_commit(
+        &self,
+        finding_candidates: &FindingCandidatesTemplate,
+        candidate_id: usize,
+        action: &str,
+        finding_name: Option<String>,
+    ) -> CommandResult<()> {
+        finding_candidates.save().change_context(CommandError)?;
+        GitCommit::UpdateFindingCandidate {
+            candidate_id,
+            action: action.to_string(),
+            finding_name,
+        }
+        .create_commit()
+        .change_context(CommandError)
+    }
+}
@@ -4,7 +4,8 @@ use crate::batbelt::git::GitCommit;
 use crate::batbelt::parser::entrypoint_parser::EntrypointParser;
 use crate::batbelt::path::{BatFile, BatFolder};
 use crate::batbelt::templates::code_overhaul_template::{
-    CodeOverhaulSection, CodeOverhaulTemplate, CoderOverhaulTemplatePlaceholders,
+    get_code_overhaul_section_content, CodeOverhaulTemplate, CodeOverhaulTemplateDefinition,
+    CoderOverhaulTemplatePlaceholders,
 };
 use crate::batbelt::BatEnumerator;
 use crate::commands::{BatCommandEnumerator, CommandError, CommandResult};
@@ -80,33 +81,14 @@ impl CodeOverhaulCommand {
             let co_parser = CodeOverhaulParser::new_from_entry_point_name(entry_point_name)
                 .change_context(CommandError)?;
             log::debug!("co_parser:\n{:#?}", co_parser);
-            let state_changes_section_content = co_parser.section_content.state_changes.replace(
-                &CodeOverhaulSection::StateChanges.to_markdown_header(),
-                &CodeOverhaulSection::StateChanges
-                    .to_markdown_header()
-                    .replace("#", "##"),
-            );
-            let notes_section_content = co_parser.section_content.notes.replace(
-                &CodeOverhaulSection::Notes.to_markdown_header(),
-                &CodeOverhaulSection::Notes
-                    .to_markdown_header()
-                    .replace("#", "##"),
-            );
-            let miro_frame_url_section_content = co_parser.section_content.miro_frame_url.replace(
-                &CodeOverhaulSection::MiroFrameUrl.to_markdown_header(),
-                &CodeOverhaulSection::MiroFrameUrl
-                    .to_markdown_header()
-                    .replace("#", "##"),
-            );
+            let sections_content = co_parser.get_summary_sections_content();
             let co_file_name = finished_co_file
                 .get_file_name()
                 .change_context(CommandError)?;
             let finished_file_summary = format!(
-                "# {}\n\n{}\n\n{}\n\n{}\n\n## Code overhaul file path:\n\n[{}](code-overhaul/finished/{})",
+                "# {}\n\n{}\n\n## Code overhaul file path:\n\n[{}](code-overhaul/finished/{})",
                 co_file_name,
-                state_changes_section_content,
-                notes_section_content,
-                miro_frame_url_section_content,
+                sections_content.join("\n\n"),
                 co_file_name,
                 co_file_name
             );
@@ -366,6 +348,31 @@ mod co_commands_functions {
                 return Err(Report::new(CommandError).attach_printable("Aborted by the user"));
             }
         }
+
+        let co_template_definition =
+            CodeOverhaulTemplateDefinition::read_definition().change_context(CommandError)?;
+        let markdown_headers = co_template_definition.get_markdown_headers();
+        for section in co_template_definition
+            .sections
+            .iter()
+            .filter(|section| !section.checklist.is_empty())
+        {
+            let section_content = get_code_overhaul_section_content(
+                &file_data,
+                &section.to_markdown_header(),
+                &markdown_headers,
+            );
+            if section_content.contains("- [ ]") {
+                let user_decided_to_continue = BatDialoguer::select_yes_or_no(format!(
+                    "{} checklist has unchecked items, do you want to proceed anyway?",
+                    section.get_title().green()
+                ))
+                .change_context(CommandError)?;
+                if !user_decided_to_continue {
+                    return Err(Report::new(CommandError).attach_printable("Aborted by the user"));
+                }
+            }
+        }
         Ok(())
     }
 
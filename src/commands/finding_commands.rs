@@ -1,20 +1,33 @@
 use crate::batbelt::bat_dialoguer::BatDialoguer;
 use crate::batbelt::command_line::execute_command;
-use crate::batbelt::templates::finding_template::FindingTemplate;
+use crate::batbelt::git::GitAction;
+use crate::batbelt::metadata::BatMetadataType;
+use crate::batbelt::parser::entrypoint_parser::EntrypointParser;
+use crate::batbelt::parser::finding_parser::{
+    add_finding_evidence_figure, get_duplicated_finding_ids, get_finding_duplicate_groups,
+    get_finding_id_prefix, get_finding_ids_table, get_finding_section, get_next_finding_id,
+    get_remediation_table, set_finding_fix_review, set_finding_id, set_finding_poc_link,
+    FindingFixReview, FindingFixStatus, FindingFolderStatus, FindingParser, FINDING_SEVERITY_TITLE,
+};
+use crate::batbelt::parser::source_code_parser::SourceCodeParser;
+use crate::batbelt::templates::finding_template::{FindingTemplate, FindingTemplateDefinition};
+use crate::batbelt::templates::poc_template::{PocLanguage, PocTemplate};
 use crate::batbelt::{
     git::GitCommit,
     path::{BatFile, BatFolder},
-    BatEnumerator,
+    BatEnumerator, ShareableData,
 };
 use colored::Colorize;
 
 use crate::commands::{BatCommandEnumerator, CommandResult};
+use crate::config::{BatAuditorConfig, BatConfig};
 use clap::Subcommand;
-use error_stack::{Report, Result, ResultExt};
+use error_stack::{IntoReport, Report, Result, ResultExt};
 use inflector::Inflector;
 use std::{
     fs::File,
     io::{self, BufRead},
+    path::Path,
     string::String,
 };
 
@@ -31,10 +44,18 @@ pub enum FindingCommand {
     Finish,
     /// Update a finding file by creating a commit
     Update,
-    /// Moves all the to-review findings to accepted
+    /// Moves all the to-review findings to accepted, offering to merge the likely duplicates first
     AcceptAll,
     /// Moves a finding from to-review to rejected
     Reject,
+    /// Assigns the missing finding ids and regenerates the table from ids to report numbers
+    UpdateIds,
+    /// Records the fix commit, status and comment of an accepted finding after the fix review
+    ReviewFix,
+    /// Adds a screenshot of a source code item or a range of lines to the evidence of a finding
+    AddEvidence,
+    /// Generates a PoC test stub for the affected entry point of a finding and links it
+    Poc,
 }
 
 impl BatEnumerator for FindingCommand {}
@@ -91,13 +112,18 @@ pub fn reject() -> Result<(), CommandError> {
 
 pub fn accept_all() -> Result<(), CommandError> {
     prepare_all()?;
+    merge_duplicated_findings()?;
     let accepted_path = BatFolder::FindingsAccepted
         .get_path(true)
         .change_context(CommandError)?;
     let findings_to_review_files = BatFolder::FindingsToReview
         .get_all_files_dir_entries(true, None, None)
         .change_context(CommandError)?;
+    let mut finding_names = vec![];
     for to_review_file in findings_to_review_files {
+        if let Some(finding_name) = to_review_file.path().file_stem() {
+            finding_names.push(finding_name.to_string_lossy().to_string());
+        }
         execute_command(
             "mv",
             &[
@@ -107,7 +133,7 @@ pub fn accept_all() -> Result<(), CommandError> {
             false,
         )?;
     }
-    GitCommit::AcceptFindings
+    GitCommit::AcceptFindings { finding_names }
         .create_commit()
         .change_context(CommandError)?;
     println!(
@@ -117,6 +143,531 @@ pub fn accept_all() -> Result<(), CommandError> {
     Ok(())
 }
 
+/// Compares the findings of every auditor, merging the evidence of the duplicates into the
+/// kept finding of the auditor and moving the to-review duplicates of the auditor to rejected.
+/// The files of the other auditors are never written, they reject their own duplicates
+fn merge_duplicated_findings() -> Result<(), CommandError> {
+    let auditor_name = BatAuditorConfig::get_config()
+        .change_context(CommandError)?
+        .auditor_name;
+    let findings = FindingParser::get_all_auditors_findings().change_context(CommandError)?;
+    let get_finding_option = |finding: &FindingParser| {
+        format!(
+            "{} ({}, {})",
+            finding.file_name, finding.auditor_name, finding.status
+        )
+    };
+    for duplicate_group in get_finding_duplicate_groups(&findings, &auditor_name) {
+        println!(
+            "Likely duplicated findings:\n{}",
+            duplicate_group
+                .iter()
+                .map(|index| format!("  - {}", get_finding_option(&findings[*index])))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        let kept_indexes = duplicate_group
+            .iter()
+            .copied()
+            .filter(|index| findings[*index].auditor_name == auditor_name)
+            .collect::<Vec<_>>();
+        let mut finding_options = kept_indexes
+            .iter()
+            .map(|index| get_finding_option(&findings[*index]))
+            .collect::<Vec<_>>();
+        finding_options.push("None, they are not duplicates".to_string());
+        let selection = BatDialoguer::select(
+            format!(
+                "Select the finding of {} to keep, the rest are merged into it:",
+                auditor_name
+            ),
+            finding_options,
+            None,
+        )?;
+        if selection == kept_indexes.len() {
+            continue;
+        }
+        let kept_finding = &findings[kept_indexes[selection]];
+        let mut merged_finding = kept_finding.clone();
+        let mut merged_finding_names = vec![];
+        let mut other_auditors_findings = vec![];
+        for index in &duplicate_group {
+            let duplicate = &findings[*index];
+            let is_own_finding = duplicate.auditor_name == auditor_name;
+            if duplicate.file_path == kept_finding.file_path
+                || (is_own_finding && duplicate.status != FindingFolderStatus::ToReview)
+            {
+                continue;
+            }
+            merged_finding = FindingParser::new_from_content(
+                &merged_finding.file_path,
+                &merged_finding.auditor_name,
+                merged_finding.status,
+                &merged_finding.get_merged_content(duplicate),
+            );
+            if !is_own_finding {
+                other_auditors_findings.push(get_finding_option(duplicate));
+                continue;
+            }
+            BatFile::FindingToReview {
+                file_name: duplicate.file_name.clone(),
+            }
+            .move_file(
+                &BatFile::FindingRejected {
+                    file_name: duplicate.file_name.clone(),
+                }
+                .get_path(false)
+                .change_context(CommandError)?,
+            )
+            .change_context(CommandError)?;
+            merged_finding_names.push(duplicate.file_name.clone());
+        }
+        if merged_finding.content == kept_finding.content && merged_finding_names.is_empty() {
+            println!(
+                "{} already has the evidence of the group, skipping",
+                kept_finding.file_name
+            );
+            continue;
+        }
+        BatFile::Generic {
+            file_path: kept_finding.file_path.clone(),
+        }
+        .write_content(false, &merged_finding.content)
+        .change_context(CommandError)?;
+        GitCommit::MergeFindings {
+            kept_file_path: kept_finding.file_path.clone(),
+            merged_finding_names: merged_finding_names.clone(),
+        }
+        .create_commit()
+        .change_context(CommandError)?;
+        if !merged_finding_names.is_empty() {
+            println!(
+                "{} merged into {} and moved to rejected",
+                merged_finding_names.join(", "),
+                kept_finding.file_name.green()
+            );
+        }
+        if !other_auditors_findings.is_empty() {
+            println!(
+                "Evidence of {} merged into {}, their auditors have to reject them",
+                other_auditors_findings.join(", "),
+                kept_finding.file_name.green()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Gives an id to the findings of the auditor without one, and a new one to the to-review
+/// findings sharing it with another finding
+pub fn update_finding_ids() -> Result<(), CommandError> {
+    let auditor_name = BatAuditorConfig::get_config()
+        .change_context(CommandError)?
+        .auditor_name;
+    let prefix = get_finding_id_prefix(
+        &BatConfig::get_config()
+            .change_context(CommandError)?
+            .project_name,
+    );
+    let mut findings = FindingParser::get_all_auditors_findings().change_context(CommandError)?;
+    let duplicated_ids = get_duplicated_finding_ids(&findings);
+    let mut finding_names = vec![];
+    for index in 0..findings.len() {
+        let finding = &findings[index];
+        let needs_new_id = finding.id.is_empty()
+            || (duplicated_ids.contains(&finding.id)
+                && finding.status == FindingFolderStatus::ToReview);
+        if finding.auditor_name != auditor_name || !needs_new_id {
+            continue;
+        }
+        let finding_id = get_next_finding_id(&findings, &prefix);
+        let content = set_finding_id(&finding.content, &finding_id);
+        BatFile::Generic {
+            file_path: finding.file_path.clone(),
+        }
+        .write_content(false, &content)
+        .change_context(CommandError)?;
+        println!("{} is now {}", finding.file_name, finding_id.green());
+        finding_names.push(finding.file_name.clone());
+        findings[index] = FindingParser::new_from_content(
+            &finding.file_path,
+            &auditor_name,
+            finding.status,
+            &content,
+        );
+    }
+    for duplicated_id in get_duplicated_finding_ids(&findings) {
+        println!(
+            "{} is used by findings of other auditors, they have to run {}",
+            duplicated_id.red(),
+            "bat-cli finding update-ids".green()
+        );
+    }
+    BatFile::FindingIdsTable
+        .write_content(
+            false,
+            &get_finding_ids_table(
+                &findings,
+                &FindingTemplateDefinition::read_definition()
+                    .change_context(CommandError)?
+                    .severities,
+            ),
+        )
+        .change_context(CommandError)?;
+    GitCommit::UpdateFindingIds { finding_names }
+        .create_commit()
+        .change_context(CommandError)?;
+    println!(
+        "Finding ids table updated at {}",
+        BatFile::FindingIdsTable
+            .get_path(false)
+            .change_context(CommandError)?
+            .green()
+    );
+    Ok(())
+}
+
+pub fn review_fix() -> Result<(), CommandError> {
+    let reviewer = BatAuditorConfig::get_config()
+        .change_context(CommandError)?
+        .auditor_name;
+    let mut findings = FindingParser::get_all_auditors_findings().change_context(CommandError)?;
+    let accepted_indexes = findings
+        .iter()
+        .enumerate()
+        .filter(|(_, finding)| finding.status == FindingFolderStatus::Accepted)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    if accepted_indexes.is_empty() {
+        return Err(Report::new(CommandError).attach_printable("There are no accepted findings"));
+    }
+    let finding_names = accepted_indexes
+        .iter()
+        .map(|index| {
+            let finding = &findings[*index];
+            format!(
+                "{} {} ({})",
+                finding.id, finding.title, finding.report_status
+            )
+        })
+        .collect::<Vec<_>>();
+    let selection = BatDialoguer::select(
+        "Select the finding to review the fix of:".to_string(),
+        finding_names,
+        None,
+    )
+    .change_context(CommandError)?;
+    let finding_index = accepted_indexes[selection];
+    if findings[finding_index].branch_name.is_some()
+        && !Path::new(&findings[finding_index].file_path).is_file()
+    {
+        return Err(Report::new(CommandError).attach_printable(format!(
+            "{} is only on the {} branch, merge it before reviewing the fix",
+            findings[finding_index].file_name,
+            findings[finding_index]
+                .branch_name
+                .clone()
+                .unwrap_or_default()
+        )));
+    }
+    let fix_commit = BatDialoguer::input("Fix commit:".to_string()).change_context(CommandError)?;
+    let fix_statuses = FindingFixStatus::get_type_vec();
+    let status_selection = BatDialoguer::select(
+        "Select the status of the finding:".to_string(),
+        fix_statuses
+            .iter()
+            .map(|fix_status| fix_status.to_string())
+            .collect(),
+        None,
+    )
+    .change_context(CommandError)?;
+    let comment = BatDialoguer::input("Comment:".to_string()).change_context(CommandError)?;
+    let fix_review = FindingFixReview {
+        status: fix_statuses[status_selection].to_string(),
+        fix_commit,
+        reviewer,
+        comment,
+    };
+    let finding = &findings[finding_index];
+    let content = set_finding_fix_review(&finding.content, &fix_review);
+    BatFile::Generic {
+        file_path: finding.file_path.clone(),
+    }
+    .write_content(false, &content)
+    .change_context(CommandError)?;
+    findings[finding_index] = FindingParser::new_from_content(
+        &finding.file_path,
+        &finding.auditor_name,
+        finding.status,
+        &content,
+    );
+    BatFile::RemediationTable
+        .write_content(
+            false,
+            &get_remediation_table(
+                &findings,
+                &FindingTemplateDefinition::read_definition()
+                    .change_context(CommandError)?
+                    .severities,
+            ),
+        )
+        .change_context(CommandError)?;
+    GitCommit::ReviewFindingFix {
+        finding_file_path: findings[finding_index].file_path.clone(),
+        fix_status: fix_review.status.clone(),
+    }
+    .create_commit()
+    .change_context(CommandError)?;
+    println!(
+        "{} marked as {}, remediation table updated at {}",
+        findings[finding_index].file_name,
+        fix_review.status.green(),
+        BatFile::RemediationTable
+            .get_path(false)
+            .change_context(CommandError)?
+            .green()
+    );
+    Ok(())
+}
+
+pub fn add_evidence() -> Result<(), CommandError> {
+    let to_review_files = BatFolder::FindingsToReview
+        .get_all_files_names(true, None, None)
+        .change_context(CommandError)?;
+    if to_review_files.is_empty() {
+        return Err(Report::new(CommandError).attach_printable("There are no findings to review"));
+    }
+    let selection = BatDialoguer::select(
+        "Select the finding to add the evidence to:".to_string(),
+        to_review_files.clone(),
+        None,
+    )
+    .change_context(CommandError)?;
+    let finding_name = to_review_files[selection]
+        .trim_end_matches(".md")
+        .to_string();
+    let (location, path, start_line_index, end_line_index, permalink) =
+        if BatDialoguer::select_yes_or_no("Is the evidence a source code item?".to_string())? {
+            let source_code_location =
+                BatMetadataType::prompt_source_code_location().change_context(CommandError)?;
+            (
+                source_code_location.name,
+                source_code_location.path,
+                source_code_location.start_line_index,
+                source_code_location.end_line_index,
+                source_code_location.permalink,
+            )
+        } else {
+            prompt_evidence_line_range()?
+        };
+    let figures_path = BatFolder::AuditorFigures
+        .get_path(false)
+        .change_context(CommandError)?;
+    let figure_number = (1..)
+        .find(|number| {
+            !Path::new(&format!("{}/{}-{}.png", figures_path, finding_name, number)).exists()
+        })
+        .unwrap();
+    let screenshot_path = SourceCodeParser::new(
+        format!("{}-{}", finding_name, figure_number),
+        path,
+        start_line_index,
+        end_line_index,
+    )
+    .create_screenshot(SourceCodeParser::prompt_screenshot_options())
+    .change_context(CommandError)?;
+    let figure_file_name = Path::new(&screenshot_path)
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let finding_file = BatFile::FindingToReview {
+        file_name: finding_name.clone(),
+    };
+    let content = finding_file
+        .read_content(false)
+        .change_context(CommandError)?;
+    finding_file
+        .write_content(
+            false,
+            &add_finding_evidence_figure(&content, &figure_file_name, &location, &permalink),
+        )
+        .change_context(CommandError)?;
+    GitCommit::UpdateFinding {
+        finding_name: finding_name.clone(),
+    }
+    .create_commit()
+    .change_context(CommandError)?;
+    println!(
+        "{} added to the evidence of {}",
+        figure_file_name.green(),
+        finding_name.green()
+    );
+    finding_file
+        .open_in_editor(true, None)
+        .change_context(CommandError)?;
+    Ok(())
+}
+
+pub fn poc() -> Result<(), CommandError> {
+    let to_review_files = BatFolder::FindingsToReview
+        .get_all_files_names(true, None, None)
+        .change_context(CommandError)?;
+    if to_review_files.is_empty() {
+        return Err(Report::new(CommandError).attach_printable("There are no findings to review"));
+    }
+    let selection = BatDialoguer::select(
+        "Select the finding to generate the PoC for:".to_string(),
+        to_review_files.clone(),
+        None,
+    )
+    .change_context(CommandError)?;
+    let finding_name = to_review_files[selection]
+        .trim_end_matches(".md")
+        .to_string();
+    let finding_file = BatFile::FindingToReview {
+        file_name: finding_name.clone(),
+    };
+    let content = finding_file
+        .read_content(false)
+        .change_context(CommandError)?;
+    let entry_point_names =
+        EntrypointParser::get_entrypoint_names(true).change_context(CommandError)?;
+    // the entry point mentioned on the affected resources is the likely one
+    let affected_resources = get_finding_section(&content, "Affected resources");
+    let default_entry_point = entry_point_names
+        .iter()
+        .position(|entry_point_name| affected_resources.contains(entry_point_name.as_str()));
+    let selection = BatDialoguer::select(
+        "Select the affected entry point:".to_string(),
+        entry_point_names.clone(),
+        default_entry_point,
+    )?;
+    let poc_languages = PocLanguage::get_type_vec();
+    let language_selection = BatDialoguer::select(
+        "Select the PoC language:".to_string(),
+        poc_languages.clone(),
+        None,
+    )?;
+    let poc_language = poc_languages[language_selection];
+    let program_name = BatConfig::get_config()
+        .change_context(CommandError)?
+        .program_name;
+    let poc_content = PocTemplate::new_from_entry_point(
+        &finding_name,
+        &program_name,
+        &entry_point_names[selection],
+    )
+    .change_context(CommandError)?
+    .get_content(poc_language);
+    // without the severity prefix, so the PoC keeps its name when the severity changes
+    let poc_file_name = format!(
+        "{}.{}",
+        PocTemplate::get_poc_name(&finding_name),
+        poc_language.get_file_extension()
+    );
+    let poc_file = BatFile::FindingPoc {
+        file_name: poc_file_name.clone(),
+    };
+    if poc_file.file_exists().change_context(CommandError)?
+        && !BatDialoguer::select_yes_or_no(format!(
+            "{} already exists, do you want to replace it?",
+            poc_file_name
+        ))?
+    {
+        return Ok(());
+    }
+    std::fs::create_dir_all(
+        BatFolder::AuditorPoc
+            .get_path(false)
+            .change_context(CommandError)?,
+    )
+    .into_report()
+    .change_context(CommandError)?;
+    poc_file
+        .write_content(false, &poc_content)
+        .change_context(CommandError)?;
+    finding_file
+        .write_content(false, &set_finding_poc_link(&content, &poc_file_name))
+        .change_context(CommandError)?;
+    GitCommit::AddFindingPoc {
+        finding_name: finding_name.clone(),
+        poc_file_name,
+    }
+    .create_commit()
+    .change_context(CommandError)?;
+    println!(
+        "PoC for {} generated at {}",
+        finding_name.green(),
+        poc_file
+            .get_path(false)
+            .change_context(CommandError)?
+            .green()
+    );
+    poc_file
+        .open_in_editor(true, None)
+        .change_context(CommandError)?;
+    Ok(())
+}
+
+// location, path, start and end line indexes and permalink of a range of lines of a program file
+fn prompt_evidence_line_range() -> Result<(String, String, usize, usize, String), CommandError> {
+    let program_file_paths = BatFolder::ProgramPath
+        .get_all_files_dir_entries(true, None, None)
+        .change_context(CommandError)?
+        .into_iter()
+        .map(|dir_entry| dir_entry.path().to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    let selection = BatDialoguer::select(
+        "Select the file:".to_string(),
+        program_file_paths.clone(),
+        None,
+    )?;
+    let path = program_file_paths[selection].clone();
+    let lines_count = std::fs::read_to_string(&path)
+        .into_report()
+        .change_context(CommandError)?
+        .lines()
+        .count();
+    let prompt_line_index = |prompt_text: &str, min_line_index: usize| {
+        let line_index = BatDialoguer::input(prompt_text.to_string())?
+            .trim()
+            .parse::<usize>()
+            .into_report()
+            .change_context(CommandError)
+            .attach_printable("The line should be a number")?;
+        if line_index < min_line_index || line_index > lines_count {
+            return Err(Report::new(CommandError).attach_printable(format!(
+                "The line should be between {} and {}",
+                min_line_index, lines_count
+            )));
+        }
+        Ok(line_index)
+    };
+    let start_line_index = prompt_line_index("Start line:", 1)?;
+    let end_line_index = prompt_line_index("End line:", start_line_index)?;
+    let shared_permalink = ShareableData::new(String::new());
+    GitAction::GetRepositoryPermalink {
+        file_path: path.clone(),
+        start_line_index,
+        end_line_index: Some(end_line_index),
+        permalink: shared_permalink.original,
+    }
+    .execute_action()
+    .change_context(CommandError)?;
+    let permalink = shared_permalink.cloned.borrow().clone();
+    let file_name = Path::new(&path)
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok((
+        format!("{}:{}-{}", file_name, start_line_index, end_line_index),
+        path,
+        start_line_index,
+        end_line_index,
+        permalink,
+    ))
+}
+
 pub fn start_finding() -> Result<(), CommandError> {
     let input_name =
         BatDialoguer::input("Finding name:".to_string()).change_context(CommandError)?;
@@ -175,6 +726,8 @@ pub fn update_finding() -> Result<(), CommandError> {
 }
 
 fn prepare_all() -> Result<(), CommandError> {
+    let finding_template_definition =
+        FindingTemplateDefinition::read_definition().change_context(CommandError)?;
     let to_review_dir_entries = BatFolder::FindingsToReview
         .get_all_files_dir_entries(true, None, None)
         .change_context(CommandError)?;
@@ -188,8 +741,10 @@ fn prepare_all() -> Result<(), CommandError> {
             .split('-')
             .map(|token| token.to_string())
             .collect::<Vec<String>>();
-        let severity_flags = ["1", "2", "3", "4"];
-        let finding_name = if severity_flags.contains(&file_name_tokenized[0].as_str()) {
+        let severity_flags = (1..=finding_template_definition.severities.len())
+            .map(|severity_flag| severity_flag.to_string())
+            .collect::<Vec<_>>();
+        let finding_name = if severity_flags.contains(&file_name_tokenized[0]) {
             file_name_tokenized.remove(0);
             file_name_tokenized.join("-")
         } else {
@@ -199,20 +754,15 @@ fn prepare_all() -> Result<(), CommandError> {
         let file_lines = io::BufReader::new(open_file).lines().map(|l| l.unwrap());
         for line in file_lines {
             if line.contains("Severity:") {
-                let file_severity = line
-                    .replace("**Severity:**", "")
-                    .replace(' ', "")
-                    .to_lowercase();
-                let severity = match file_severity.as_str() {
-                    "high" => "1",
-                    "medium" => "2",
-                    "low" => "3",
-                    "informational" => "4",
-                    &_ => {
+                let file_severity = line.replace(FINDING_SEVERITY_TITLE, "").trim().to_string();
+                let severity = match finding_template_definition.get_severity_flag(&file_severity) {
+                    Some(severity_flag) => severity_flag,
+                    None => {
                         return Err(Report::new(CommandError).attach_printable(format!(
-                            "severity: {:?} not recongnized in file {:?}",
+                            "severity: {:?} not recongnized in file {:?}, expected one of {:?}",
                             file_severity,
-                            file.path()
+                            file.path(),
+                            finding_template_definition.severities
                         )));
                     }
                 };
@@ -251,11 +801,11 @@ fn validate_config_create_finding_file(finding_name: String) -> Result<(), Comma
 }
 
 fn copy_template_to_findings_to_review(finding_name: String) -> Result<(), CommandError> {
-    let prompt_text = "is the finding an informational?";
-    let is_informational =
-        BatDialoguer::select_yes_or_no(prompt_text.to_string()).change_context(CommandError)?;
-    FindingTemplate::new_finding_file(&finding_name, is_informational)
+    let severity = FindingTemplateDefinition::read_definition()
+        .change_context(CommandError)?
+        .prompt_severity()
         .change_context(CommandError)?;
+    FindingTemplate::new_finding_file(&finding_name, &severity).change_context(CommandError)?;
     let finding_path = BatFile::FindingToReview {
         file_name: finding_name,
     }
@@ -270,30 +820,16 @@ fn validate_finished_finding_file(file_name: String) -> Result<(), CommandError>
         file_name: file_name.clone(),
     };
     let file_data = bat_file.read_content(true).change_context(CommandError)?;
-    if file_data.contains("Fill the description") {
-        bat_file
-            .open_in_editor(true, None)
-            .change_context(CommandError)?;
-        return Err(Report::new(CommandError).attach_printable(format!(
-            "Please complete the Description section of the {} file",
-            file_name
-        )));
-    }
-    if file_data.contains("Fill the impact") {
-        bat_file
-            .open_in_editor(true, None)
-            .change_context(CommandError)?;
-        return Err(Report::new(CommandError).attach_printable(format!(
-            "Please complete the Impact section of the {} file",
-            file_name
-        )));
-    }
-    if file_data.contains("Add recommendations") {
+    let incomplete_sections = FindingTemplateDefinition::read_definition()
+        .change_context(CommandError)?
+        .get_incomplete_sections(&file_data);
+    if !incomplete_sections.is_empty() {
         bat_file
             .open_in_editor(true, None)
             .change_context(CommandError)?;
         return Err(Report::new(CommandError).attach_printable(format!(
-            "Please complete the Recommendations section of the {} file",
+            "Please complete the {} sections of the {} file",
+            incomplete_sections.join(", "),
             file_name
         )));
     }
@@ -0,0 +1,30 @@
+use crate::batbelt::git::audit_log::BatAuditLog;
+use crate::commands::{CommandError, CommandResult};
+use colored::Colorize;
+use error_stack::{IntoReport, ResultExt};
+use std::fs;
+
+/// Prints the audit timeline as CSV, or JSON if json is set, or writes it to output_path
+pub fn export_audit_log(json: bool, output_path: Option<String>) -> CommandResult<()> {
+    let audit_log = BatAuditLog::new_from_repository().change_context(CommandError)?;
+    let log_content = if json {
+        audit_log.get_json().change_context(CommandError)?
+    } else {
+        audit_log.get_csv()
+    };
+    match output_path {
+        Some(output_path) => {
+            fs::write(&output_path, log_content)
+                .into_report()
+                .change_context(CommandError)
+                .attach_printable(format!("Error writing the audit log to {}", output_path))?;
+            println!(
+                "{} events exported to {}",
+                audit_log.events.len(),
+                output_path.green()
+            );
+        }
+        None => println!("{}", log_content),
+    }
+    Ok(())
+}
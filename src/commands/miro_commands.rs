@@ -8,6 +8,7 @@ use regex::Regex;
 use strum::IntoEnumIterator;
 
 use crate::batbelt::bat_dialoguer::BatDialoguer;
+use crate::batbelt::canvas::CodeOverhaulCanvas;
 use crate::batbelt::git::GitCommit;
 use crate::batbelt::metadata::enums_source_code_metadata::EnumMetadataType;
 use crate::batbelt::metadata::functions_source_code_metadata::{
@@ -22,20 +23,28 @@ use crate::batbelt::metadata::{
 };
 use crate::batbelt::miro::connector::{create_connector, ConnectorOptions};
 use crate::batbelt::miro::frame::{MiroCodeOverhaulConfig, MiroFrame};
-use crate::batbelt::miro::frame::{
-    MIRO_BOARD_COLUMNS, MIRO_FRAME_HEIGHT, MIRO_FRAME_WIDTH, MIRO_INITIAL_X, MIRO_INITIAL_Y,
-};
+use crate::batbelt::miro::frame::{MIRO_BOARD_COLUMNS, MIRO_INITIAL_X, MIRO_INITIAL_Y};
 use crate::batbelt::miro::image::{MiroImage, MiroImageType};
+use crate::batbelt::miro::item::MiroItem;
+use crate::batbelt::miro::layout::{
+    MiroFrameLayout, MIRO_SIGNER_FIGURE_URL, MIRO_SIGNER_FIGURE_WIDTH, MIRO_SIGNER_WIDTH,
+};
+#[cfg(debug_assertions)]
+use crate::batbelt::miro::mock_server::MiroMockServer;
+use crate::batbelt::miro::overview::{MiroOverview, MIRO_OVERVIEW_FRAME_TITLE};
 use crate::batbelt::miro::sticky_note::MiroStickyNote;
-use crate::batbelt::miro::MiroConfig;
+use crate::batbelt::miro::sync::{MiroBoardSync, MiroDriftType, MiroTrackedItem};
+use crate::batbelt::miro::{MiroConfig, MiroItemType};
 use crate::batbelt::parser::code_overhaul_parser::CodeOverhaulParser;
 use crate::batbelt::parser::entrypoint_parser::EntrypointParser;
 use crate::batbelt::parser::function_parser::FunctionParser;
 use crate::batbelt::parser::solana_account_parser::SolanaAccountType;
 use crate::batbelt::parser::source_code_parser::{SourceCodeParser, SourceCodeScreenshotOptions};
 use crate::batbelt::path::BatFolder;
+use crate::batbelt::templates::code_overhaul_template::CodeOverhaulSection;
 use crate::batbelt::BatEnumerator;
 use crate::commands::{BatCommandEnumerator, CommandResult};
+use crate::config::{BatConfig, DiagramBackend};
 use crate::{batbelt, Suggestion};
 
 use super::CommandError;
@@ -80,6 +89,16 @@ pub enum MiroCommand {
         #[arg(long)]
         use_external: bool,
     },
+    /// Detects and repairs the drift between the Miro board and the Miro metadata
+    Sync,
+    /// Creates a frame linking the entry points with their findings and shared context accounts
+    Overview,
+    /// Runs a local Miro API mock server to test the miro commands offline, available only for dev
+    MockServer {
+        /// port to listen to, 0 picks a free one
+        #[arg(long, default_value_t = 3010)]
+        port: u16,
+    },
 }
 
 impl BatEnumerator for MiroCommand {}
@@ -90,18 +109,43 @@ impl BatCommandEnumerator for MiroCommand {
     }
 
     fn check_metadata_is_initialized(&self) -> bool {
-        true
+        !matches!(self, MiroCommand::MockServer { .. })
     }
 
     fn check_correct_branch(&self) -> bool {
         false
     }
+
+    fn is_package_json_command(&self) -> bool {
+        !matches!(self, MiroCommand::MockServer { .. })
+    }
 }
 
 impl MiroCommand {
     pub async fn execute_command(&self) -> Result<(), CommandError> {
-        MiroConfig::check_miro_enabled().change_context(CommandError)?;
         match self {
+            // only for dev
+            #[cfg(debug_assertions)]
+            MiroCommand::MockServer { port } => self.run_mock_server(*port).await,
+            #[cfg(not(debug_assertions))]
+            MiroCommand::MockServer { .. } => {
+                unimplemented!("Command only implemented for dev operations")
+            }
+            MiroCommand::CodeOverhaulFrames if Self::check_diagram_backend()? => {
+                self.create_co_canvas_frames()
+            }
+            MiroCommand::CodeOverhaulScreenshots { entry_point_name }
+                if Self::check_diagram_backend()? =>
+            {
+                self.create_co_canvas_screenshots(entry_point_name.clone())
+            }
+            _ if Self::check_diagram_backend()? => {
+                Err(Report::new(CommandError).attach_printable(format!(
+                    "{} is only available for the {} diagram backend",
+                    self.to_string().to_kebab_case(),
+                    DiagramBackend::Miro
+                )))
+            }
             MiroCommand::CodeOverhaulFrames => self.deploy_co_frames().await,
             MiroCommand::CodeOverhaulScreenshots { entry_point_name } => {
                 self.deploy_co_screenshots(entry_point_name.clone()).await
@@ -129,7 +173,438 @@ impl MiroCommand {
                     .change_context(CommandError)?;
                 self.function_dependencies(*select_all).await
             }
+            MiroCommand::Sync => self.sync_board().await,
+            MiroCommand::Overview => self.deploy_overview().await,
+        }
+    }
+
+    /// True for the JSON canvas backend, checks that Miro is enabled otherwise
+    fn check_diagram_backend() -> CommandResult<bool> {
+        let bat_config = BatConfig::get_config().change_context(CommandError)?;
+        if bat_config.diagram_backend == DiagramBackend::JsonCanvas {
+            return Ok(true);
+        }
+        MiroConfig::check_miro_enabled().change_context(CommandError)?;
+        Ok(false)
+    }
+
+    async fn deploy_overview(&self) -> CommandResult<()> {
+        let miro_overview = MiroOverview::new_from_audit().change_context(CommandError)?;
+        // the previous overview is replaced
+        let board_frames = MiroFrame::get_frames_from_miro()
+            .await
+            .change_context(CommandError)?;
+        for previous_frame in board_frames
+            .iter()
+            .filter(|frame| frame.title == MIRO_OVERVIEW_FRAME_TITLE)
+        {
+            println!(
+                "Deleting the previous overview frame {}",
+                previous_frame.item_id
+            );
+            for frame_item in previous_frame
+                .get_items_within_frame()
+                .await
+                .change_context(CommandError)?
+            {
+                MiroItem::delete_item_on_board(&frame_item.item_id)
+                    .await
+                    .change_context(CommandError)?;
+            }
+            MiroItem::delete_item_on_board(&previous_frame.item_id)
+                .await
+                .change_context(CommandError)?;
+        }
+
+        let frame_layout = MiroFrameLayout::get_layout().change_context(CommandError)?;
+        let (frame_width, frame_height) = miro_overview.get_frame_size();
+        // at the left of the first code-overhaul frame
+        let x_position = MIRO_INITIAL_X - (frame_layout.frame_width + frame_width) as i64 / 2 - 100;
+        let mut overview_frame =
+            MiroFrame::new(MIRO_OVERVIEW_FRAME_TITLE, frame_height, frame_width, 0, 0);
+        overview_frame.deploy().await.change_context(CommandError)?;
+        overview_frame
+            .update_position(x_position, MIRO_INITIAL_Y)
+            .await
+            .change_context(CommandError)?;
+        println!(
+            "Deploying {} entry points, {} shared accounts and {} findings to the overview",
+            miro_overview.entry_points.len(),
+            miro_overview.get_shared_structs().len(),
+            miro_overview.findings.len()
+        );
+        miro_overview
+            .deploy(&overview_frame)
+            .await
+            .change_context(CommandError)?;
+        println!(
+            "Overview frame url: {}",
+            MiroFrame::get_frame_url_by_frame_id(&overview_frame.item_id)
+                .change_context(CommandError)?
+                .green()
+        );
+        Ok(())
+    }
+
+    async fn sync_board(&self) -> CommandResult<()> {
+        let mut bat_metadata = BatMetadata::read_metadata().change_context(CommandError)?;
+        println!("Comparing the Miro board with the Miro metadata");
+        let board_sync = MiroBoardSync::new_scanned(&bat_metadata.miro.code_overhaul)
+            .await
+            .change_context(CommandError)?;
+        if board_sync.drifts.is_empty() {
+            println!("{}", "Miro board and Miro metadata are in sync".green());
+            return Ok(());
+        }
+        println!("{} differences found:", board_sync.drifts.len());
+        for drift in &board_sync.drifts {
+            println!(
+                "- {}: {}",
+                drift.drift_type.to_string().red(),
+                drift.get_description()
+            );
+        }
+
+        let entry_point_names =
+            EntrypointParser::get_entrypoint_names(false).change_context(CommandError)?;
+        let mut metadata_updated = false;
+        // the connectors to these items are created when they are deployed again
+        let missing_item_ids = board_sync
+            .drifts
+            .iter()
+            .filter(|drift| drift.drift_type == MiroDriftType::MissingItem)
+            .filter_map(|drift| drift.item.as_ref().map(|item| item.item_id.clone()))
+            .collect::<Vec<_>>();
+        for drift in board_sync.drifts.clone() {
+            let entry_point_name = drift.entry_point_name.clone();
+            let co_metadata_index = bat_metadata
+                .miro
+                .code_overhaul
+                .iter()
+                .position(|co_metadata| co_metadata.entry_point_name == entry_point_name);
+            match drift.drift_type {
+                MiroDriftType::MissingFrame => {
+                    let co_metadata_index = co_metadata_index.unwrap();
+                    let adopt_frame = match &drift.adoptable_frame_id {
+                        Some(adoptable_frame_id) => BatDialoguer::select_yes_or_no(format!(
+                            "Adopt the frame {} for {}?",
+                            adoptable_frame_id,
+                            entry_point_name.green()
+                        ))?,
+                        None => false,
+                    };
+                    let new_frame_id = if adopt_frame {
+                        drift.adoptable_frame_id.clone().unwrap()
+                    } else if BatDialoguer::select_yes_or_no(format!(
+                        "Recreate the frame for {}?",
+                        entry_point_name.green()
+                    ))? {
+                        let entry_point_index = entry_point_names
+                            .iter()
+                            .position(|name| name == &entry_point_name)
+                            .unwrap_or(entry_point_names.len());
+                        miro_command_functions::deploy_miro_frame_for_co(
+                            &entry_point_name,
+                            entry_point_index,
+                        )
+                        .await?
+                        .item_id
+                    } else {
+                        continue;
+                    };
+                    let co_metadata = &mut bat_metadata.miro.code_overhaul[co_metadata_index];
+                    co_metadata.miro_frame_id = new_frame_id;
+                    co_metadata.reset_deployed_items();
+                    metadata_updated = true;
+                }
+                MiroDriftType::MissingItem => {
+                    let tracked_item = drift.item.clone().unwrap();
+                    let prompt_text = format!(
+                        "Deploy the {} of {} again?",
+                        tracked_item.label,
+                        entry_point_name.green()
+                    );
+                    if !BatDialoguer::select_yes_or_no(prompt_text)? {
+                        continue;
+                    }
+                    let co_miro_frame = board_sync
+                        .board_frames
+                        .iter()
+                        .find(|frame| frame.item_id == drift.frame_id)
+                        .cloned()
+                        .unwrap();
+                    let co_metadata =
+                        &mut bat_metadata.miro.code_overhaul[co_metadata_index.unwrap()];
+                    miro_command_functions::redeploy_co_item(
+                        co_metadata,
+                        co_miro_frame,
+                        &tracked_item,
+                        &missing_item_ids,
+                    )
+                    .await?;
+                    metadata_updated = true;
+                }
+                MiroDriftType::MovedItem => {
+                    let tracked_item = drift.item.clone().unwrap();
+                    let prompt_text = format!(
+                        "Move the {} of {} back to its frame?",
+                        tracked_item.label,
+                        entry_point_name.green()
+                    );
+                    if BatDialoguer::select_yes_or_no(prompt_text)? {
+                        MiroItem::new(
+                            &tracked_item.item_id,
+                            &drift.frame_id,
+                            tracked_item.x_position,
+                            tracked_item.y_position,
+                            tracked_item.item_type,
+                        )
+                        .update_item_position()
+                        .await;
+                    }
+                }
+                MiroDriftType::UntrackedItem => {
+                    let untracked_item = drift.item.clone().unwrap();
+                    println!(
+                        "The {} {} can be deleted from the board, or kept as a note",
+                        untracked_item.label, untracked_item.item_id
+                    );
+                }
+                MiroDriftType::OrphanedFrame => {
+                    if co_metadata_index.is_some() || !entry_point_names.contains(&entry_point_name)
+                    {
+                        println!(
+                            "Frame {} can be deleted from the board: {}",
+                            drift.frame_id,
+                            MiroFrame::get_frame_url_by_frame_id(&drift.frame_id)
+                                .change_context(CommandError)?
+                        );
+                        continue;
+                    }
+                    let prompt_text = format!(
+                        "Adopt the frame {} for {}?",
+                        drift.frame_id,
+                        entry_point_name.green()
+                    );
+                    if !BatDialoguer::select_yes_or_no(prompt_text)? {
+                        continue;
+                    }
+                    let co_metadata = MiroCodeOverhaulMetadata {
+                        metadata_id: BatMetadata::create_metadata_id(),
+                        entry_point_name: entry_point_name.clone(),
+                        miro_frame_id: drift.frame_id.clone(),
+                        images_deployed: false,
+                        entry_point_image_id: "".to_string(),
+                        context_accounts_image_id: "".to_string(),
+                        validations_image_id: "".to_string(),
+                        handler_image_id: "".to_string(),
+                        signers: vec![],
+                        state_changes_image_ids: vec![],
+                        handler_parameters_image_ids: vec![],
+                    };
+                    bat_metadata.miro.code_overhaul.push(co_metadata);
+                    metadata_updated = true;
+                }
+            }
+        }
+
+        if metadata_updated {
+            bat_metadata.save_metadata().change_context(CommandError)?;
+            GitCommit::UpdateMetadataJson {
+                bat_metadata_commit: BatMetadataCommit::MiroMetadataCommit,
+            }
+            .create_commit()
+            .change_context(CommandError)?;
+        }
+        Ok(())
+    }
+
+    fn create_co_canvas_frames(&self) -> CommandResult<()> {
+        println!("Creating the code-overhaul canvas files");
+        let entry_point_names =
+            EntrypointParser::get_entrypoint_names(false).change_context(CommandError)?;
+        for entry_point_name in entry_point_names {
+            let mut co_canvas = CodeOverhaulCanvas::new_from_entry_point_name(&entry_point_name)
+                .change_context(CommandError)?;
+            co_canvas.add_frame();
+            let canvas_file_path = co_canvas.save().change_context(CommandError)?;
+            println!(
+                "Canvas for {} saved to {}",
+                entry_point_name.green(),
+                canvas_file_path
+            );
         }
+        Ok(())
+    }
+
+    fn create_co_canvas_screenshots(&self, entry_point_name: Option<String>) -> CommandResult<()> {
+        let entrypoint_name =
+            miro_command_functions::prompt_select_co_entry_point(entry_point_name)?;
+        let mut co_canvas = CodeOverhaulCanvas::new_from_entry_point_name(&entrypoint_name)
+            .change_context(CommandError)?;
+        co_canvas.add_frame();
+        let frame_title = co_canvas.get_frame_title();
+
+        let entrypoint_parser =
+            EntrypointParser::new_from_name(&entrypoint_name).change_context(CommandError)?;
+        let co_parser = CodeOverhaulParser::new_from_entry_point_name(entrypoint_name.clone())
+            .change_context(CommandError)?;
+        let signers_info = miro_command_functions::prompt_signers_info(&co_parser)?;
+
+        let screenshot_options = SourceCodeScreenshotOptions {
+            include_path: false,
+            offset_to_start_line: true,
+            filter_comments: false,
+            font_size: None,
+            filters: None,
+            show_line_number: true,
+        };
+        let entrypoint_path = entrypoint_parser
+            .entry_point_function
+            .to_source_code_parser(Some(miro_command_functions::parse_screenshot_name(
+                &entrypoint_parser.entry_point_function.name,
+                &frame_title,
+            )))
+            .create_screenshot(screenshot_options.clone())
+            .change_context(CommandError)?;
+        co_canvas
+            .add_screenshot(&entrypoint_path, MiroCodeOverhaulConfig::EntryPoint)
+            .change_context(CommandError)?;
+
+        let ca_path = co_parser
+            .create_context_accounts_screenshot(&miro_command_functions::parse_screenshot_name(
+                "context_accounts",
+                &frame_title,
+            ))
+            .change_context(CommandError)?;
+        co_canvas
+            .add_screenshot(&ca_path, MiroCodeOverhaulConfig::ContextAccount)
+            .change_context(CommandError)?;
+
+        let validations_path = co_parser
+            .create_validations_screenshot(&miro_command_functions::parse_screenshot_name(
+                "validations",
+                &frame_title,
+            ))
+            .change_context(CommandError)?;
+        co_canvas
+            .add_screenshot(&validations_path, MiroCodeOverhaulConfig::Validations)
+            .change_context(CommandError)?;
+
+        let entry_point_node_id =
+            CodeOverhaulCanvas::get_screenshot_node_id(&MiroCodeOverhaulConfig::EntryPoint);
+        let ca_node_id =
+            CodeOverhaulCanvas::get_screenshot_node_id(&MiroCodeOverhaulConfig::ContextAccount);
+        let validations_node_id =
+            CodeOverhaulCanvas::get_screenshot_node_id(&MiroCodeOverhaulConfig::Validations);
+        co_canvas.connect(entry_point_node_id, ca_node_id, None);
+        co_canvas.connect(ca_node_id, validations_node_id, None);
+
+        if let Some(handler_meta) = entrypoint_parser.handler.clone() {
+            let handler_path = handler_meta
+                .to_source_code_parser(Some(miro_command_functions::parse_screenshot_name(
+                    &handler_meta.name,
+                    &frame_title,
+                )))
+                .create_screenshot(SourceCodeScreenshotOptions {
+                    include_path: true,
+                    ..screenshot_options.clone()
+                })
+                .change_context(CommandError)?;
+            co_canvas
+                .add_screenshot(&handler_path, MiroCodeOverhaulConfig::Handler)
+                .change_context(CommandError)?;
+            co_canvas.connect(
+                validations_node_id,
+                CodeOverhaulCanvas::get_screenshot_node_id(&MiroCodeOverhaulConfig::Handler),
+                None,
+            );
+        }
+
+        let state_changes_parsers =
+            miro_command_functions::get_state_changes_parsers(&entrypoint_parser, &frame_title)?;
+        for (state_changes_index, state_changes_parser) in
+            state_changes_parsers.into_iter().enumerate()
+        {
+            let state_changes_path = state_changes_parser
+                .create_screenshot(SourceCodeScreenshotOptions {
+                    include_path: true,
+                    ..screenshot_options.clone()
+                })
+                .change_context(CommandError)?;
+            co_canvas
+                .add_group_screenshot(
+                    &state_changes_path,
+                    MiroCodeOverhaulConfig::StateChanges,
+                    state_changes_index,
+                )
+                .change_context(CommandError)?;
+        }
+
+        let handler_parameters_parsers = miro_command_functions::get_handler_parameters_parsers(
+            &entrypoint_parser,
+            &frame_title,
+        )?;
+        for (handler_parameter_index, handler_parameter_parser) in
+            handler_parameters_parsers.into_iter().enumerate()
+        {
+            let handler_parameter_path = handler_parameter_parser
+                .create_screenshot(SourceCodeScreenshotOptions {
+                    include_path: true,
+                    ..screenshot_options.clone()
+                })
+                .change_context(CommandError)?;
+            co_canvas
+                .add_group_screenshot(
+                    &handler_parameter_path,
+                    MiroCodeOverhaulConfig::HandlerParameters,
+                    handler_parameter_index,
+                )
+                .change_context(CommandError)?;
+        }
+
+        for (signer_index, signer_info) in signers_info.iter().enumerate() {
+            co_canvas.add_signer(signer_index, signer_info);
+        }
+        if co_canvas.frame_layout.auto_layout {
+            co_canvas.pack_screenshots();
+        }
+
+        let canvas_file_path = co_canvas.save().change_context(CommandError)?;
+        println!(
+            "Canvas for {} saved to {}",
+            entrypoint_name.green(),
+            canvas_file_path
+        );
+        Ok(())
+    }
+
+    #[cfg(debug_assertions)]
+    async fn run_mock_server(&self, port: u16) -> Result<(), CommandError> {
+        let mock_server = MiroMockServer::start(port)
+            .await
+            .change_context(CommandError)?;
+        println!(
+            "Miro mock server listening on {}\nSet {} to {} in BatAuditor.toml to use it, press Ctrl+C to stop",
+            mock_server.base_url.green(),
+            "miro_api_base_url".bright_blue(),
+            mock_server.base_url.green()
+        );
+        tokio::signal::ctrl_c()
+            .await
+            .into_report()
+            .change_context(CommandError)?;
+        let created_items = mock_server.get_created_items();
+        println!("\n{} items created:", created_items.len());
+        for item in created_items {
+            println!(
+                "- {} {}: {}",
+                item.item_type.bright_blue(),
+                item.id,
+                item.data
+            );
+        }
+        Ok(())
     }
 
     async fn entrypoint_screenshots(
@@ -704,6 +1179,8 @@ impl MiroCommand {
                                 validations_image_id: "".to_string(),
                                 handler_image_id: "".to_string(),
                                 signers: vec![],
+                                state_changes_image_ids: vec![],
+                                handler_parameters_image_ids: vec![],
                             };
                             new_co_metadata
                                 .update_code_overhaul_metadata()
@@ -722,6 +1199,8 @@ impl MiroCommand {
                         validations_image_id: "".to_string(),
                         handler_image_id: "".to_string(),
                         signers: vec![],
+                        state_changes_image_ids: vec![],
+                        handler_parameters_image_ids: vec![],
                     };
 
                     let miro_frame = miro_command_functions::deploy_miro_frame_for_co(
@@ -764,46 +1243,8 @@ impl MiroCommand {
                 .attach(Suggestion(suggestion_message)));
         }
 
-        let co_started_bat_folder = BatFolder::CodeOverhaulStarted;
-        let co_finished_bat_folder = BatFolder::CodeOverhaulFinished;
-        let mut started_files_names = co_started_bat_folder
-            .get_all_files_names(true, None, None)
-            .change_context(CommandError)?;
-        let mut finished_files_names = co_finished_bat_folder
-            .get_all_files_names(true, None, None)
-            .change_context(CommandError)?;
-        if started_files_names.is_empty() && finished_files_names.is_empty() {
-            return Err(Report::new(CommandError)
-                .attach_printable("code-overhaul's to-review and finished folders are empty"));
-        }
-
-        let mut co_files_names = vec![];
-        co_files_names.append(&mut started_files_names.clone());
-        co_files_names.append(&mut finished_files_names.clone());
-        co_files_names.sort();
-
-        let entrypoint_name = match entry_point_name {
-            None => {
-                let prompt_text = "Select the co file to deploy to Miro".to_string();
-                let selection = BatDialoguer::select(prompt_text, co_files_names.clone(), None)?;
-                let selected_file_name = co_files_names[selection].clone();
-                let entrypoint_name = selected_file_name.trim_end_matches(".md").to_string();
-                entrypoint_name
-            }
-            Some(ep_name) => {
-                let entrypoint_name = ep_name.trim_end_matches(".md").to_string();
-                let co_file_name = format!("{}.md", entrypoint_name.clone());
-                if !co_files_names.contains(&co_file_name) {
-                    return Err(Report::new(CommandError).attach_printable(format!(
-                        "code-overhaul's file with name {} not found on {} and {} folders",
-                        co_file_name.clone(),
-                        "to-review".bright_red(),
-                        "finished".bright_red()
-                    )));
-                }
-                entrypoint_name
-            }
-        };
+        let entrypoint_name =
+            miro_command_functions::prompt_select_co_entry_point(entry_point_name)?;
 
         let (co_miro_frame, mut miro_co_metadata) =
             match MiroMetadata::get_co_metadata_by_entrypoint_name(entrypoint_name.clone()) {
@@ -849,43 +1290,8 @@ impl MiroCommand {
                 EntrypointParser::new_from_name(&entrypoint_name).change_context(CommandError)?;
             let co_parser = CodeOverhaulParser::new_from_entry_point_name(entrypoint_name.clone())
                 .change_context(CommandError)?;
-            let mut signers_info: Vec<SignerInfo> = vec![];
-            if !co_parser.signers.is_empty() {
-                for signer in co_parser.signers.clone().into_iter() {
-                    let prompt_text = format!(
-                        "is the signer {} a validated signer?",
-                        signer.name.to_string().red()
-                    );
-                    let is_validated =
-                        BatDialoguer::select_yes_or_no(prompt_text).change_context(CommandError)?;
-                    let signer_type = if is_validated {
-                        SignerType::Validated
-                    } else {
-                        SignerType::NotValidated
-                    };
-
-                    let signer_title = if is_validated {
-                        format!("Validated signer:<br> <strong>{}</strong>", signer.name)
-                    } else {
-                        format!("Not validated signer:<br> <strong>{}</strong>", signer.name)
-                    };
-
-                    signers_info.push(SignerInfo {
-                        signer_text: signer_title,
-                        sticky_note_id: "".to_string(),
-                        user_figure_id: "".to_string(),
-                        signer_type,
-                    })
-                }
-            } else {
-                // no signers, push template signer
-                signers_info.push(SignerInfo {
-                    signer_text: SignerType::Permissionless.to_string(),
-                    sticky_note_id: "".to_string(),
-                    user_figure_id: "".to_string(),
-                    signer_type: SignerType::Permissionless,
-                })
-            }
+            let mut signers_info = miro_command_functions::prompt_signers_info(&co_parser)?;
+            let frame_layout = MiroFrameLayout::get_layout().change_context(CommandError)?;
 
             println!(
                 "Creating signers figures in Miro for {}",
@@ -893,33 +1299,21 @@ impl MiroCommand {
             );
 
             for (signer_index, signer) in signers_info.iter_mut().enumerate() {
-                let x_position = 550;
-                let y_position = (150 + signer_index * 270) as i64;
-                let width = 374;
-                let mut signer_sticky_note = MiroStickyNote::new(
-                    &signer.signer_text,
-                    signer.signer_type.get_sticky_note_color(),
+                let ((x_position, y_position), (figure_x_position, figure_y_position)) =
+                    frame_layout.get_signer_positions(signer_index);
+                let signer_sticky_note = miro_command_functions::deploy_signer_sticky_note(
+                    signer,
                     &co_miro_frame.item_id,
                     x_position,
                     y_position,
-                    width,
-                    0,
-                );
-                signer_sticky_note
-                    .deploy()
-                    .await
-                    .change_context(CommandError)?;
-
-                let user_figure_url = "https://mirostatic.com/app/static/12079327f83ff492.svg";
-                let y_position = (150 + signer_index * 270) as i64;
-                let mut user_figure = MiroImage::new_from_url(
-                    user_figure_url,
+                )
+                .await?;
+                let user_figure = miro_command_functions::deploy_signer_figure(
                     &co_miro_frame.item_id,
-                    150,
-                    y_position,
-                    200,
-                );
-                user_figure.deploy().await.change_context(CommandError)?;
+                    figure_x_position,
+                    figure_y_position,
+                )
+                .await?;
 
                 *signer = SignerInfo {
                     signer_text: signer.signer_text.clone(),
@@ -931,12 +1325,12 @@ impl MiroCommand {
             miro_co_metadata.signers = signers_info.clone();
 
             let (entrypoint_x_position, entrypoint_y_position) =
-                MiroCodeOverhaulConfig::EntryPoint.get_positions();
+                frame_layout.get_position(&MiroCodeOverhaulConfig::EntryPoint);
             let (handler_x_position, handler_y_position) =
-                MiroCodeOverhaulConfig::Handler.get_positions();
+                frame_layout.get_position(&MiroCodeOverhaulConfig::Handler);
 
-            match entrypoint_parser.handler.clone() {
-                None => {}
+            let handler_image = match entrypoint_parser.handler.clone() {
+                None => None,
                 Some(handler_meta) => {
                     let handler_sc = handler_meta.to_source_code_parser(Some(
                         miro_command_functions::parse_screenshot_name(
@@ -960,9 +1354,10 @@ impl MiroCommand {
                         )
                         .await
                         .change_context(CommandError)?;
-                    miro_co_metadata.handler_image_id = handler_image.item_id;
+                    miro_co_metadata.handler_image_id = handler_image.item_id.clone();
+                    Some(handler_image)
                 }
-            }
+            };
 
             let entrypoint_function_image = entrypoint_parser
                 .entry_point_function
@@ -1002,106 +1397,39 @@ impl MiroCommand {
             miro_co_metadata.context_accounts_image_id = ca_miro_image.item_id.clone();
             miro_co_metadata.images_deployed = true;
 
-            miro_co_metadata
-                .update_code_overhaul_metadata()
-                .change_context(CommandError)?;
-
-            GitCommit::UpdateMetadataJson {
-                bat_metadata_commit: BatMetadataCommit::MiroMetadataCommit,
-            }
-            .create_commit()
-            .change_context(CommandError)?;
-
-            println!("Connecting signers to entrypoint");
-            for signer_miro_ids in signers_info {
-                batbelt::miro::connector::create_connector(
-                    &signer_miro_ids.user_figure_id,
-                    &signer_miro_ids.sticky_note_id,
-                    None,
-                )
-                .await
-                .change_context(CommandError)?;
-                batbelt::miro::connector::create_connector(
-                    &signer_miro_ids.sticky_note_id,
-                    &miro_co_metadata.entry_point_image_id,
-                    Some(ConnectorOptions {
-                        start_x_position: "100%".to_string(),
-                        start_y_position: "50%".to_string(),
-                        end_x_position: "0%".to_string(),
-                        end_y_position: "50%".to_string(),
-                    }),
-                )
-                .await
-                .change_context(CommandError)?;
+            let mut screenshot_images = vec![
+                (
+                    MiroCodeOverhaulConfig::EntryPoint,
+                    entrypoint_function_image,
+                ),
+                (MiroCodeOverhaulConfig::ContextAccount, ca_miro_image),
+                (MiroCodeOverhaulConfig::Validations, validations_miro_image),
+            ];
+            if let Some(handler_image) = handler_image {
+                screenshot_images.push((MiroCodeOverhaulConfig::Handler, handler_image));
             }
 
-            println!("Connecting entrypoint screenshot to context accounts screenshot in Miro");
-            batbelt::miro::connector::create_connector(
-                &miro_co_metadata.entry_point_image_id,
-                &miro_co_metadata.context_accounts_image_id,
-                None,
-            )
-            .await
-            .change_context(CommandError)?;
-
-            println!("Connecting context accounts screenshot to validations screenshot in Miro");
-            batbelt::miro::connector::create_connector(
-                &miro_co_metadata.context_accounts_image_id,
-                &miro_co_metadata.validations_image_id,
-                None,
-            )
-            .await
-            .change_context(CommandError)?;
-
-            if !miro_co_metadata.handler_image_id.is_empty() {
-                println!("Connecting validations screenshot to handler screenshot in Miro");
-                batbelt::miro::connector::create_connector(
-                    &miro_co_metadata.validations_image_id,
-                    &miro_co_metadata.handler_image_id,
-                    None,
-                )
-                .await
-                .change_context(CommandError)?;
+            println!("Connecting the signers and screenshots in Miro");
+            for (start_item_id, end_item_id, connector_options) in
+                miro_command_functions::get_co_connections(&miro_co_metadata)
+            {
+                create_connector(&start_item_id, &end_item_id, connector_options)
+                    .await
+                    .change_context(CommandError)?;
             }
 
             // Deploy mut_accounts
-            let bat_metadata = BatMetadata::read_metadata().change_context(CommandError)?;
-            let context_accounts_metadata = bat_metadata
-                .get_context_accounts_metadata_by_struct_source_code_metadata_id(
-                    entrypoint_parser.context_accounts.metadata_id,
-                )
-                .change_context(CommandError)?;
-            let mut_program_owned_accounts = context_accounts_metadata
-                .context_accounts_info
-                .into_iter()
-                .filter(|ca_info| {
-                    ca_info.is_mut
-                        && ca_info.solana_account_type == SolanaAccountType::ProgramStateAccount
-                });
-            for mut_account in mut_program_owned_accounts {
-                let struct_metadata_vec = SourceCodeMetadata::get_filtered_structs(
-                    Some(mut_account.account_struct_name),
-                    Some(StructMetadataType::SolanaAccount),
-                )
-                .change_context(CommandError)?;
-
-                if struct_metadata_vec.len() != 1 {
-                    return Err(Report::new(CommandError).attach_printable(format!(
-                        "Error looking for Solana Accounts, expected 1 result, got:\n{:#?}",
-                        struct_metadata_vec
-                    )));
-                }
-
-                let struct_metadata = struct_metadata_vec[0].clone();
-                struct_metadata
-                    .to_source_code_parser(Some(miro_command_functions::parse_screenshot_name(
-                        &struct_metadata.name,
-                        &co_miro_frame.title,
-                    )))
+            let (state_changes_x_position, state_changes_y_position) =
+                frame_layout.get_position(&MiroCodeOverhaulConfig::StateChanges);
+            for state_changes_parser in miro_command_functions::get_state_changes_parsers(
+                &entrypoint_parser,
+                &co_miro_frame.title,
+            )? {
+                let state_changes_image = state_changes_parser
                     .deploy_screenshot_to_miro_frame(
                         co_miro_frame.clone(),
-                        0,
-                        co_miro_frame.height as i64,
+                        state_changes_x_position,
+                        state_changes_y_position,
                         SourceCodeScreenshotOptions {
                             include_path: true,
                             offset_to_start_line: true,
@@ -1113,51 +1441,56 @@ impl MiroCommand {
                     )
                     .await
                     .change_context(CommandError)?;
+                miro_co_metadata
+                    .state_changes_image_ids
+                    .push(state_changes_image.item_id.clone());
+                screenshot_images.push((MiroCodeOverhaulConfig::StateChanges, state_changes_image));
             }
 
             // Deploy handler parameters
-            if let Some(handler_meta) = entrypoint_parser.handler.clone() {
-                let handler_function_parser =
-                    FunctionParser::new_from_metadata(handler_meta).change_context(CommandError)?;
-                for handler_function_parameter in handler_function_parser.parameters {
-                    // parameters are most likely Structs
-                    if let Ok(parameter_metadata_vec) = SourceCodeMetadata::get_filtered_structs(
-                        Some(
-                            handler_function_parameter
-                                .parameter_type
-                                .trim_start_matches("&")
-                                .to_string(),
-                        ),
-                        Some(StructMetadataType::Other),
-                    ) {
-                        if parameter_metadata_vec.len() == 1 {
-                            let parameter_metadata = parameter_metadata_vec[0].clone();
-                            parameter_metadata
-                                .to_source_code_parser(Some(
-                                    miro_command_functions::parse_screenshot_name(
-                                        &parameter_metadata.name,
-                                        &co_miro_frame.title,
-                                    ),
-                                ))
-                                .deploy_screenshot_to_miro_frame(
-                                    co_miro_frame.clone(),
-                                    0,
-                                    co_miro_frame.height as i64,
-                                    SourceCodeScreenshotOptions {
-                                        include_path: true,
-                                        offset_to_start_line: true,
-                                        filter_comments: false,
-                                        font_size: None,
-                                        filters: None,
-                                        show_line_number: true,
-                                    },
-                                )
-                                .await
-                                .change_context(CommandError)?;
-                        }
-                    }
-                }
+            let (handler_parameters_x_position, handler_parameters_y_position) =
+                frame_layout.get_position(&MiroCodeOverhaulConfig::HandlerParameters);
+            for handler_parameter_parser in miro_command_functions::get_handler_parameters_parsers(
+                &entrypoint_parser,
+                &co_miro_frame.title,
+            )? {
+                let handler_parameter_image = handler_parameter_parser
+                    .deploy_screenshot_to_miro_frame(
+                        co_miro_frame.clone(),
+                        handler_parameters_x_position,
+                        handler_parameters_y_position,
+                        SourceCodeScreenshotOptions {
+                            include_path: true,
+                            offset_to_start_line: true,
+                            filter_comments: false,
+                            font_size: None,
+                            filters: None,
+                            show_line_number: true,
+                        },
+                    )
+                    .await
+                    .change_context(CommandError)?;
+                miro_co_metadata
+                    .handler_parameters_image_ids
+                    .push(handler_parameter_image.item_id.clone());
+                screenshot_images.push((
+                    MiroCodeOverhaulConfig::HandlerParameters,
+                    handler_parameter_image,
+                ));
             }
+
+            miro_co_metadata
+                .update_code_overhaul_metadata()
+                .change_context(CommandError)?;
+
+            GitCommit::UpdateMetadataJson {
+                bat_metadata_commit: BatMetadataCommit::MiroMetadataCommit,
+            }
+            .create_commit()
+            .change_context(CommandError)?;
+
+            miro_command_functions::arrange_co_screenshots(&frame_layout, screenshot_images)
+                .await?;
         } else {
             // update screenshots
             let options = vec![
@@ -1284,6 +1617,124 @@ pub mod miro_command_functions {
     use super::*;
     use crate::batbelt::path::prettify_source_code_path;
 
+    /// Selects a code-overhaul file from the started and finished folders, or validates the
+    /// provided entry point name
+    pub fn prompt_select_co_entry_point(entry_point_name: Option<String>) -> CommandResult<String> {
+        let co_started_bat_folder = BatFolder::CodeOverhaulStarted;
+        let co_finished_bat_folder = BatFolder::CodeOverhaulFinished;
+        let mut started_files_names = co_started_bat_folder
+            .get_all_files_names(true, None, None)
+            .change_context(CommandError)?;
+        let mut finished_files_names = co_finished_bat_folder
+            .get_all_files_names(true, None, None)
+            .change_context(CommandError)?;
+        if started_files_names.is_empty() && finished_files_names.is_empty() {
+            return Err(Report::new(CommandError)
+                .attach_printable("code-overhaul's to-review and finished folders are empty"));
+        }
+
+        let mut co_files_names = vec![];
+        co_files_names.append(&mut started_files_names.clone());
+        co_files_names.append(&mut finished_files_names.clone());
+        co_files_names.sort();
+
+        let entrypoint_name = match entry_point_name {
+            None => {
+                let prompt_text = "Select the co file to deploy".to_string();
+                let selection = BatDialoguer::select(prompt_text, co_files_names.clone(), None)?;
+                let selected_file_name = co_files_names[selection].clone();
+                let entrypoint_name = selected_file_name.trim_end_matches(".md").to_string();
+                entrypoint_name
+            }
+            Some(ep_name) => {
+                let entrypoint_name = ep_name.trim_end_matches(".md").to_string();
+                let co_file_name = format!("{}.md", entrypoint_name.clone());
+                if !co_files_names.contains(&co_file_name) {
+                    return Err(Report::new(CommandError).attach_printable(format!(
+                        "code-overhaul's file with name {} not found on {} and {} folders",
+                        co_file_name.clone(),
+                        "to-review".bright_red(),
+                        "finished".bright_red()
+                    )));
+                }
+                entrypoint_name
+            }
+        };
+        Ok(entrypoint_name)
+    }
+
+    pub fn prompt_signers_info(co_parser: &CodeOverhaulParser) -> CommandResult<Vec<SignerInfo>> {
+        // a missing section would deploy the entry point as permissionless
+        co_parser
+            .get_built_in_section_content(CodeOverhaulSection::Signers)
+            .change_context(CommandError)?;
+        let mut signers_info: Vec<SignerInfo> = vec![];
+        if !co_parser.signers.is_empty() {
+            for signer in co_parser.signers.clone().into_iter() {
+                let prompt_text = format!(
+                    "is the signer {} a validated signer?",
+                    signer.name.to_string().red()
+                );
+                let is_validated =
+                    BatDialoguer::select_yes_or_no(prompt_text).change_context(CommandError)?;
+                let signer_type = if is_validated {
+                    SignerType::Validated
+                } else {
+                    SignerType::NotValidated
+                };
+
+                let signer_title = if is_validated {
+                    format!("Validated signer:<br> <strong>{}</strong>", signer.name)
+                } else {
+                    format!("Not validated signer:<br> <strong>{}</strong>", signer.name)
+                };
+
+                signers_info.push(SignerInfo {
+                    signer_text: signer_title,
+                    sticky_note_id: "".to_string(),
+                    user_figure_id: "".to_string(),
+                    signer_type,
+                })
+            }
+        } else {
+            // no signers, push template signer
+            signers_info.push(SignerInfo {
+                signer_text: SignerType::Permissionless.to_string(),
+                sticky_note_id: "".to_string(),
+                user_figure_id: "".to_string(),
+                signer_type: SignerType::Permissionless,
+            })
+        }
+        Ok(signers_info)
+    }
+
+    /// Resizes the screenshots to their slot width and, with auto-layout, packs them in the frame
+    pub async fn arrange_co_screenshots(
+        frame_layout: &MiroFrameLayout,
+        mut screenshot_images: Vec<(MiroCodeOverhaulConfig, MiroImage)>,
+    ) -> CommandResult<()> {
+        for (co_config, image) in screenshot_images.iter_mut() {
+            if let Some(width) = frame_layout.get_width(co_config) {
+                image
+                    .update_width(width)
+                    .await
+                    .change_context(CommandError)?;
+            }
+        }
+        if !frame_layout.auto_layout {
+            return Ok(());
+        }
+        let item_sizes = screenshot_images
+            .iter()
+            .map(|(_, image)| (image.width, image.height))
+            .collect::<Vec<_>>();
+        let positions = frame_layout.pack_items(&item_sizes);
+        for ((_, image), (x_position, y_position)) in screenshot_images.iter_mut().zip(positions) {
+            image.update_position(x_position, y_position).await;
+        }
+        Ok(())
+    }
+
     pub async fn deploy_miro_frame_for_co(
         entry_point_name: &str,
         entry_point_index: usize,
@@ -1291,12 +1742,19 @@ pub mod miro_command_functions {
         let frame_name = format!("co: {}", entry_point_name);
 
         println!("Creating frame in Miro for {}", entry_point_name.green());
-        let mut miro_frame = MiroFrame::new(&frame_name, MIRO_FRAME_HEIGHT, MIRO_FRAME_WIDTH, 0, 0);
+        let frame_layout = MiroFrameLayout::get_layout().change_context(CommandError)?;
+        let mut miro_frame = MiroFrame::new(
+            &frame_name,
+            frame_layout.frame_height,
+            frame_layout.frame_width,
+            0,
+            0,
+        );
         miro_frame.deploy().await.change_context(CommandError)?;
         let x_modifier = entry_point_index as i64 % MIRO_BOARD_COLUMNS;
         let y_modifier = entry_point_index as i64 / MIRO_BOARD_COLUMNS;
-        let x_position = MIRO_INITIAL_X + (MIRO_FRAME_WIDTH as i64 + 100) * x_modifier;
-        let y_position = MIRO_INITIAL_Y + (MIRO_FRAME_HEIGHT as i64 + 100) * y_modifier;
+        let x_position = MIRO_INITIAL_X + (frame_layout.frame_width as i64 + 100) * x_modifier;
+        let y_position = MIRO_INITIAL_Y + (frame_layout.frame_height as i64 + 100) * y_modifier;
         miro_frame
             .update_position(x_position, y_position)
             .await
@@ -1412,7 +1870,22 @@ pub mod miro_command_functions {
         let formatted_option = function_dependencies
             .clone()
             .into_iter()
-            .map(|dep| get_formatted_path(dep.name, dep.path.clone(), dep.start_line_index))
+            .map(|dep| {
+                let formatted_path =
+                    get_formatted_path(dep.name, dep.path.clone(), dep.start_line_index)?;
+                if function_parser
+                    .ambiguous_dependencies
+                    .contains(&dep.metadata_id)
+                {
+                    Ok(format!(
+                        "{} {}",
+                        formatted_path,
+                        "(ambiguous dispatch)".yellow()
+                    ))
+                } else {
+                    Ok(formatted_path)
+                }
+            })
             .collect::<Result<Vec<_>, _>>()?;
 
         let multi_selection = BatDialoguer::multiselect(
@@ -1486,6 +1959,325 @@ pub mod miro_command_functions {
                 .to_screaming_snake_case()
         )
     }
+
+    pub async fn deploy_signer_sticky_note(
+        signer: &SignerInfo,
+        frame_id: &str,
+        x_position: i64,
+        y_position: i64,
+    ) -> CommandResult<MiroStickyNote> {
+        let mut signer_sticky_note = MiroStickyNote::new(
+            &signer.signer_text,
+            signer.signer_type.get_sticky_note_color(),
+            frame_id,
+            x_position,
+            y_position,
+            MIRO_SIGNER_WIDTH,
+            0,
+        );
+        signer_sticky_note
+            .deploy()
+            .await
+            .change_context(CommandError)?;
+        Ok(signer_sticky_note)
+    }
+
+    pub async fn deploy_signer_figure(
+        frame_id: &str,
+        x_position: i64,
+        y_position: i64,
+    ) -> CommandResult<MiroImage> {
+        let mut user_figure = MiroImage::new_from_url(
+            MIRO_SIGNER_FIGURE_URL,
+            frame_id,
+            x_position,
+            y_position,
+            MIRO_SIGNER_FIGURE_WIDTH,
+        );
+        user_figure.deploy().await.change_context(CommandError)?;
+        Ok(user_figure)
+    }
+
+    /// Screenshots of the mutable program accounts of the entry point, in deploy order
+    pub fn get_state_changes_parsers(
+        entrypoint_parser: &EntrypointParser,
+        frame_title: &str,
+    ) -> CommandResult<Vec<SourceCodeParser>> {
+        let bat_metadata = BatMetadata::read_metadata().change_context(CommandError)?;
+        let context_accounts_metadata = bat_metadata
+            .get_context_accounts_metadata_by_struct_source_code_metadata_id(
+                entrypoint_parser.context_accounts.metadata_id.clone(),
+            )
+            .change_context(CommandError)?;
+        let mut_program_owned_accounts = context_accounts_metadata
+            .context_accounts_info
+            .into_iter()
+            .filter(|ca_info| {
+                ca_info.is_mut
+                    && ca_info.solana_account_type == SolanaAccountType::ProgramStateAccount
+            });
+        let mut state_changes_parsers = vec![];
+        for mut_account in mut_program_owned_accounts {
+            let struct_metadata_vec = SourceCodeMetadata::get_filtered_structs(
+                Some(mut_account.account_struct_name),
+                Some(StructMetadataType::SolanaAccount),
+            )
+            .change_context(CommandError)?;
+
+            if struct_metadata_vec.len() != 1 {
+                return Err(Report::new(CommandError).attach_printable(format!(
+                    "Error looking for Solana Accounts, expected 1 result, got:\n{:#?}",
+                    struct_metadata_vec
+                )));
+            }
+
+            let struct_metadata = struct_metadata_vec[0].clone();
+            state_changes_parsers.push(struct_metadata.to_source_code_parser(Some(
+                parse_screenshot_name(&struct_metadata.name, frame_title),
+            )));
+        }
+        Ok(state_changes_parsers)
+    }
+
+    /// Screenshots of the struct parameters of the handler, in deploy order
+    pub fn get_handler_parameters_parsers(
+        entrypoint_parser: &EntrypointParser,
+        frame_title: &str,
+    ) -> CommandResult<Vec<SourceCodeParser>> {
+        let mut handler_parameters_parsers = vec![];
+        if let Some(handler_meta) = entrypoint_parser.handler.clone() {
+            let handler_function_parser =
+                FunctionParser::new_from_metadata(handler_meta).change_context(CommandError)?;
+            for handler_function_parameter in handler_function_parser.parameters {
+                // parameters are most likely Structs
+                if let Ok(parameter_metadata_vec) = SourceCodeMetadata::get_filtered_structs(
+                    Some(
+                        handler_function_parameter
+                            .parameter_type
+                            .trim_start_matches('&')
+                            .to_string(),
+                    ),
+                    Some(StructMetadataType::Other),
+                ) {
+                    if parameter_metadata_vec.len() == 1 {
+                        let parameter_metadata = parameter_metadata_vec[0].clone();
+                        handler_parameters_parsers.push(parameter_metadata.to_source_code_parser(
+                            Some(parse_screenshot_name(&parameter_metadata.name, frame_title)),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(handler_parameters_parsers)
+    }
+
+    /// Connectors between the signers and the screenshots of a code-overhaul frame, as
+    /// (start item id, end item id, options)
+    pub fn get_co_connections(
+        co_metadata: &MiroCodeOverhaulMetadata,
+    ) -> Vec<(String, String, Option<ConnectorOptions>)> {
+        let mut connections = vec![];
+        for signer in &co_metadata.signers {
+            connections.push((
+                signer.user_figure_id.clone(),
+                signer.sticky_note_id.clone(),
+                None,
+            ));
+            connections.push((
+                signer.sticky_note_id.clone(),
+                co_metadata.entry_point_image_id.clone(),
+                Some(ConnectorOptions {
+                    start_x_position: "100%".to_string(),
+                    start_y_position: "50%".to_string(),
+                    end_x_position: "0%".to_string(),
+                    end_y_position: "50%".to_string(),
+                }),
+            ));
+        }
+        connections.push((
+            co_metadata.entry_point_image_id.clone(),
+            co_metadata.context_accounts_image_id.clone(),
+            None,
+        ));
+        connections.push((
+            co_metadata.context_accounts_image_id.clone(),
+            co_metadata.validations_image_id.clone(),
+            None,
+        ));
+        connections.push((
+            co_metadata.validations_image_id.clone(),
+            co_metadata.handler_image_id.clone(),
+            None,
+        ));
+        connections
+            .into_iter()
+            .filter(|(start_item_id, end_item_id, _)| {
+                !start_item_id.is_empty() && !end_item_id.is_empty()
+            })
+            .collect()
+    }
+
+    /// Deploys a missing item of a code-overhaul frame at its position, stores the new id in the
+    /// metadata and connects it again to the items still on the board
+    pub async fn redeploy_co_item(
+        co_metadata: &mut MiroCodeOverhaulMetadata,
+        co_miro_frame: MiroFrame,
+        tracked_item: &MiroTrackedItem,
+        missing_item_ids: &[String],
+    ) -> CommandResult<()> {
+        let frame_id = co_miro_frame.item_id.clone();
+        let x_position = tracked_item.x_position;
+        let y_position = tracked_item.y_position;
+        let new_item_id = match tracked_item.co_config {
+            MiroCodeOverhaulConfig::Signers => {
+                let signer = &mut co_metadata.signers[tracked_item.index];
+                if tracked_item.item_type == MiroItemType::StickyNote {
+                    signer.sticky_note_id =
+                        deploy_signer_sticky_note(signer, &frame_id, x_position, y_position)
+                            .await?
+                            .item_id;
+                    signer.sticky_note_id.clone()
+                } else {
+                    signer.user_figure_id = deploy_signer_figure(&frame_id, x_position, y_position)
+                        .await?
+                        .item_id;
+                    signer.user_figure_id.clone()
+                }
+            }
+            co_config => {
+                let entrypoint_parser =
+                    EntrypointParser::new_from_name(&co_metadata.entry_point_name)
+                        .change_context(CommandError)?;
+                let screenshot_parser = match co_config {
+                    MiroCodeOverhaulConfig::EntryPoint => Some(
+                        entrypoint_parser
+                            .entry_point_function
+                            .to_source_code_parser(Some(parse_screenshot_name(
+                                &entrypoint_parser.entry_point_function.name,
+                                &co_miro_frame.title,
+                            ))),
+                    ),
+                    MiroCodeOverhaulConfig::Handler => {
+                        let handler_meta = entrypoint_parser.handler.clone().ok_or(
+                            Report::new(CommandError).attach_printable(format!(
+                                "{} doesn't have a handler anymore",
+                                co_metadata.entry_point_name
+                            )),
+                        )?;
+                        Some(
+                            handler_meta.to_source_code_parser(Some(parse_screenshot_name(
+                                &handler_meta.name,
+                                &co_miro_frame.title,
+                            ))),
+                        )
+                    }
+                    MiroCodeOverhaulConfig::StateChanges => Some(
+                        get_state_changes_parsers(&entrypoint_parser, &co_miro_frame.title)?
+                            .get(tracked_item.index)
+                            .cloned()
+                            .ok_or(Report::new(CommandError).attach_printable(format!(
+                                "{} not found for {}",
+                                tracked_item.label, co_metadata.entry_point_name
+                            )))?,
+                    ),
+                    MiroCodeOverhaulConfig::HandlerParameters => Some(
+                        get_handler_parameters_parsers(&entrypoint_parser, &co_miro_frame.title)?
+                            .get(tracked_item.index)
+                            .cloned()
+                            .ok_or(Report::new(CommandError).attach_printable(format!(
+                                "{} not found for {}",
+                                tracked_item.label, co_metadata.entry_point_name
+                            )))?,
+                    ),
+                    _ => None,
+                };
+                let mut image = match screenshot_parser {
+                    Some(screenshot_parser) => screenshot_parser
+                        .deploy_screenshot_to_miro_frame(
+                            co_miro_frame.clone(),
+                            x_position,
+                            y_position,
+                            SourceCodeScreenshotOptions {
+                                include_path: co_config != MiroCodeOverhaulConfig::EntryPoint,
+                                offset_to_start_line: true,
+                                filter_comments: false,
+                                font_size: None,
+                                filters: None,
+                                show_line_number: true,
+                            },
+                        )
+                        .await
+                        .change_context(CommandError)?,
+                    None => {
+                        let co_parser = CodeOverhaulParser::new_from_entry_point_name(
+                            co_metadata.entry_point_name.clone(),
+                        )
+                        .change_context(CommandError)?;
+                        if co_config == MiroCodeOverhaulConfig::ContextAccount {
+                            co_parser
+                                .deploy_new_context_accounts_image_for_miro_co_frame(
+                                    co_miro_frame.clone(),
+                                )
+                                .await
+                                .change_context(CommandError)?
+                        } else {
+                            co_parser
+                                .deploy_new_validations_image_for_miro_co_frame(
+                                    co_miro_frame.clone(),
+                                )
+                                .await
+                                .change_context(CommandError)?
+                        }
+                    }
+                };
+                let frame_layout = MiroFrameLayout::get_layout().change_context(CommandError)?;
+                if let Some(width) = frame_layout.get_width(&co_config) {
+                    image
+                        .update_width(width)
+                        .await
+                        .change_context(CommandError)?;
+                }
+                let new_item_id = image.item_id.clone();
+                match co_config {
+                    MiroCodeOverhaulConfig::EntryPoint => {
+                        co_metadata.entry_point_image_id = new_item_id.clone()
+                    }
+                    MiroCodeOverhaulConfig::ContextAccount => {
+                        co_metadata.context_accounts_image_id = new_item_id.clone()
+                    }
+                    MiroCodeOverhaulConfig::Validations => {
+                        co_metadata.validations_image_id = new_item_id.clone()
+                    }
+                    MiroCodeOverhaulConfig::Handler => {
+                        co_metadata.handler_image_id = new_item_id.clone()
+                    }
+                    MiroCodeOverhaulConfig::StateChanges => {
+                        co_metadata.state_changes_image_ids[tracked_item.index] =
+                            new_item_id.clone()
+                    }
+                    MiroCodeOverhaulConfig::HandlerParameters => {
+                        co_metadata.handler_parameters_image_ids[tracked_item.index] =
+                            new_item_id.clone()
+                    }
+                    MiroCodeOverhaulConfig::Signers => unreachable!(),
+                }
+                new_item_id
+            }
+        };
+        for (start_item_id, end_item_id, connector_options) in get_co_connections(co_metadata) {
+            let is_connected = start_item_id == new_item_id || end_item_id == new_item_id;
+            if !is_connected
+                || missing_item_ids.contains(&start_item_id)
+                || missing_item_ids.contains(&end_item_id)
+            {
+                continue;
+            }
+            create_connector(&start_item_id, &end_item_id, connector_options)
+                .await
+                .change_context(CommandError)?;
+        }
+        Ok(())
+    }
 }
 
 #[test]
@@ -1497,3 +2289,73 @@ fn test_enum_display() {
     }
     println!("{:#?}", bat_package_json_command);
 }
+
+#[tokio::test]
+async fn test_sync_against_mock_server() {
+    use crate::config::BatAuditorConfig;
+    use assert_fs::prelude::*;
+    let mock_server = MiroMockServer::start(0).await.unwrap();
+    let project_dir = assert_fs::TempDir::new().unwrap();
+    let bat_config = BatConfig {
+        project_name: "vault-audit".to_string(),
+        miro_board_url: "https://miro.com/app/board/uXjVMock=/".to_string(),
+        auditor_names: vec!["alice".to_string()],
+        program_name: "vault".to_string(),
+        ..Default::default()
+    };
+    let bat_auditor_config = BatAuditorConfig {
+        auditor_name: "alice".to_string(),
+        miro_oauth_access_token: "token".to_string(),
+        miro_api_base_url: mock_server.base_url.clone(),
+        ..Default::default()
+    };
+    project_dir
+        .child("Bat.toml")
+        .write_str(&toml::to_string(&bat_config).unwrap())
+        .unwrap();
+    project_dir
+        .child("BatAuditor.toml")
+        .write_str(&toml::to_string(&bat_auditor_config).unwrap())
+        .unwrap();
+    // the command reads the project files of the working directory, so it runs in a child
+    // process started in the project, instead of changing the directory of every test
+    let project_path = project_dir.path().to_path_buf();
+    let sync_output = tokio::process::Command::new(std::env::current_exe().unwrap())
+        .args([
+            "commands::miro_commands::sync_in_current_dir",
+            "--exact",
+            "--ignored",
+        ])
+        .current_dir(project_path)
+        .output()
+        .await
+        .unwrap();
+    assert!(
+        sync_output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&sync_output.stdout)
+    );
+    let requests = mock_server.get_requests();
+    // the board is listed once, instead of a request per tracked item
+    assert!(requests
+        .iter()
+        .any(|request| request.method == "GET" && request.path.contains("items?limit=50")));
+    assert!(!requests
+        .iter()
+        .any(|request| request.method == "GET" && request.path.contains("items/")));
+    assert!(mock_server.get_created_items().is_empty());
+    // the mock server is a dev tool, not a package.json script
+    assert!(
+        !MiroCommand::get_bat_package_json_commands("miro".to_string())
+            .command_options
+            .iter()
+            .any(|command_option| command_option.command_option_name == "mock-server")
+    );
+}
+
+#[tokio::test]
+#[ignore = "run by test_sync_against_mock_server, in the project directory"]
+async fn sync_in_current_dir() {
+    BatMetadata::new_empty().save_metadata().unwrap();
+    MiroCommand::Sync.execute_command().await.unwrap();
+}
@@ -1,9 +1,13 @@
+pub mod candidate_commands;
 pub mod co_commands;
 pub mod finding_commands;
+pub mod log_commands;
 pub mod miro_commands;
 pub mod project_commands;
+pub mod questions_commands;
 pub mod repository_commands;
 pub mod sonar_commands;
+pub mod threat_model_commands;
 pub mod tools_commands;
 
 use crate::batbelt::BatEnumerator;
@@ -32,11 +36,17 @@ where
     fn execute_command(&self) -> CommandResult<()>;
     fn check_metadata_is_initialized(&self) -> bool;
     fn check_correct_branch(&self) -> bool;
+
+    /// False for the commands not run by the auditor, like the ones called by git
+    fn is_package_json_command(&self) -> bool {
+        true
+    }
     fn get_bat_package_json_commands(command_name: String) -> BatPackageJsonCommand {
         let command_with_options_regex = Regex::new(r"\w+ \{\s*([\s\w]+: false,\n)+\}").unwrap();
         let boolean_flag_regex = Regex::new(r"\w+: false,").unwrap();
 
         let commands_vec = Self::iter()
+            .filter(|command| command.is_package_json_command())
             .map(|command| format!("{command:#?}"))
             .collect::<Vec<String>>();
 
@@ -10,7 +10,8 @@ use error_stack::{IntoReport, Result};
 
 use crate::batbelt;
 
-use crate::batbelt::git::{GitAction, GitCommit};
+use crate::batbelt::git::repository::BatRepository;
+use crate::batbelt::git::{GitAction, GitCommit, BAT_DEVELOP_BRANCH_NAME, BAT_MAIN_BRANCH_NAME};
 
 use crate::batbelt::parser::entrypoint_parser::EntrypointParser;
 use crate::batbelt::path::BatFile::GitIgnore;
@@ -22,14 +23,15 @@ use clap::Subcommand;
 
 use crate::commands::sonar_commands::SonarCommand;
 use std::path::Path;
-use std::process::Command;
 
 #[derive(
     Subcommand, Debug, strum_macros::Display, PartialEq, Clone, strum_macros::EnumIter, Default,
 )]
 pub enum ProjectCommands {
+    New {
+        bare_repository_path: Option<String>,
+    },
     #[default]
-    New,
     Reload,
 }
 impl BatEnumerator for ProjectCommands {}
@@ -37,7 +39,9 @@ impl BatEnumerator for ProjectCommands {}
 impl BatCommandEnumerator for ProjectCommands {
     fn execute_command(&self) -> CommandResult<()> {
         match self {
-            ProjectCommands::New => self.new_bat_project(),
+            ProjectCommands::New {
+                bare_repository_path,
+            } => self.new_bat_project(bare_repository_path.clone()),
             ProjectCommands::Reload => self.reload_bat_project(),
         }
     }
@@ -84,6 +88,9 @@ impl ProjectCommands {
             project_commands_functions::update_co_to_review()?;
             project_commands_functions::update_package_json()?;
             project_commands_functions::update_git_ignore()?;
+            GitAction::RegisterMetadataMergeDriver
+                .execute_action()
+                .change_context(CommandError)?;
 
             GitCommit::BatReload
                 .create_commit()
@@ -94,7 +101,19 @@ impl ProjectCommands {
         Ok(())
     }
 
-    fn new_bat_project(&self) -> Result<(), CommandError> {
+    fn new_bat_project(&self, bare_repository_path: Option<String>) -> Result<(), CommandError> {
+        // relative to the current folder, before moving to the project folder
+        let bare_repository_path = match bare_repository_path {
+            Some(path) => Some(
+                env::current_dir()
+                    .into_report()
+                    .change_context(CommandError)?
+                    .join(path)
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+            None => None,
+        };
         let bat_config = BatConfig::new_with_prompt().change_context(CommandError)?;
         println!("Creating {:#?} project", bat_config);
         TemplateGenerator
@@ -115,6 +134,10 @@ impl ProjectCommands {
             println!("Initializing project repository");
             project_commands_functions::initialize_project_repository()?;
             println!("Project repository successfully initialized");
+        } else {
+            GitAction::RegisterMetadataMergeDriver
+                .execute_action()
+                .change_context(CommandError)?;
         }
 
         PackageJsonTemplate::create_package_json(None).change_context(CommandError)?;
@@ -134,7 +157,7 @@ impl ProjectCommands {
         .execute_command()?;
 
         // create auditors branches from develop
-        for auditor_name in bat_config.auditor_names {
+        for auditor_name in bat_config.auditor_names.clone() {
             BatFile::BatAuditorToml
                 .create_empty(false)
                 .change_context(CommandError)?;
@@ -144,6 +167,7 @@ impl ProjectCommands {
                 use_code_editor: false,
                 code_editor: Default::default(),
                 external_bat_metadata: vec![],
+                miro_api_base_url: "".to_string(),
             };
             bat_auditor_config.save().change_context(CommandError)?;
 
@@ -154,7 +178,17 @@ impl ProjectCommands {
                 .change_context(CommandError)?;
         }
 
+        if let Some(bare_repository_path) = bare_repository_path {
+            project_commands_functions::push_to_bare_repository(
+                &bare_repository_path,
+                &bat_config,
+            )?;
+        }
+
         BatAuditorConfig::new_with_prompt().change_context(CommandError)?;
+        GitAction::CheckoutAuditorBranch
+            .execute_action()
+            .change_context(CommandError)?;
 
         BatFile::ProgramLib
             .open_in_editor(false, None)
@@ -174,25 +208,19 @@ mod project_commands_functions {
         let auditor_project_branch_exists =
             batbelt::git::check_if_branch_exists(&auditor_project_branch_name)
                 .change_context(CommandError)?;
+        let bat_repository = BatRepository::open().change_context(CommandError)?;
         if !auditor_project_branch_exists {
             println!("Creating branch {:?}", auditor_project_branch_name);
-            // checkout develop to create auditor project branch from there
-            Command::new("git")
-                .args(["checkout", "develop"])
-                .output()
-                .unwrap();
-            Command::new("git")
-                .args(["checkout", "-b", &auditor_project_branch_name])
-                .output()
-                .unwrap();
+            // create the auditor project branch from develop
+            bat_repository
+                .create_branch(&auditor_project_branch_name, BAT_DEVELOP_BRANCH_NAME)
+                .change_context(CommandError)?;
         } else {
             println!("Checking out {:?} branch", auditor_project_branch_name);
-            // checkout auditor branch
-            Command::new("git")
-                .args(["checkout", auditor_project_branch_name.as_str()])
-                .output()
-                .unwrap();
         }
+        bat_repository
+            .checkout_branch(&auditor_project_branch_name)
+            .change_context(CommandError)?;
         TemplateGenerator
             .create_folders_for_current_auditor()
             .change_context(CommandError)?;
@@ -285,6 +313,9 @@ mod project_commands_functions {
         GitAction::Init
             .execute_action()
             .change_context(CommandError)?;
+        GitAction::RegisterMetadataMergeDriver
+            .execute_action()
+            .change_context(CommandError)?;
 
         println!("Adding project repository as remote");
         GitAction::RemoteAddProjectRepo
@@ -301,11 +332,41 @@ mod project_commands_functions {
 
         println!("Creating develop branch");
         GitAction::CreateBranch {
-            branch_name: "develop".to_string(),
+            branch_name: BAT_DEVELOP_BRANCH_NAME.to_string(),
         }
         .execute_action()
         .change_context(CommandError)?;
 
         Ok(())
     }
+
+    pub fn push_to_bare_repository(
+        bare_repository_path: &str,
+        bat_config: &BatConfig,
+    ) -> CommandResult<()> {
+        println!(
+            "Pushing branches to the bare repository {}",
+            bare_repository_path.green()
+        );
+        let mut branch_names = vec![
+            BAT_MAIN_BRANCH_NAME.to_string(),
+            BAT_DEVELOP_BRANCH_NAME.to_string(),
+        ];
+        branch_names.extend(
+            bat_config
+                .auditor_names
+                .iter()
+                .map(|auditor_name| format!("{}-{}", auditor_name, bat_config.project_name)),
+        );
+        BatRepository::open()
+            .and_then(|bat_repository| {
+                bat_repository.push_to_bare_repository(bare_repository_path, &branch_names)
+            })
+            .change_context(CommandError)?;
+        println!(
+            "Auditors can clone the project with {}",
+            format!("git clone {}", bare_repository_path).green()
+        );
+        Ok(())
+    }
 }
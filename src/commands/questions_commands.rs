@@ -0,0 +1,208 @@
+use crate::batbelt::bat_dialoguer::BatDialoguer;
+use crate::batbelt::git::GitCommit;
+use crate::batbelt::metadata::{BatMetadata, BatMetadataType};
+use crate::batbelt::path::BatFile;
+use crate::batbelt::templates::open_questions_template::{
+    insert_code_overhaul_notes, OpenQuestion, OpenQuestionsTemplate,
+};
+use crate::batbelt::templates::sidecar_template::SidecarTemplate;
+use crate::batbelt::BatEnumerator;
+use crate::commands::{BatCommandEnumerator, CommandError, CommandResult};
+use crate::config::{BatAuditorConfig, BatConfig};
+use clap::Subcommand;
+use colored::Colorize;
+use error_stack::{Report, ResultExt};
+
+#[derive(
+    Subcommand, Debug, strum_macros::Display, PartialEq, Clone, strum_macros::EnumIter, Default,
+)]
+pub enum QuestionsCommand {
+    /// Adds an open question about an entry point or a source code item
+    #[default]
+    Add,
+    /// Exports the open questions grouped by entry point, ready to send to the client
+    Export {
+        /// File to write the questions to, notes/<auditor>-notes/open_questions_client.md by default
+        #[arg(long)]
+        output_path: Option<String>,
+    },
+    /// Imports the client answers from a document with the export format
+    Import {
+        /// Answered document, notes/<auditor>-notes/open_questions_client.md by default
+        #[arg(long)]
+        input_path: Option<String>,
+    },
+}
+
+impl BatEnumerator for QuestionsCommand {}
+
+impl BatCommandEnumerator for QuestionsCommand {
+    fn execute_command(&self) -> CommandResult<()> {
+        match self {
+            QuestionsCommand::Add => self.add_question(),
+            QuestionsCommand::Export { output_path } => self.export_questions(output_path.clone()),
+            QuestionsCommand::Import { input_path } => self.import_answers(input_path.clone()),
+        }
+    }
+
+    fn check_metadata_is_initialized(&self) -> bool {
+        matches!(self, QuestionsCommand::Add)
+    }
+
+    fn check_correct_branch(&self) -> bool {
+        true
+    }
+}
+
+impl QuestionsCommand {
+    fn add_question(&self) -> CommandResult<()> {
+        let mut open_questions = OpenQuestionsTemplate::read().change_context(CommandError)?;
+        let question = BatDialoguer::input("Question:".to_string())?;
+        let mut new_question = OpenQuestion {
+            question,
+            author: BatAuditorConfig::get_config()
+                .change_context(CommandError)?
+                .auditor_name,
+            ..Default::default()
+        };
+        if BatDialoguer::select_yes_or_no("Is the question about an entry point?".to_string())? {
+            let entry_points = BatMetadata::read_metadata()
+                .change_context(CommandError)?
+                .entry_points;
+            let entry_point_names = entry_points
+                .iter()
+                .map(|entry_point| entry_point.name.clone())
+                .collect::<Vec<_>>();
+            let selection = BatDialoguer::select(
+                "Select the entry point:".to_string(),
+                entry_point_names,
+                None,
+            )?;
+            new_question.entry_point = entry_points[selection].name.clone();
+            new_question.metadata_id = entry_points[selection].metadata_id.clone();
+        }
+        if BatDialoguer::select_yes_or_no("Do you want to link a source code item?".to_string())? {
+            let source_code_location =
+                BatMetadataType::prompt_source_code_location().change_context(CommandError)?;
+            new_question.metadata_id = source_code_location.metadata_id;
+            new_question.location = source_code_location.name;
+            new_question.permalink = source_code_location.permalink;
+        }
+        let question_id = open_questions.add_question(new_question);
+        open_questions.save().change_context(CommandError)?;
+        GitCommit::UpdateOpenQuestions {
+            action: format!("Q{} added", question_id),
+            co_bat_files: vec![],
+        }
+        .create_commit()
+        .change_context(CommandError)?;
+        println!("Open question Q{} added", question_id);
+        Ok(())
+    }
+
+    fn export_questions(&self, output_path: Option<String>) -> CommandResult<()> {
+        let mut open_questions = OpenQuestionsTemplate::read().change_context(CommandError)?;
+        if open_questions.questions.is_empty() {
+            return Err(Report::new(CommandError).attach_printable("There are no open questions"));
+        }
+        let project_name = BatConfig::get_config()
+            .change_context(CommandError)?
+            .project_name;
+        let export_content = open_questions.get_export_content(&project_name);
+        let export_file = Self::get_export_file(output_path);
+        export_file
+            .write_content(false, &export_content)
+            .change_context(CommandError)?;
+        open_questions.save().change_context(CommandError)?;
+        GitCommit::UpdateOpenQuestions {
+            action: "exported".to_string(),
+            co_bat_files: vec![],
+        }
+        .create_commit()
+        .change_context(CommandError)?;
+        println!(
+            "Open questions exported to {}",
+            export_file
+                .get_path(false)
+                .change_context(CommandError)?
+                .green()
+        );
+        Ok(())
+    }
+
+    fn import_answers(&self, input_path: Option<String>) -> CommandResult<()> {
+        let mut open_questions = OpenQuestionsTemplate::read().change_context(CommandError)?;
+        let export_content = Self::get_export_file(input_path)
+            .read_content(false)
+            .change_context(CommandError)?;
+        let answered_ids = open_questions
+            .merge_export_content(&export_content)
+            .change_context(CommandError)?;
+        if answered_ids.is_empty() {
+            println!("No new answers to import");
+            return Ok(());
+        }
+        open_questions.save().change_context(CommandError)?;
+        let mut entry_point_names: Vec<String> = vec![];
+        for question in &open_questions.questions {
+            if answered_ids.contains(&question.id)
+                && !question.entry_point.is_empty()
+                && !entry_point_names.contains(&question.entry_point)
+            {
+                entry_point_names.push(question.entry_point.clone());
+            }
+        }
+        // the to-review files get the answers when they are started
+        let mut co_bat_files = vec![];
+        for entry_point_name in entry_point_names {
+            let co_bat_file = [
+                BatFile::CodeOverhaulStarted {
+                    file_name: entry_point_name.clone(),
+                },
+                BatFile::CodeOverhaulFinished {
+                    file_name: entry_point_name.clone(),
+                },
+            ]
+            .into_iter()
+            .find(|co_bat_file| co_bat_file.file_exists().unwrap_or(false));
+            if let Some(co_bat_file) = co_bat_file {
+                let co_content = co_bat_file
+                    .read_content(false)
+                    .change_context(CommandError)?;
+                co_bat_file
+                    .write_content(
+                        false,
+                        &insert_code_overhaul_notes(
+                            &co_content,
+                            &open_questions.get_code_overhaul_notes(&entry_point_name),
+                        ),
+                    )
+                    .change_context(CommandError)?;
+                co_bat_files.push(co_bat_file);
+            }
+        }
+        GitCommit::UpdateOpenQuestions {
+            action: format!("{} answers imported", answered_ids.len()),
+            co_bat_files,
+        }
+        .create_commit()
+        .change_context(CommandError)?;
+        println!(
+            "Answers imported for {}",
+            answered_ids
+                .iter()
+                .map(|id| format!("Q{}", id))
+                .collect::<Vec<_>>()
+                .join(", ")
+                .green()
+        );
+        Ok(())
+    }
+
+    fn get_export_file(path: Option<String>) -> BatFile {
+        match path {
+            Some(file_path) => BatFile::Generic { file_path },
+            None => BatFile::OpenQuestionsExport,
+        }
+    }
+}
@@ -1,9 +1,14 @@
 use crate::batbelt;
 use crate::batbelt::command_line::execute_command;
 
-use crate::batbelt::git::{get_current_branch_name, get_not_committed_files, GitCommit};
+use crate::batbelt::git::repository::BatRepository;
+use crate::batbelt::git::{
+    get_current_branch_name, get_not_committed_files, GitCommit, BAT_DEVELOP_BRANCH_NAME,
+    BAT_MAIN_BRANCH_NAME,
+};
 
 use crate::batbelt::bat_dialoguer::BatDialoguer;
+use crate::batbelt::metadata::metadata_merge::BatMetadataMerge;
 use crate::batbelt::path::{BatFile, BatFolder};
 use crate::batbelt::BatEnumerator;
 use crate::commands::{BatCommandEnumerator, CommandError, CommandResult};
@@ -37,15 +42,24 @@ pub enum RepositoryCommand {
     UpdateCodeOverhaulFile,
     /// Creates a commit for the code_overhaul_summary.md file
     UpdateCodeOverhaulSummary,
+    /// Three-way merge of BatMetadata.json, called by git as merge driver
+    MergeMetadata {
+        /// common ancestor version (%O)
+        base_path: String,
+        /// current branch version (%A), receives the merge result
+        ours_path: String,
+        /// other branch version (%B)
+        theirs_path: String,
+    },
 }
 
 impl BatEnumerator for RepositoryCommand {}
 
 impl BatCommandEnumerator for RepositoryCommand {
     fn execute_command(&self) -> CommandResult<()> {
-        self.check_develop_exists()?;
         match self {
             RepositoryCommand::UpdateBranches => {
+                self.check_develop_exists()?;
                 self.merge_all_to_develop()?;
                 self.merge_develop_to_all()
             }
@@ -53,6 +67,7 @@ impl BatCommandEnumerator for RepositoryCommand {
                 self.fetch_remote_branches(*select_all)
             }
             RepositoryCommand::DeleteLocalBranches { select_all } => {
+                self.check_develop_exists()?;
                 self.delete_local_branches(*select_all)
             }
             RepositoryCommand::UpdateNotes => GitCommit::Notes
@@ -60,6 +75,11 @@ impl BatCommandEnumerator for RepositoryCommand {
                 .change_context(CommandError),
             RepositoryCommand::UpdateCodeOverhaulFile => self.execute_update_co_file(),
             RepositoryCommand::UpdateCodeOverhaulSummary => self.update_code_overhaul_summary(),
+            RepositoryCommand::MergeMetadata {
+                base_path,
+                ours_path,
+                theirs_path,
+            } => self.merge_metadata(base_path, ours_path, theirs_path),
         }
     }
 
@@ -73,9 +93,39 @@ impl BatCommandEnumerator for RepositoryCommand {
             _ => false,
         }
     }
+
+    fn is_package_json_command(&self) -> bool {
+        !matches!(self, RepositoryCommand::MergeMetadata { .. })
+    }
 }
 
 impl RepositoryCommand {
+    fn merge_metadata(
+        &self,
+        base_path: &str,
+        ours_path: &str,
+        theirs_path: &str,
+    ) -> CommandResult<()> {
+        let metadata_merge = BatMetadataMerge::merge_files(base_path, ours_path, theirs_path)
+            .change_context(CommandError)?;
+        if metadata_merge.conflicts.is_empty() {
+            return Ok(());
+        }
+        let conflicts = metadata_merge
+            .conflicts
+            .iter()
+            .map(|conflict| format!("- {}", conflict.get_description()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        // a failed driver leaves BatMetadata.json as conflicted, with ours values on the conflicts
+        Err(Report::new(CommandError).attach_printable(format!(
+            "{} conflicts merging {}, the current branch values were kept:\n{}",
+            metadata_merge.conflicts.len(),
+            "BatMetadata.json".red(),
+            conflicts
+        )))
+    }
+
     fn update_code_overhaul_summary(&self) -> CommandResult<()> {
         GitCommit::UpdateCOSummary
             .create_commit()
@@ -133,12 +183,7 @@ impl RepositoryCommand {
             let selected_branch = &branches_list.clone()[selection];
             println!("Fetching {}", selected_branch.green());
             log::debug!("selected_branch to fetch: {}", selected_branch);
-            execute_command(
-                "git",
-                &["checkout", selected_branch.trim_start_matches("origin/")],
-                false,
-            )
-            .change_context(CommandError)?;
+            self.checkout_branch(selected_branch.trim_start_matches("origin/"))?;
         }
         self.checkout_branch(&current_branch)?;
         Ok(())
@@ -158,7 +203,8 @@ impl RepositoryCommand {
             let selected_branch = &branches_list.clone()[selection];
             println!("Deleting {}", selected_branch.green());
             log::debug!("selected_branch to delete: {}", selected_branch);
-            execute_command("git", &["branch", "-D", selected_branch], false)
+            BatRepository::open()
+                .and_then(|bat_repository| bat_repository.delete_branch(selected_branch))
                 .change_context(CommandError)?;
         }
         Ok(())
@@ -167,10 +213,10 @@ impl RepositoryCommand {
     fn check_develop_exists(&self) -> Result<(), CommandError> {
         let branches_list = batbelt::git::get_local_branches().change_context(CommandError)?;
         if !branches_list
-            .lines()
-            .any(|line| line.trim_start_matches('*').trim_start() == "develop")
+            .iter()
+            .any(|branch_name| branch_name == BAT_DEVELOP_BRANCH_NAME)
         {
-            log::debug!("branches_list:\n{}", branches_list);
+            log::debug!("branches_list:\n{:#?}", branches_list);
             return Err(Report::new(CommandError).attach_printable("develop branch not found"));
         }
         Ok(())
@@ -178,16 +224,11 @@ impl RepositoryCommand {
 
     fn get_local_branches_filtered(&self) -> Result<Vec<String>, CommandError> {
         let branches_list = batbelt::git::get_local_branches().change_context(CommandError)?;
-        log::debug!("local_branches from batbelt::git: \n{}", branches_list);
+        log::debug!("local_branches from batbelt::git: \n{:#?}", branches_list);
         let list = branches_list
-            .lines()
-            .filter_map(|branch| {
-                let branch_name = branch.trim().trim_start_matches('*').trim();
-                if branch_name != "main" && branch_name != "develop" {
-                    Some(branch_name.to_string())
-                } else {
-                    None
-                }
+            .into_iter()
+            .filter(|branch_name| {
+                branch_name != BAT_MAIN_BRANCH_NAME && branch_name != BAT_DEVELOP_BRANCH_NAME
             })
             .collect::<Vec<_>>();
         log::debug!("filtered branches_list: \n{:#?}", list);
@@ -196,19 +237,12 @@ impl RepositoryCommand {
 
     fn get_remote_branches_filtered(&self) -> Result<Vec<String>, CommandError> {
         let branches_list = batbelt::git::get_remote_branches().change_context(CommandError)?;
-        log::debug!("remote_branches from batbelt::git: \n{}", branches_list);
+        log::debug!("remote_branches from batbelt::git: \n{:#?}", branches_list);
         let list = branches_list
-            .lines()
-            .filter_map(|branch| {
-                let branch_name = branch.trim().trim_start_matches('*').trim();
-                if branch_name != "origin/main"
-                    && branch_name != "origin/develop"
-                    && branch_name.split(" ->").next().unwrap() != "origin/HEAD"
-                {
-                    Some(branch_name.to_string())
-                } else {
-                    None
-                }
+            .into_iter()
+            .filter(|branch_name| {
+                *branch_name != format!("origin/{}", BAT_MAIN_BRANCH_NAME)
+                    && *branch_name != format!("origin/{}", BAT_DEVELOP_BRANCH_NAME)
             })
             .collect::<Vec<_>>();
         log::debug!("filtered remote_branches: \n{:#?}", list);
@@ -273,8 +307,10 @@ impl RepositoryCommand {
     }
 
     fn checkout_branch(&self, branch_name: &str) -> Result<(), CommandError> {
-        execute_command("git", &["checkout", branch_name], false).change_context(CommandError)?;
-        Ok(())
+        BatRepository::open()
+            .and_then(|bat_repository| bat_repository.checkout_branch(branch_name))
+            .change_context(CommandError)
+            .attach_printable(format!("Error checking out {}", branch_name))
     }
 }
 
@@ -966,3 +1002,16 @@ fn test_get_local_branches_filtered() {
 //     //     "|KS-01|High|This is the description|Open|"
 //     // );
 // }
+
+#[test]
+fn test_merge_metadata_not_in_package_json() {
+    let package_json_command =
+        RepositoryCommand::get_bat_package_json_commands("repository".to_string());
+    let command_option_names = package_json_command
+        .command_options
+        .iter()
+        .map(|command_option| command_option.command_option_name.as_str())
+        .collect::<Vec<_>>();
+    assert!(command_option_names.contains(&"update-branches"));
+    assert!(!command_option_names.contains(&"merge-metadata"));
+}
@@ -0,0 +1,41 @@
+use crate::batbelt::git::GitCommit;
+use crate::batbelt::metadata::BatMetadata;
+use crate::batbelt::path::BatFile;
+use crate::batbelt::templates::sidecar_template::SidecarTemplate;
+use crate::batbelt::templates::threat_model_template::ThreatModelTemplate;
+use crate::commands::{CommandError, CommandResult};
+use colored::Colorize;
+use error_stack::ResultExt;
+
+/// Fills the threat model from the BatMetadata, keeping the edits of a previous run
+pub fn generate_threat_model() -> CommandResult<()> {
+    let bat_metadata = BatMetadata::read_metadata().change_context(CommandError)?;
+    let generated_threat_model = ThreatModelTemplate::new_from_metadata(&bat_metadata);
+    let previous_threat_model =
+        match ThreatModelTemplate::read_sidecar().change_context(CommandError)? {
+            Some(previous_threat_model) => Some(previous_threat_model),
+            // first run, the threat_modeling.md may have been filled by hand
+            None => ThreatModelTemplate::read_markdown().change_context(CommandError)?,
+        };
+    let threat_model = match previous_threat_model {
+        Some(previous_threat_model) => generated_threat_model.merge_previous(previous_threat_model),
+        None => generated_threat_model,
+    };
+    threat_model.save().change_context(CommandError)?;
+    GitCommit::ThreatModel
+        .create_commit()
+        .change_context(CommandError)?;
+    println!(
+        "Threat model updated with {} assets, {} actors and {} scenarios, edit {} and run {} again to update {}",
+        threat_model.assets.len(),
+        threat_model.actors.len(),
+        threat_model.scenarios.len(),
+        BatFile::ThreatModelingToml
+            .get_path(false)
+            .change_context(CommandError)?
+            .green(),
+        "bat-cli threat-model".green(),
+        "threat_modeling.md".green()
+    );
+    Ok(())
+}
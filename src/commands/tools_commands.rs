@@ -1,5 +1,6 @@
 use crate::batbelt::bat_dialoguer::BatDialoguer;
 use crate::batbelt::command_line::CodeEditor;
+use std::collections::BTreeMap;
 use std::env;
 
 use crate::batbelt::path::{BatFile, BatFolder};
@@ -21,7 +22,9 @@ use crate::batbelt::templates::package_json_template::PackageJsonTemplate;
 
 use crate::batbelt;
 use crate::batbelt::metadata::enums_source_code_metadata::EnumSourceCodeMetadata;
+use crate::batbelt::parser::cargo_parser::CargoParser;
 use crate::batbelt::parser::entrypoint_parser::EntrypointParser;
+use crate::batbelt::parser::function_parser::FunctionParser;
 use crate::config::BatAuditorConfig;
 use log::Level;
 
@@ -40,6 +43,8 @@ pub enum ToolCommand {
     GetMetadataById,
     /// Counts the to-review, started, finished and total co files
     CountCodeOverhaul,
+    /// Prints the external crates APIs used by every entry point, flagging the crates listed in BatAdvisories.toml
+    ExternalDependencies,
 }
 
 impl BatEnumerator for ToolCommand {}
@@ -52,6 +57,7 @@ impl BatCommandEnumerator for ToolCommand {
             ToolCommand::GetMetadataById => self.execute_get_metadata_by_id(),
             ToolCommand::OpenCodeOverhaulFile => self.execute_open_co(),
             ToolCommand::CountCodeOverhaul => self.execute_count_co_files(),
+            ToolCommand::ExternalDependencies => self.execute_external_dependencies(),
         }
     }
 
@@ -62,6 +68,7 @@ impl BatCommandEnumerator for ToolCommand {
             ToolCommand::GetMetadataById => true,
             ToolCommand::OpenCodeOverhaulFile => true,
             ToolCommand::CountCodeOverhaul => false,
+            ToolCommand::ExternalDependencies => true,
         }
     }
 
@@ -72,6 +79,7 @@ impl BatCommandEnumerator for ToolCommand {
             ToolCommand::GetMetadataById => false,
             ToolCommand::OpenCodeOverhaulFile => false,
             ToolCommand::CountCodeOverhaul => false,
+            ToolCommand::ExternalDependencies => false,
         }
     }
 }
@@ -329,4 +337,101 @@ impl ToolCommand {
             .len();
         Ok((to_review_count, started_count, finished_count))
     }
+
+    fn execute_external_dependencies(&self) -> error_stack::Result<(), CommandError> {
+        let cargo_parser = CargoParser::new_from_program_lib().change_context(CommandError)?;
+        let advisories = CargoParser::get_crate_advisories().change_context(CommandError)?;
+        let entrypoint_names =
+            EntrypointParser::get_entrypoint_names(true).change_context(CommandError)?;
+        for entrypoint_name in entrypoint_names {
+            let ep_parser =
+                EntrypointParser::new_from_name(&entrypoint_name).change_context(CommandError)?;
+            let mut pending_to_check = vec![ep_parser.entry_point_function.clone()];
+            if let Some(handler) = ep_parser.handler.clone() {
+                pending_to_check.push(handler);
+            }
+            let bat_metadata = BatMetadata::read_metadata().change_context(CommandError)?;
+            let mut checked_functions: Vec<String> = vec![];
+            let mut crates_calls: BTreeMap<String, Vec<String>> = BTreeMap::new();
+            while let Some(function_metadata) = pending_to_check.pop() {
+                if checked_functions.contains(&function_metadata.metadata_id) {
+                    continue;
+                }
+                checked_functions.push(function_metadata.metadata_id.clone());
+                let function_parser = FunctionParser::new_from_metadata(function_metadata)
+                    .change_context(CommandError)?;
+                for crate_dependency in function_parser.external_crate_dependencies {
+                    let crate_calls = crates_calls
+                        .entry(crate_dependency.crate_name.clone())
+                        .or_default();
+                    if !crate_calls.contains(&crate_dependency.call_path) {
+                        crate_calls.push(crate_dependency.call_path);
+                    }
+                }
+                for dependency_id in function_parser.dependencies {
+                    pending_to_check.push(
+                        bat_metadata
+                            .source_code
+                            .get_function_by_id(dependency_id)
+                            .change_context(CommandError)?,
+                    );
+                }
+            }
+            println!("{}:", entrypoint_name.bright_blue());
+            if crates_calls.is_empty() {
+                println!("  no external crates calls\n");
+                continue;
+            }
+            for (crate_name, crate_calls) in crates_calls {
+                // the metadata may name a crate that is no longer in the Cargo.toml
+                let dependency = match cargo_parser.get_dependency(&crate_name) {
+                    Some(dependency) => dependency,
+                    None => {
+                        println!(
+                            "  {} {} not found in {}, skipping it",
+                            "warning".yellow(),
+                            crate_name,
+                            cargo_parser.cargo_toml_path
+                        );
+                        continue;
+                    }
+                };
+                let crate_version = dependency
+                    .locked_version
+                    .clone()
+                    .unwrap_or(dependency.version_requirement.clone());
+                let workspace_label = if dependency.is_workspace_crate {
+                    " (workspace)"
+                } else {
+                    ""
+                };
+                println!(
+                    "  {}@{}{}: {}",
+                    crate_name.green(),
+                    crate_version,
+                    workspace_label,
+                    crate_calls.join(", ")
+                );
+                if dependency.locked_versions.len() > 1 {
+                    println!(
+                        "    {} Cargo.lock has versions {}, using {} for the {} requirement",
+                        "ambiguous".yellow(),
+                        dependency.locked_versions.join(", "),
+                        crate_version,
+                        dependency.version_requirement
+                    );
+                }
+                for advisory in CargoParser::get_matching_advisories(&dependency, &advisories) {
+                    println!(
+                        "    {} {}: {}",
+                        "advisory".red(),
+                        advisory.affected_versions,
+                        advisory.description
+                    );
+                }
+            }
+            println!();
+        }
+        Ok(())
+    }
 }
@@ -9,13 +9,16 @@ use std::{error::Error, fmt, fs, str};
 
 use crate::batbelt::bat_dialoguer::BatDialoguer;
 use crate::batbelt::command_line::CodeEditor;
+use crate::batbelt::miro::layout::MiroFrameLayout;
 use crate::batbelt::path::BatFile;
 use crate::batbelt::{bat_dialoguer, BatEnumerator};
 
+use crate::batbelt::git::forge::GitForge;
 use crate::batbelt::git::GitCommit;
 use colored::Colorize;
 use error_stack::{FutureExt, IntoReport, Report, Result, ResultExt};
 use figment::error::Kind;
+use inflector::Inflector;
 use normalize_url::normalizer;
 use walkdir::WalkDir;
 
@@ -42,6 +45,8 @@ pub struct BatAuditorConfig {
     pub code_editor: CodeEditor,
     #[serde(default)]
     pub external_bat_metadata: Vec<String>,
+    #[serde(default)]
+    pub miro_api_base_url: String,
 }
 
 impl BatAuditorConfig {
@@ -52,6 +57,7 @@ impl BatAuditorConfig {
             use_code_editor: false,
             code_editor: Default::default(),
             external_bat_metadata: vec![],
+            miro_api_base_url: "".to_string(),
         };
         bat_auditor_config.prompt_auditor_name()?;
         bat_auditor_config.prompt_miro_integration()?;
@@ -197,6 +203,18 @@ impl BatAuditorConfig {
     }
 }
 
+// folders of a Foundry or Hardhat project without contracts to audit
+const SOLIDITY_NOT_CONTRACT_FOLDERS: [&str; 8] = [
+    "lib",
+    "node_modules",
+    "test",
+    "tests",
+    "script",
+    "out",
+    "cache",
+    "artifacts",
+];
+
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
 pub struct BatConfig {
     pub initialized: bool,
@@ -210,8 +228,88 @@ pub struct BatConfig {
     #[serde(default)]
     pub program_name: String,
     pub project_repository_url: String,
+    /// macros that expand to entry points when invoked inside the #[program] module
+    #[serde(default)]
+    pub entry_point_macros: Vec<String>,
+    /// derive or attribute macros that mark a struct as context accounts, besides #[derive(Accounts)]
+    #[serde(default)]
+    pub context_accounts_macros: Vec<String>,
+    /// Anchor programs are parsed from #[program], native programs from the instruction enum
+    #[serde(default)]
+    pub program_framework: ProgramFramework,
+    /// where the code-overhaul diagrams are drawn, a local canvas avoids sharing code with Miro
+    #[serde(default)]
+    pub diagram_backend: DiagramBackend,
+    /// layout of the code-overhaul frames, the default layout is used if not declared
+    #[serde(default)]
+    pub miro_layout: Option<MiroFrameLayout>,
+    /// toml file with the frame layout, takes precedence over miro_layout
+    #[serde(default)]
+    pub miro_layout_file: String,
+    /// forge of the audited repository, detected from the urls when not set. Needed for
+    /// self-hosted instances with an unknown host
+    #[serde(default)]
+    pub git_forge: Option<GitForge>,
+}
+
+#[derive(
+    Default,
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    strum_macros::EnumIter,
+    strum_macros::Display,
+    PartialOrd,
+    PartialEq,
+)]
+pub enum ProgramFramework {
+    #[default]
+    Anchor,
+    Native,
+    Foundry,
+    Hardhat,
 }
 
+impl BatEnumerator for ProgramFramework {}
+
+impl ProgramFramework {
+    /// Files found at the root of a project of the framework
+    pub fn get_project_file_names(&self) -> &'static [&'static str] {
+        match self {
+            ProgramFramework::Anchor => &["Anchor.toml"],
+            ProgramFramework::Native => &["Cargo.toml"],
+            ProgramFramework::Foundry => &["foundry.toml"],
+            ProgramFramework::Hardhat => &["hardhat.config.js", "hardhat.config.ts"],
+        }
+    }
+
+    pub fn is_solidity(&self) -> bool {
+        matches!(self, ProgramFramework::Foundry | ProgramFramework::Hardhat)
+    }
+}
+
+#[derive(
+    Default,
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    strum_macros::EnumIter,
+    strum_macros::Display,
+    PartialOrd,
+    PartialEq,
+)]
+pub enum DiagramBackend {
+    #[default]
+    Miro,
+    JsonCanvas,
+}
+
+impl BatEnumerator for DiagramBackend {}
+
 impl BatConfig {
     pub fn new_with_prompt() -> BatConfigResult<Self> {
         let new = Self::create_bat_config_file()?;
@@ -219,82 +317,41 @@ impl BatConfig {
     }
 
     fn create_bat_config_file() -> Result<BatConfig, BatConfigError> {
-        let local_anchor_project_folders = WalkDir::new(".")
-            .into_iter()
-            .map(|f| f.unwrap())
-            .filter(|f| {
-                f.file_type().is_dir()
-                    && ![".", "target"]
-                        .iter()
-                        .any(|y| f.file_name().to_str().unwrap().contains(y))
-            })
-            .filter(|f| {
-                let path = f.path();
-                let dir = fs::read_dir(path).unwrap();
-                let file_names = dir
-                    .map(|f| f.unwrap().file_name().to_str().unwrap().to_string())
-                    .collect::<Vec<_>>();
-
-                file_names.contains(&"Anchor.toml".to_string())
-            })
-            .map(|f| f.path().to_str().unwrap().to_string())
-            .collect::<Vec<_>>();
-        if local_anchor_project_folders.is_empty() {
-            let message =
-                "No Anchor projects were found on the current working directory".to_string();
-            return Err(Report::new(BatConfigError).attach_printable(message));
-        }
+        // native programs don't have an Anchor.toml, so the Cargo projects are listed last
+        let (local_project_folders, program_framework) = [
+            ProgramFramework::Anchor,
+            ProgramFramework::Foundry,
+            ProgramFramework::Hardhat,
+            ProgramFramework::Native,
+        ]
+        .into_iter()
+        .map(|program_framework| {
+            (
+                Self::get_folders_containing_file(program_framework.get_project_file_names()),
+                program_framework,
+            )
+        })
+        .find(|(project_folders, _)| !project_folders.is_empty())
+        .ok_or(BatConfigError)
+        .into_report()
+        .attach_printable(
+            "No Anchor, Foundry, Hardhat or Cargo projects were found on the current working directory",
+        )?;
+        log::debug!("program_framework: {:#?}", program_framework);
         // Folder with the program to audit selection
         let prompt_text = "Select the folder with the program to audit";
-        let selection =
-            bat_dialoguer::select(prompt_text, local_anchor_project_folders.clone(), None)
-                .change_context(BatConfigError)?;
-        let selected_folder_path = &local_anchor_project_folders[selection];
-        let cargo_programs_files_info = WalkDir::new(selected_folder_path)
-            .into_iter()
-            .map(|f| f.unwrap())
-            .filter(|dir_entry| {
-                dir_entry
-                    .file_name()
-                    .to_str()
-                    .unwrap()
-                    .contains("Cargo.toml")
-                    && !dir_entry.path().to_str().unwrap().contains("target")
-            })
-            .collect::<Vec<_>>();
-
-        // Program to audit selection
-        let prompt_text = "Select the program to audit";
-        let cargo_programs_paths = cargo_programs_files_info
-            .iter()
-            .map(|f| {
-                f.path()
-                    .to_str()
-                    .unwrap()
-                    .trim_end_matches("/Cargo.toml")
-                    .to_string()
-            })
-            .collect::<Vec<_>>();
-        let selection = bat_dialoguer::select(prompt_text, cargo_programs_paths.clone(), None)
+        let selection = bat_dialoguer::select(prompt_text, local_project_folders.clone(), None)
             .change_context(BatConfigError)?;
-        let selected_program_path = &cargo_programs_paths[selection];
-        log::debug!("selected_program: {:#?}", selected_program_path);
-        let program_name = selected_program_path
-            .split('/')
-            .last()
-            .unwrap()
-            .to_string()
-            .replace('_', "-");
+        let selected_folder_path = &local_project_folders[selection];
+        let (program_name, program_lib_path) = if program_framework.is_solidity() {
+            Self::prompt_solidity_contract(selected_folder_path)?
+        } else {
+            Self::prompt_cargo_program(selected_folder_path)?
+        };
         log::debug!("program_name: {:#?}", program_name);
-        let program_lib_path = format!("{}/src/lib.rs", selected_program_path);
         log::debug!("program_lib_path: {:#?}", program_lib_path);
         let normalized_to_audit_program_lib_path = program_lib_path.replace("./", "../");
 
-        if !Path::new(&program_lib_path).is_file() {
-            return Err(Report::new(BatConfigError)
-                .attach_printable("lib.rs file not found in selected folder"));
-        }
-
         // Project name selection
         let mut project_name: String = program_name.replace('_', "-") + "-audit";
         let prompt_text = format!(
@@ -377,11 +434,136 @@ impl BatConfig {
             commit_hash_url,
             project_repository_url,
             program_lib_path: normalized_to_audit_program_lib_path,
+            entry_point_macros: vec![],
+            context_accounts_macros: vec![],
+            program_framework,
+            diagram_backend: Default::default(),
+            miro_layout: None,
+            miro_layout_file: "".to_string(),
+            git_forge: None,
         };
         bat_config.save().change_context(BatConfigError)?;
         Ok(bat_config)
     }
 
+    /// The Cargo program of the project folder, as (program name, lib.rs path)
+    fn prompt_cargo_program(selected_folder_path: &str) -> BatConfigResult<(String, String)> {
+        let cargo_programs_files_info = WalkDir::new(selected_folder_path)
+            .into_iter()
+            .map(|f| f.unwrap())
+            .filter(|dir_entry| {
+                dir_entry
+                    .file_name()
+                    .to_str()
+                    .unwrap()
+                    .contains("Cargo.toml")
+                    && !dir_entry.path().to_str().unwrap().contains("target")
+            })
+            .collect::<Vec<_>>();
+
+        // Program to audit selection
+        let prompt_text = "Select the program to audit";
+        let cargo_programs_paths = cargo_programs_files_info
+            .iter()
+            .map(|f| {
+                f.path()
+                    .to_str()
+                    .unwrap()
+                    .trim_end_matches("/Cargo.toml")
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        let selection = bat_dialoguer::select(prompt_text, cargo_programs_paths.clone(), None)
+            .change_context(BatConfigError)?;
+        let selected_program_path = &cargo_programs_paths[selection];
+        log::debug!("selected_program: {:#?}", selected_program_path);
+        let program_name = selected_program_path
+            .split('/')
+            .last()
+            .unwrap()
+            .to_string()
+            .replace('_', "-");
+        let program_lib_path = format!("{}/src/lib.rs", selected_program_path);
+
+        if !Path::new(&program_lib_path).is_file() {
+            return Err(Report::new(BatConfigError)
+                .attach_printable("lib.rs file not found in selected folder"));
+        }
+        Ok((program_name, program_lib_path))
+    }
+
+    /// The contract file of the Foundry or Hardhat project, as (program name, contract path)
+    fn prompt_solidity_contract(selected_folder_path: &str) -> BatConfigResult<(String, String)> {
+        let contract_paths = Self::get_solidity_contract_paths(selected_folder_path);
+        if contract_paths.is_empty() {
+            return Err(Report::new(BatConfigError).attach_printable(format!(
+                "No Solidity contracts were found on {}",
+                selected_folder_path
+            )));
+        }
+        let prompt_text = "Select the contract to audit";
+        let selection = bat_dialoguer::select(prompt_text, contract_paths.clone(), None)
+            .change_context(BatConfigError)?;
+        let program_lib_path = contract_paths[selection].clone();
+        let program_name = Path::new(&program_lib_path)
+            .file_stem()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_kebab_case();
+        Ok((program_name, program_lib_path))
+    }
+
+    /// Solidity files of the project, without the dependencies, tests and scripts
+    fn get_solidity_contract_paths(project_folder_path: &str) -> Vec<String> {
+        let mut contract_paths = WalkDir::new(project_folder_path)
+            .into_iter()
+            .filter_entry(|dir_entry| {
+                !SOLIDITY_NOT_CONTRACT_FOLDERS.contains(&dir_entry.file_name().to_str().unwrap())
+            })
+            .filter_map(|dir_entry| dir_entry.ok())
+            .map(|dir_entry| dir_entry.path().to_str().unwrap().to_string())
+            .filter(|file_path| {
+                file_path.ends_with(".sol")
+                    && !file_path.ends_with(".t.sol")
+                    && !file_path.ends_with(".s.sol")
+            })
+            .collect::<Vec<_>>();
+        contract_paths.sort();
+        contract_paths
+    }
+
+    fn get_folders_containing_file(file_names_to_find: &[&str]) -> Vec<String> {
+        WalkDir::new(".")
+            .into_iter()
+            .map(|f| f.unwrap())
+            .filter(|f| {
+                f.file_type().is_dir()
+                    && ![".", "target"]
+                        .iter()
+                        .any(|y| f.file_name().to_str().unwrap().contains(y))
+                    // dependencies ship their own foundry.toml and hardhat config
+                    && !f.path().components().any(|component| {
+                        ["node_modules", "lib"]
+                            .iter()
+                            .any(|dependencies_folder| component.as_os_str() == *dependencies_folder)
+                    })
+            })
+            .filter(|f| {
+                let path = f.path();
+                let dir = fs::read_dir(path).unwrap();
+                let file_names = dir
+                    .map(|f| f.unwrap().file_name().to_str().unwrap().to_string())
+                    .collect::<Vec<_>>();
+
+                file_names
+                    .iter()
+                    .any(|dir_file_name| file_names_to_find.contains(&dir_file_name.as_str()))
+            })
+            .map(|f| f.path().to_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    }
+
     fn normalize_miro_board_url(url_to_normalize: &str) -> Result<String, BatConfigError> {
         let url = normalizer::UrlNormalizer::new(url_to_normalize)
             .into_report()
@@ -455,3 +637,28 @@ impl BatConfig {
             .change_context(BatConfigError)
     }
 }
+
+#[test]
+fn test_get_solidity_contract_paths() {
+    use assert_fs::prelude::*;
+    let temp_dir = assert_fs::TempDir::new().unwrap();
+    for file_path in [
+        "foundry.toml",
+        "src/Vault.sol",
+        "src/interfaces/IVault.sol",
+        "test/Vault.t.sol",
+        "script/Deploy.s.sol",
+        "lib/forge-std/src/Test.sol",
+        "lib/forge-std/foundry.toml",
+    ] {
+        temp_dir.child(file_path).write_str("").unwrap();
+    }
+    let project_path = temp_dir.path().to_str().unwrap();
+    assert_eq!(
+        BatConfig::get_solidity_contract_paths(project_path),
+        vec![
+            format!("{}/src/Vault.sol", project_path),
+            format!("{}/src/interfaces/IVault.sol", project_path),
+        ]
+    );
+}
@@ -9,7 +9,9 @@ use inflector::Inflector;
 
 use crate::batbelt::metadata::BatMetadata;
 use crate::batbelt::path::BatFile;
+use crate::commands::candidate_commands::CandidateCommand;
 use crate::commands::miro_commands::MiroCommand;
+use crate::commands::questions_commands::QuestionsCommand;
 use crate::commands::sonar_commands::SonarCommand;
 use crate::commands::{BatCommandEnumerator, BatPackageJsonCommand, CommandResult};
 
@@ -54,9 +56,13 @@ struct Cli {
 )]
 enum BatCommands {
     /// Creates a Bat project
-    #[default]
-    New,
+    New {
+        /// Local bare repository to push main, develop and the auditor branches to, created if it doesn't exist
+        #[arg(long)]
+        bare_repository_path: Option<String>,
+    },
     /// Reload the Bat project files (ideal to resume work from git clone)
+    #[default]
     Reload,
     /// code-overhaul files management
     #[command(subcommand)]
@@ -94,6 +100,23 @@ enum BatCommands {
     /// Git actions to manage repository
     #[command(subcommand)]
     Repository(RepositoryCommand),
+    /// Generates the threat model from the BatMetadata, keeping the previous edits
+    ThreatModel,
+    /// Finding candidates management
+    #[command(subcommand)]
+    Candidate(CandidateCommand),
+    /// Open questions management
+    #[command(subcommand)]
+    Questions(QuestionsCommand),
+    /// Exports the audit timeline from the git history, as CSV by default
+    Log {
+        /// Exports the timeline as JSON
+        #[arg(long)]
+        json: bool,
+        /// File to write the timeline to, printed if not provided
+        #[arg(long)]
+        output_path: Option<String>,
+    },
     /// Cargo publish operations, available only for dev
     #[command(subcommand)]
     Package(PackageCommand),
@@ -105,7 +128,12 @@ impl BatCommands {
     pub async fn execute(&self) -> Result<(), CommandError> {
         self.validate_command()?;
         match self {
-            BatCommands::New => ProjectCommands::New.execute_command(),
+            BatCommands::New {
+                bare_repository_path,
+            } => ProjectCommands::New {
+                bare_repository_path: bare_repository_path.clone(),
+            }
+            .execute_command(),
             BatCommands::Reload => ProjectCommands::Reload.execute_command(),
             BatCommands::CodeOverhaul(command) => command.execute_command().await,
             BatCommands::Finding(FindingCommand::Create) => {
@@ -136,9 +164,25 @@ impl BatCommands {
             .execute_command(),
             // BatCommands::SonarSpecific(command) => command.execute_command(),
             BatCommands::Finding(FindingCommand::Reject) => commands::finding_commands::reject(),
+            BatCommands::Finding(FindingCommand::UpdateIds) => {
+                commands::finding_commands::update_finding_ids()
+            }
+            BatCommands::Finding(FindingCommand::ReviewFix) => {
+                commands::finding_commands::review_fix()
+            }
+            BatCommands::Finding(FindingCommand::AddEvidence) => {
+                commands::finding_commands::add_evidence()
+            }
+            BatCommands::Finding(FindingCommand::Poc) => commands::finding_commands::poc(),
             BatCommands::Miro(command) => command.execute_command().await,
             BatCommands::Tool(command) => command.execute_command(),
             BatCommands::Repository(command) => command.execute_command(),
+            BatCommands::Candidate(command) => command.execute_command(),
+            BatCommands::Questions(command) => command.execute_command(),
+            BatCommands::ThreatModel => commands::threat_model_commands::generate_threat_model(),
+            BatCommands::Log { json, output_path } => {
+                commands::log_commands::export_audit_log(*json, output_path.clone())
+            }
             // only for dev
             #[cfg(debug_assertions)]
             BatCommands::Package(PackageCommand::Format) => {
@@ -154,15 +198,16 @@ impl BatCommands {
 
     fn validate_command(&self) -> CommandResult<()> {
         let (check_metadata, check_branch) = match self {
-            BatCommands::New => {
+            BatCommands::New { .. } => {
                 return Ok(());
             }
             BatCommands::Reload => {
                 return Ok(());
             }
-            BatCommands::Package(_) => {
+            BatCommands::Package(_) | BatCommands::Log { .. } => {
                 return Ok(());
             }
+            BatCommands::ThreatModel => (true, true),
             BatCommands::Sonar { .. } => (
                 SonarCommand::Run {
                     skip_source_code: false,
@@ -201,6 +246,14 @@ impl BatCommands {
                 command.check_metadata_is_initialized(),
                 command.check_correct_branch(),
             ),
+            BatCommands::Candidate(command) => (
+                command.check_metadata_is_initialized(),
+                command.check_correct_branch(),
+            ),
+            BatCommands::Questions(command) => (
+                command.check_metadata_is_initialized(),
+                command.check_correct_branch(),
+            ),
         };
         if check_metadata {
             BatMetadata::read_metadata()
@@ -240,10 +293,16 @@ impl BatCommands {
                         command.to_string().to_kebab_case(),
                     ))
                 }
+                BatCommands::Candidate(_) => Some(CandidateCommand::get_bat_package_json_commands(
+                    command.to_string().to_kebab_case(),
+                )),
+                BatCommands::Questions(_) => Some(QuestionsCommand::get_bat_package_json_commands(
+                    command.to_string().to_kebab_case(),
+                )),
                 BatCommands::Sonar { .. } => Some(SonarCommand::get_bat_package_json_commands(
                     command.to_string().to_kebab_case(),
                 )),
-                BatCommands::Reload => Some(BatPackageJsonCommand {
+                BatCommands::Reload | BatCommands::ThreatModel => Some(BatPackageJsonCommand {
                     command_name: command.to_string().to_kebab_case(),
                     command_options: vec![],
                 }),
@@ -311,7 +370,7 @@ async fn run() -> CommandResult<()> {
     Suggestion::set_report();
     // env_logger selectively
     match cli.command {
-        BatCommands::Package(..) | BatCommands::New => {
+        BatCommands::Package(..) | BatCommands::New { .. } => {
             env_logger::init();
             Ok(())
         }
@@ -327,11 +386,17 @@ async fn main() -> CommandResult<()> {
 
     match run().await {
         Ok(_) => {
-            println!(
+            let success_message = format!(
                 "{} {} script successfully executed!",
                 "bat-cli".green(),
                 cli.command.get_pretty_command()?.green()
             );
+            // the audit log is printed to stdout when there's no output path, keep it clean
+            if matches!(cli.command, BatCommands::Log { .. }) {
+                eprintln!("{}", success_message);
+            } else {
+                println!("{}", success_message);
+            }
             Ok(())
         }
         Err(error) => {